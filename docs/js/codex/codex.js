@@ -6,10 +6,25 @@ const REGISTRY = {
     enemies: [
         { id: 'slime', path: 'data/enemies/slime' },
         { id: 'goblin_rogue', path: 'data/enemies/goblin_rogue' },
+        { id: 'skeleton_mage', path: 'data/enemies/skeleton_mage' },
+        { id: 'forest_wolf', path: 'data/enemies/forest_wolf' },
+        { id: 'giant_slime', path: 'data/enemies/giant_slime' },
+        { id: 'goblin_bandit', path: 'data/enemies/goblin_bandit' },
+        { id: 'dragon', path: 'data/enemies/dragon' },
+        { id: 'dragon_whelp', path: 'data/enemies/dragon_whelp' },
     ],
     cards: [
         { id: 'attack', path: 'data/cards/attack' },
         { id: 'defense', path: 'data/cards/defense' },
+        { id: 'critical_strike', path: 'data/cards/critical_strike' },
+        { id: 'heal', path: 'data/cards/heal' },
+        { id: 'dragon_breath', path: 'data/cards/dragon_breath' },
+        { id: 'armor_break', path: 'data/cards/armor_break' },
+        { id: 'poison_dagger', path: 'data/cards/poison_dagger' },
+        { id: 'fireball', path: 'data/cards/fireball' },
+        { id: 'rejuvenation', path: 'data/cards/rejuvenation' },
+        { id: 'whirlwind', path: 'data/cards/whirlwind' },
+        { id: 'battle_plan', path: 'data/cards/battle_plan' },
     ],
     skills: [
         { id: 'emergency_heal', path: 'data/skills/emergency_heal' },
@@ -108,6 +123,11 @@ function showDetail(info) {
         tr.innerHTML = `<td>${key}</td><td>${val}</td>`;
         dom.dStats.appendChild(tr);
     }
+    for (const [key, val] of Object.entries(info.upgrade || {})) {
+        const tr = document.createElement('tr');
+        tr.innerHTML = `<td>升级后${key}</td><td>${val}</td>`;
+        dom.dStats.appendChild(tr);
+    }
 
     // Skills
     const skills = info.skills || [];
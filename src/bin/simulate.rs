@@ -0,0 +1,81 @@
+use xiaoer_game::game::sim::{simulate, Policy, SimReport};
+
+const DEFAULT_RUNS: u32 = 1_000;
+
+struct Options {
+    runs: u32,
+    seed: u64,
+    policies: Vec<Policy>,
+}
+
+fn main() {
+    let options = match parse_options(std::env::args().skip(1)) {
+        Ok(options) => options,
+        Err(msg) => {
+            eprintln!("{msg}");
+            eprintln!(
+                "用法：simulate [--runs <数量>] [--seed <数字>] [--policy <aggressive|defensive|all>]"
+            );
+            std::process::exit(2);
+        }
+    };
+
+    for policy in options.policies {
+        let report = simulate(options.runs, options.seed, policy);
+        print_report(policy, options.seed, &report);
+    }
+}
+
+fn parse_options(mut args: impl Iterator<Item = String>) -> Result<Options, String> {
+    let mut options = Options {
+        runs: DEFAULT_RUNS,
+        seed: 0,
+        policies: Policy::ALL.to_vec(),
+    };
+    while let Some(arg) = args.next() {
+        let mut value = |flag: &str| args.next().ok_or(format!("{flag} 需要一个参数"));
+        match arg.as_str() {
+            "--runs" => {
+                let v = value("--runs")?;
+                options.runs = v.parse().map_err(|_| format!("无效的对局数：{v}"))?;
+            }
+            "--seed" => {
+                let v = value("--seed")?;
+                options.seed = v.parse().map_err(|_| format!("无效的种子：{v}"))?;
+            }
+            "--policy" => {
+                let v = value("--policy")?;
+                options.policies = if v == "all" {
+                    Policy::ALL.to_vec()
+                } else {
+                    vec![Policy::from_name(&v).ok_or(format!("未知策略：{v}"))?]
+                };
+            }
+            _ => return Err(format!("未知参数：{arg}")),
+        }
+    }
+    Ok(options)
+}
+
+fn print_report(policy: Policy, seed: u64, report: &SimReport) {
+    println!("═══ 策略：{} ═══", policy.name());
+    println!("对局数：{}（种子 {} 起）", report.runs, seed);
+    println!(
+        "胜率：{:.1}%（{}/{}）",
+        report.win_rate() * 100.0,
+        report.wins,
+        report.runs
+    );
+    println!("平均金币收入：{:.2}", report.average_gold());
+    println!("通关时平均剩余 HP：{:.2}", report.average_hp_remaining());
+    println!("各敌人平均回合数：");
+    for (name, stats) in &report.enemies {
+        println!(
+            "  {}：{:.2} 回合（{} 场）",
+            name,
+            stats.average_rounds(),
+            stats.battles
+        );
+    }
+    println!();
+}
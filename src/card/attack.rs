@@ -1,23 +0,0 @@
-use super::{Card, CardEffect, DEFAULT_CARD_COOLDOWN_MS};
-
-/// Creates the basic attack card (deals 1 damage).
-pub fn create_attack_card() -> Card {
-    Card::new(
-        "攻击",
-        "造成 1 点伤害",
-        CardEffect::Damage(1),
-        DEFAULT_CARD_COOLDOWN_MS,
-    )
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn attack_card_deals_one_damage() {
-        let card = create_attack_card();
-        assert_eq!(card.name, "攻击");
-        assert!(matches!(card.effect, CardEffect::Damage(1)));
-    }
-}
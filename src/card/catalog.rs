@@ -0,0 +1,437 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use rand::Rng;
+
+use super::{
+    Card, CardEffect, CardRarity, CardType, DEFAULT_CARD_COOLDOWN_MS, DEFAULT_CARD_ENERGY_COST,
+};
+use crate::data::{self, CatalogError, Info, Stats};
+use crate::mechanics::buff::BuffRegistry;
+use crate::mechanics::effect::EffectTarget;
+use crate::mechanics::pick::pick_distinct;
+
+/// Card ids the game rules refer to directly, those of the starting deck; every catalog
+/// must define them.
+pub const CORE_CARDS: [&str; 2] = ["attack", "defense"];
+
+/// Playable cards keyed by their codex id (the directory name under `cards/`).
+#[derive(Debug, Clone, Default)]
+pub struct CardCatalog {
+    cards: BTreeMap<String, Card>,
+}
+
+impl CardCatalog {
+    /// Loads every `<id>/info.json` under `cards_dir`.
+    pub fn from_dir(cards_dir: &Path) -> Result<Self, CatalogError> {
+        Self::from_entries(data::read_entries(cards_dir)?)
+    }
+
+    /// Builds the catalog from parsed `(id, info.json)` entries.
+    pub(crate) fn from_entries(entries: Vec<(String, Info)>) -> Result<Self, CatalogError> {
+        let mut cards = BTreeMap::new();
+        for (id, info) in entries {
+            cards.insert(id, parse_card(&info)?);
+        }
+
+        let catalog = Self { cards };
+        if let Some(id) = CORE_CARDS.into_iter().find(|id| catalog.get(id).is_none()) {
+            return Err(CatalogError::MissingEntry(id.to_string()));
+        }
+        Ok(catalog)
+    }
+
+    pub fn get(&self, id: &str) -> Option<&Card> {
+        self.cards.get(id)
+    }
+
+    /// A fresh copy of the card with this id, ready to put in a hand.
+    ///
+    /// Panics on an unknown id; only use it for [`CORE_CARDS`] or ids read from this catalog.
+    pub fn card(&self, id: &str) -> Card {
+        self.get(id)
+            .cloned()
+            .unwrap_or_else(|| panic!("卡牌目录中没有 {id}"))
+    }
+
+    pub fn ids(&self) -> impl Iterator<Item = &str> {
+        self.cards.keys().map(String::as_str)
+    }
+
+    /// Draws up to `count` different card ids for a reward at `stage`, weighted by rarity.
+    /// With `min_rarity`, at least one of them is that rare, if the catalog has such a card.
+    pub fn pick_reward(
+        &self,
+        count: usize,
+        stage: u32,
+        min_rarity: Option<CardRarity>,
+        rng: &mut impl Rng,
+    ) -> Vec<&str> {
+        let pool: Vec<(&str, CardRarity)> = self
+            .cards
+            .iter()
+            .map(|(id, card)| (id.as_str(), card.rarity))
+            .collect();
+        pick_distinct(
+            pool,
+            count,
+            |(_, rarity)| rarity.weight(stage),
+            |(_, rarity)| *rarity,
+            min_rarity,
+            rng,
+        )
+        .into_iter()
+        .map(|(id, _)| id)
+        .collect()
+    }
+}
+
+/// Builds a card from the codex stats table.
+///
+/// `类型` picks the effect (伤害/群体伤害/防御/治疗/抽牌/状态) and its amount key
+/// (伤害值/护盾值/恢复值/抽牌数).
+/// `施加状态` names a buff to apply as well, with optional `状态层数` (default 1),
+/// `状态回合` (default 0, permanent) and `状态目标` (自身/敌方; buffs default to 自身).
+/// `冷却` ("5 秒"), `能量消耗` (default 1), `卡牌类型` (物理/法术), `法力消耗`,
+/// `消耗` (是/否, default 否) and `稀有度` (普通/稀有/史诗/首领, default 普通) are optional.
+///
+/// An `upgrade` table overrides some of the stats for the upgraded card, named with a
+/// trailing "+".
+fn parse_card(info: &Info) -> Result<Card, CatalogError> {
+    let mut card = parse_stats(info)?;
+    if !info.upgrade.is_empty() {
+        let mut stats = info.stats.clone();
+        stats.extend(info.upgrade.clone());
+        let upgraded_info = Info {
+            name: format!("{}+", info.name),
+            stats,
+            skills: Vec::new(),
+            upgrade: BTreeMap::new(),
+        };
+        let mut upgraded = parse_stats(&upgraded_info)?;
+        upgraded.upgraded = true;
+        card.set_upgrade(upgraded);
+    }
+    Ok(card)
+}
+
+fn parse_stats(info: &Info) -> Result<Card, CatalogError> {
+    let stats = info.stats();
+    let kind = stats.require("类型")?;
+    let base = match kind.as_str() {
+        Some("伤害") => Some(CardEffect::Damage(stats.count("伤害值")?)),
+        Some("群体伤害") => Some(CardEffect::DamageAll(stats.count("伤害值")?)),
+        Some("防御") => Some(CardEffect::Shield(stats.count("护盾值")?)),
+        Some("治疗") => Some(CardEffect::Heal(stats.count("恢复值")?)),
+        Some("抽牌") => Some(CardEffect::DrawCards(stats.count("抽牌数")? as u32)),
+        Some("状态") => None,
+        _ => return Err(stats.invalid("类型", kind)),
+    };
+    let effect = match (base, parse_status(&stats)?) {
+        (Some(base), None) => base,
+        (Some(base), Some(status)) => CardEffect::Composite(vec![base, status]),
+        (None, Some(status)) => status,
+        (None, None) => {
+            return Err(CatalogError::MissingStat {
+                entry: info.name.clone(),
+                key: "施加状态",
+            })
+        }
+    };
+    let exhaust = stats.flag_or("消耗", false)?;
+    let mut description = effect.describe();
+    if exhaust {
+        description.push_str("。消耗");
+    }
+
+    let cooldown_ms = stats.millis_or("冷却", DEFAULT_CARD_COOLDOWN_MS)?;
+
+    let card_type = match stats.get("卡牌类型") {
+        None => CardType::Physical,
+        Some(value) => match value.as_str() {
+            Some("物理") => CardType::Physical,
+            Some("法术") => CardType::Spell,
+            _ => return Err(stats.invalid("卡牌类型", value)),
+        },
+    };
+
+    let mana_cost = stats.count_or("法力消耗", 0)?;
+    if mana_cost > 0 && card_type != CardType::Spell {
+        return Err(stats.invalid("法力消耗", stats.require("法力消耗")?));
+    }
+
+    let name = info.name.as_str();
+    let mut card = match card_type {
+        CardType::Physical => Card::new(name, &description, effect, cooldown_ms),
+        CardType::Spell => Card::new_spell(name, &description, effect, cooldown_ms, mana_cost),
+    };
+    card.energy_cost = stats.count_or("能量消耗", DEFAULT_CARD_ENERGY_COST)?;
+    card.exhaust = exhaust;
+    card.rarity = match stats.get("稀有度") {
+        None => CardRarity::Common,
+        Some(value) => value
+            .as_str()
+            .and_then(CardRarity::from_label)
+            .ok_or_else(|| stats.invalid("稀有度", value))?,
+    };
+    Ok(card)
+}
+
+/// The `施加状态` part of a card, if any.
+fn parse_status(stats: &Stats<'_>) -> Result<Option<CardEffect>, CatalogError> {
+    let Some(value) = stats.get("施加状态") else {
+        return Ok(None);
+    };
+    let registry = BuffRegistry::builtin();
+    let def = value
+        .as_str()
+        .and_then(|name| registry.get(name).or_else(|| registry.by_name(name)))
+        .ok_or_else(|| stats.invalid("施加状态", value))?;
+
+    let target = match stats.get("状态目标") {
+        None => EffectTarget::default_for(def.kind),
+        Some(value) => match value.as_str() {
+            Some("自身") => EffectTarget::Caster,
+            Some("敌方") => EffectTarget::Opponent,
+            _ => return Err(stats.invalid("状态目标", value)),
+        },
+    };
+    Ok(Some(CardEffect::ApplyBuff {
+        target,
+        id: def.id.to_string(),
+        stacks: stats.count_or("状态层数", 1)? as u32,
+        duration: stats.count_or("状态回合", 0)? as u32,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::GameData;
+    use std::fs;
+
+    fn parse(json: &str) -> Result<Card, CatalogError> {
+        parse_card(&serde_json::from_str(json).unwrap())
+    }
+
+    #[test]
+    fn builtin_catalog_has_core_cards() {
+        let catalog = &GameData::builtin().cards;
+        let attack = catalog.card("attack");
+        assert_eq!(attack.name, "攻击");
+        assert_eq!(attack.description, "造成 1 点伤害");
+        assert!(matches!(attack.effect, CardEffect::Damage(1)));
+        assert_eq!(attack.cooldown_ms(), DEFAULT_CARD_COOLDOWN_MS);
+
+        let defense = catalog.card("defense");
+        assert_eq!(defense.name, "防御");
+        assert!(matches!(defense.effect, CardEffect::Shield(1)));
+
+        let critical = catalog.card("critical_strike");
+        assert_eq!(critical.name, "暴击");
+        assert!(matches!(critical.effect, CardEffect::Damage(2)));
+        assert_eq!(critical.cooldown_ms(), 5_000);
+        assert_eq!(critical.energy_cost, 2);
+        assert_eq!(defense.energy_cost, DEFAULT_CARD_ENERGY_COST);
+
+        let heal = catalog.card("heal");
+        assert_eq!(heal.name, "治愈");
+        assert!(matches!(heal.effect, CardEffect::Heal(1)));
+        assert_eq!(heal.cooldown_ms(), 4_000);
+
+        let whirlwind = catalog.card("whirlwind");
+        assert_eq!(whirlwind.effect, CardEffect::DamageAll(1));
+        assert_eq!(whirlwind.description, "对所有敌人造成 1 点伤害");
+        assert_eq!(whirlwind.rarity, CardRarity::Rare);
+    }
+
+    #[test]
+    fn upgrades_override_stats_and_keep_the_rest() {
+        let card = parse(
+            r#"{ "name": "攻击", "stats": { "类型": "伤害", "伤害值": 1, "冷却": "3 秒",
+                 "稀有度": "稀有" }, "upgrade": { "伤害值": 2 } }"#,
+        )
+        .unwrap();
+        assert_eq!(card.rarity, CardRarity::Uncommon);
+        assert!(!card.upgraded);
+        let plus = card.upgraded_version().unwrap();
+        assert_eq!(plus.name, "攻击+");
+        assert_eq!(plus.effect, CardEffect::Damage(2));
+        assert_eq!(plus.cooldown_ms(), 3_000);
+        assert_eq!(plus.rarity, CardRarity::Uncommon);
+        assert!(plus.upgraded && plus.upgraded_version().is_none());
+
+        let err = parse(
+            r#"{ "name": "攻击", "stats": { "类型": "伤害", "伤害值": 1 },
+                 "upgrade": { "伤害值": -2 } }"#,
+        )
+        .unwrap_err();
+        assert!(matches!(err, CatalogError::InvalidStat { entry, .. } if entry == "攻击+"));
+
+        let err = parse(
+            r#"{ "name": "坏牌", "stats": { "类型": "伤害", "伤害值": 1, "稀有度": "神话" } }"#,
+        )
+        .unwrap_err();
+        assert!(matches!(
+            err,
+            CatalogError::InvalidStat {
+                key: "稀有度", ..
+            }
+        ));
+    }
+
+    #[test]
+    fn every_player_card_can_be_upgraded() {
+        let catalog = &GameData::builtin().cards;
+        for id in catalog.ids() {
+            let card = catalog.card(id);
+            assert_eq!(
+                card.upgraded_version().is_some(),
+                card.rarity != CardRarity::Boss,
+                "{id}"
+            );
+        }
+        assert_eq!(catalog.card("dragon_breath").rarity, CardRarity::Boss);
+    }
+
+    #[test]
+    fn rewards_are_distinct_player_cards_with_a_rare_one_when_asked() {
+        use rand::rngs::StdRng;
+        use rand::SeedableRng;
+
+        let catalog = &GameData::builtin().cards;
+        for seed in 0..20 {
+            let mut rng = StdRng::seed_from_u64(seed);
+            let picked = catalog.pick_reward(3, 1, Some(CardRarity::Uncommon), &mut rng);
+            assert_eq!(picked.len(), 3);
+            assert!(!picked.contains(&"dragon_breath"));
+            assert!(picked[1..].iter().all(|id| *id != picked[0]) && picked[1] != picked[2]);
+            assert!(picked
+                .iter()
+                .any(|id| catalog.card(id).rarity >= CardRarity::Uncommon));
+        }
+    }
+
+    #[test]
+    fn exhausting_cards_say_so() {
+        let plan = GameData::builtin().cards.card("battle_plan");
+        assert_eq!(plan.effect, CardEffect::DrawCards(2));
+        assert!(plan.exhaust);
+        assert_eq!(plan.description, "抽 2 张牌。消耗");
+        assert!(!GameData::builtin().cards.card("attack").exhaust);
+    }
+
+    #[test]
+    fn status_cards_apply_buffs() {
+        let armor_break = GameData::builtin().cards.card("armor_break");
+        assert_eq!(
+            armor_break.description,
+            "造成 1 点伤害并对敌方施加 2 层脆弱（3 回合）"
+        );
+        assert_eq!(armor_break.effect.damage(), 1);
+
+        let card = parse(
+            r#"{ "name": "怒吼", "stats": { "类型": "状态", "施加状态": "strength",
+                 "状态层数": 2, "状态回合": 2 } }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            card.effect,
+            CardEffect::ApplyBuff {
+                target: EffectTarget::Caster,
+                id: "strength".to_string(),
+                stacks: 2,
+                duration: 2,
+            }
+        );
+
+        let err = parse(r#"{ "name": "坏牌", "stats": { "类型": "状态", "施加状态": "诅咒" } }"#)
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            CatalogError::InvalidStat {
+                key: "施加状态",
+                ..
+            }
+        ));
+        let err = parse(r#"{ "name": "坏牌", "stats": { "类型": "状态" } }"#).unwrap_err();
+        assert!(matches!(
+            err,
+            CatalogError::MissingStat {
+                key: "施加状态",
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn spell_cards_carry_mana_cost() {
+        let card = parse(
+            r#"{ "name": "火球", "stats": { "类型": "伤害", "伤害值": 3,
+                 "冷却": 6, "卡牌类型": "法术", "法力消耗": 30 } }"#,
+        )
+        .unwrap();
+        assert!(card.is_spell());
+        assert_eq!(card.mana_cost, 30);
+        assert_eq!(card.cooldown_ms(), 6_000);
+    }
+
+    #[test]
+    fn invalid_stats_are_reported() {
+        let err = parse(r#"{ "name": "坏牌", "stats": { "类型": "伤害" } }"#).unwrap_err();
+        assert!(matches!(
+            err,
+            CatalogError::MissingStat {
+                key: "伤害值", ..
+            }
+        ));
+
+        let err = parse(r#"{ "name": "坏牌", "stats": { "类型": "诅咒" } }"#).unwrap_err();
+        assert!(matches!(err, CatalogError::InvalidStat { key: "类型", .. }));
+
+        let err =
+            parse(r#"{ "name": "坏牌", "stats": { "类型": "治疗", "恢复值": -1 } }"#).unwrap_err();
+        assert!(matches!(
+            err,
+            CatalogError::InvalidStat {
+                key: "恢复值", ..
+            }
+        ));
+
+        let err = parse(
+            r#"{ "name": "坏牌", "stats": { "类型": "伤害", "伤害值": 1, "法力消耗": 10 } }"#,
+        )
+        .unwrap_err();
+        assert!(matches!(
+            err,
+            CatalogError::InvalidStat {
+                key: "法力消耗",
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn missing_core_card_is_an_error() {
+        let dir = std::env::temp_dir().join(format!("xiaoer_cards_{}", std::process::id()));
+        fs::create_dir_all(dir.join("attack")).unwrap();
+        fs::write(
+            dir.join("attack/info.json"),
+            r#"{ "name": "攻击", "stats": { "类型": "伤害", "伤害值": 1 } }"#,
+        )
+        .unwrap();
+        let missing = CardCatalog::from_dir(&dir);
+
+        fs::create_dir_all(dir.join("defense")).unwrap();
+        fs::write(
+            dir.join("defense/info.json"),
+            r#"{ "name": "防御", "stats": { "类型": "防御", "护盾值": 1 } }"#,
+        )
+        .unwrap();
+        let starting_deck_only = CardCatalog::from_dir(&dir);
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert!(matches!(missing, Err(CatalogError::MissingEntry(id)) if id == "defense"));
+        assert_eq!(starting_deck_only.unwrap().ids().count(), 2);
+    }
+}
@@ -0,0 +1,218 @@
+use rand::seq::SliceRandom;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use super::Card;
+
+/// Most cards a hand can hold; draws beyond it stay in the draw pile.
+pub const MAX_HAND_SIZE: usize = 7;
+
+/// The cards a player owns, plus the piles they move through during a battle.
+///
+/// Owned cards only change between battles. A battle deals copies of them into the
+/// draw pile; played cards go to the discard pile, or to the exhaust pile for the rest
+/// of the battle. An empty draw pile is refilled by shuffling the discard pile back in.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Deck {
+    /// Every owned card, in the order gained.
+    cards: Vec<Card>,
+    /// Drawn from the end.
+    draw_pile: Vec<Card>,
+    hand: Vec<Card>,
+    discard_pile: Vec<Card>,
+    exhaust_pile: Vec<Card>,
+}
+
+/// What a call to [`Deck::draw`] did.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Drawn {
+    pub count: usize,
+    /// Whether the discard pile was shuffled back into the draw pile on the way.
+    pub reshuffled: bool,
+}
+
+impl Deck {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds an owned card. It joins the piles from the next battle on.
+    pub fn add(&mut self, card: Card) {
+        self.cards.push(card);
+    }
+
+    pub fn cards(&self) -> &[Card] {
+        &self.cards
+    }
+
+    /// Removes owned card `index` for good.
+    pub fn remove(&mut self, index: usize) -> Option<Card> {
+        (index < self.cards.len()).then(|| self.cards.remove(index))
+    }
+
+    /// Upgrades owned card `index`. Returns false if there is no such card or it has no
+    /// upgrade left.
+    pub fn upgrade(&mut self, index: usize) -> bool {
+        self.cards.get_mut(index).is_some_and(Card::upgrade)
+    }
+
+    pub fn hand(&self) -> &[Card] {
+        &self.hand
+    }
+
+    pub fn hand_mut(&mut self) -> &mut [Card] {
+        &mut self.hand
+    }
+
+    pub fn draw_pile(&self) -> &[Card] {
+        &self.draw_pile
+    }
+
+    pub fn discard_pile(&self) -> &[Card] {
+        &self.discard_pile
+    }
+
+    pub fn exhaust_pile(&self) -> &[Card] {
+        &self.exhaust_pile
+    }
+
+    /// Sets the piles up for a new battle: every owned card shuffled into the draw pile.
+    pub fn start_battle(&mut self, rng: &mut impl Rng) {
+        self.hand.clear();
+        self.discard_pile.clear();
+        self.exhaust_pile.clear();
+        self.draw_pile = self.cards.clone();
+        self.draw_pile.shuffle(rng);
+    }
+
+    /// Draws up to `count` cards, stopping early once the hand is full or both the draw
+    /// and discard piles are empty.
+    pub fn draw(&mut self, count: usize, rng: &mut impl Rng) -> Drawn {
+        let mut drawn = Drawn::default();
+        while drawn.count < count && self.hand.len() < MAX_HAND_SIZE {
+            if self.draw_pile.is_empty() {
+                if self.discard_pile.is_empty() {
+                    break;
+                }
+                self.draw_pile.append(&mut self.discard_pile);
+                self.draw_pile.shuffle(rng);
+                drawn.reshuffled = true;
+            }
+            if let Some(card) = self.draw_pile.pop() {
+                self.hand.push(card);
+                drawn.count += 1;
+            }
+        }
+        drawn
+    }
+
+    /// Puts a card straight into the hand for this battle only.
+    pub fn add_to_hand(&mut self, card: Card) {
+        self.hand.push(card);
+    }
+
+    /// Takes the card at `index` out of the hand, e.g. to play it.
+    pub fn take_from_hand(&mut self, index: usize) -> Option<Card> {
+        (index < self.hand.len()).then(|| self.hand.remove(index))
+    }
+
+    /// Puts a played card where it belongs: the exhaust pile if it exhausts, otherwise
+    /// the discard pile.
+    pub fn put_away(&mut self, card: Card) {
+        if card.exhaust {
+            self.exhaust_pile.push(card);
+        } else {
+            self.discard_pile.push(card);
+        }
+    }
+
+    /// Moves the rest of the hand to the discard pile at the end of a round.
+    pub fn discard_hand(&mut self) {
+        self.discard_pile.append(&mut self.hand);
+    }
+
+    /// Every card still in play this battle: draw pile, hand and discard pile.
+    pub fn battle_cards_mut(&mut self) -> impl Iterator<Item = &mut Card> {
+        self.draw_pile
+            .iter_mut()
+            .chain(&mut self.hand)
+            .chain(&mut self.discard_pile)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::card::CardEffect;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    fn deck_of(names: &[&str]) -> Deck {
+        let mut deck = Deck::new();
+        for name in names {
+            deck.add(Card::new(name, "", CardEffect::Damage(1), 0));
+        }
+        deck
+    }
+
+    fn names(cards: &[Card]) -> Vec<&str> {
+        cards.iter().map(|c| c.name.as_str()).collect()
+    }
+
+    #[test]
+    fn same_seed_same_shuffle() {
+        let mut a = deck_of(&["a", "b", "c", "d", "e"]);
+        let mut b = a.clone();
+        a.start_battle(&mut StdRng::seed_from_u64(4));
+        b.start_battle(&mut StdRng::seed_from_u64(4));
+        assert_eq!(names(a.draw_pile()), names(b.draw_pile()));
+        assert_eq!(a.draw_pile().len(), 5);
+    }
+
+    #[test]
+    fn empty_draw_pile_is_refilled_from_the_discard_pile() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let mut deck = deck_of(&["a", "b", "c"]);
+        deck.start_battle(&mut rng);
+        assert_eq!(
+            deck.draw(2, &mut rng),
+            Drawn {
+                count: 2,
+                reshuffled: false
+            }
+        );
+        deck.discard_hand();
+        assert_eq!(deck.discard_pile().len(), 2);
+
+        let drawn = deck.draw(3, &mut rng);
+        assert_eq!((drawn.count, drawn.reshuffled), (3, true));
+        assert!(deck.draw_pile().is_empty() && deck.discard_pile().is_empty());
+        assert_eq!(deck.draw(1, &mut rng).count, 0, "所有牌都在手上");
+    }
+
+    #[test]
+    fn exhausted_cards_sit_out_until_the_next_battle() {
+        let mut rng = StdRng::seed_from_u64(2);
+        let mut deck = deck_of(&["a"]);
+        deck.cards[0].exhaust = true;
+        deck.start_battle(&mut rng);
+        deck.draw(1, &mut rng);
+        let card = deck.take_from_hand(0).unwrap();
+        deck.put_away(card);
+        assert_eq!(deck.exhaust_pile().len(), 1);
+        assert_eq!(deck.draw(1, &mut rng).count, 0);
+
+        deck.start_battle(&mut rng);
+        assert!(deck.exhaust_pile().is_empty());
+        assert_eq!(deck.draw_pile().len(), 1);
+    }
+
+    #[test]
+    fn hand_stops_growing_at_the_limit() {
+        let mut rng = StdRng::seed_from_u64(3);
+        let mut deck = deck_of(&["a"; 10]);
+        deck.start_battle(&mut rng);
+        assert_eq!(deck.draw(10, &mut rng).count, MAX_HAND_SIZE);
+        assert_eq!(deck.draw_pile().len(), 10 - MAX_HAND_SIZE);
+    }
+}
@@ -1,40 +1,107 @@
-pub mod attack;
-pub mod critical_strike;
-pub mod defense;
-pub mod heal;
+pub mod catalog;
+pub mod deck;
+
+pub use catalog::CardCatalog;
+pub use deck::Deck;
+
+use serde::{Deserialize, Serialize};
+
+use crate::mechanics::effect::Effect;
 
 pub const DEFAULT_CARD_COOLDOWN_MS: u64 = 3_000;
+pub const DEFAULT_CARD_ENERGY_COST: i32 = 1;
 
 /// The effect a card produces when played.
-#[derive(Debug, Clone)]
-pub enum CardEffect {
-    Damage(i32),
-    Shield(i32),
-    Heal(i32),
-}
+pub type CardEffect = Effect;
 
-/// Card category — physical cards use energy, spell cards use mana.
-#[allow(dead_code)]
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Card category — every card uses energy, spell cards use mana as well.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum CardType {
     Physical,
     Spell,
 }
 
+/// How rare a card is; rarer cards cost more and show up less often as rewards.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+pub enum CardRarity {
+    #[default]
+    Common,
+    Uncommon,
+    Rare,
+    /// Enemy-only cards; never sold or offered to the player.
+    Boss,
+}
+
+impl CardRarity {
+    pub fn label(self) -> &'static str {
+        match self {
+            CardRarity::Common => "普通",
+            CardRarity::Uncommon => "稀有",
+            CardRarity::Rare => "史诗",
+            CardRarity::Boss => "首领",
+        }
+    }
+
+    /// The rarity written as `label` in a codex entry, e.g. "稀有".
+    pub fn from_label(label: &str) -> Option<Self> {
+        [
+            CardRarity::Common,
+            CardRarity::Uncommon,
+            CardRarity::Rare,
+            CardRarity::Boss,
+        ]
+        .into_iter()
+        .find(|rarity| rarity.label() == label)
+    }
+
+    /// Shop price before discounts; `None` for cards that are never sold.
+    pub fn price(self) -> Option<i32> {
+        match self {
+            CardRarity::Common => Some(3),
+            CardRarity::Uncommon => Some(5),
+            CardRarity::Rare => Some(7),
+            CardRarity::Boss => None,
+        }
+    }
+
+    /// Relative chance of being drawn for a reward at `stage`; rarer cards turn up more
+    /// often the deeper the run goes.
+    pub fn weight(self, stage: u32) -> u32 {
+        match self {
+            CardRarity::Common => 60,
+            CardRarity::Uncommon => 30 + 5 * stage,
+            CardRarity::Rare => 10 + 3 * stage,
+            CardRarity::Boss => 0,
+        }
+    }
+}
+
 /// A playable card in the game.
-#[allow(dead_code)]
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Card {
     pub name: String,
     pub description: String,
     pub effect: CardEffect,
     pub card_type: CardType,
+    pub energy_cost: i32,
     pub mana_cost: i32,
+    /// Exhausted cards leave play for the rest of the battle once played.
+    #[serde(default)]
+    pub exhaust: bool,
+    #[serde(default)]
+    pub rarity: CardRarity,
+    /// Whether this is already the upgraded version, e.g. "攻击+".
+    #[serde(default)]
+    pub upgraded: bool,
+    /// What the card becomes when upgraded; `None` if it cannot be.
+    #[serde(default)]
+    upgrade: Option<Box<Card>>,
     cooldown_ms: u64,
     remaining_cooldown_ms: u64,
 }
 
-#[allow(dead_code)]
 impl Card {
     pub fn new(name: &str, description: &str, effect: CardEffect, cooldown_ms: u64) -> Self {
         Self {
@@ -42,7 +109,12 @@ impl Card {
             description: description.to_string(),
             effect,
             card_type: CardType::Physical,
+            energy_cost: DEFAULT_CARD_ENERGY_COST,
             mana_cost: 0,
+            exhaust: false,
+            rarity: CardRarity::Common,
+            upgraded: false,
+            upgrade: None,
             cooldown_ms,
             remaining_cooldown_ms: 0,
         }
@@ -60,7 +132,12 @@ impl Card {
             description: description.to_string(),
             effect,
             card_type: CardType::Spell,
+            energy_cost: DEFAULT_CARD_ENERGY_COST,
             mana_cost,
+            exhaust: false,
+            rarity: CardRarity::Common,
+            upgraded: false,
+            upgrade: None,
             cooldown_ms,
             remaining_cooldown_ms: 0,
         }
@@ -70,10 +147,35 @@ impl Card {
         self.card_type == CardType::Spell
     }
 
+    /// What the card becomes when upgraded, if it can be.
+    pub fn upgraded_version(&self) -> Option<&Card> {
+        self.upgrade.as_deref()
+    }
+
+    pub fn set_upgrade(&mut self, upgraded: Card) {
+        self.upgrade = Some(Box::new(upgraded));
+    }
+
+    /// Turns the card into its upgraded version, keeping the cooldown it is on.
+    /// Returns false if it has none.
+    pub fn upgrade(&mut self) -> bool {
+        let Some(upgraded) = self.upgrade.take() else {
+            return false;
+        };
+        let remaining_cooldown_ms = self.remaining_cooldown_ms;
+        *self = *upgraded;
+        self.remaining_cooldown_ms = remaining_cooldown_ms;
+        true
+    }
+
     pub fn is_ready(&self) -> bool {
         self.remaining_cooldown_ms == 0
     }
 
+    pub fn cooldown_ms(&self) -> u64 {
+        self.cooldown_ms
+    }
+
     pub fn trigger_cooldown(&mut self) {
         self.remaining_cooldown_ms = self.cooldown_ms;
     }
@@ -134,6 +236,34 @@ mod tests {
         assert_eq!(c.remaining_cooldown_ms(), 0);
     }
 
+    #[test]
+    fn upgrading_swaps_in_the_better_card_once() {
+        let mut c = Card::new("攻击", "造成 1 点伤害", CardEffect::Damage(1), 3_000);
+        let mut plus = Card::new("攻击+", "造成 2 点伤害", CardEffect::Damage(2), 3_000);
+        plus.upgraded = true;
+        c.set_upgrade(plus);
+        c.set_initial_cooldown_ms(1_000);
+
+        assert_eq!(c.upgraded_version().unwrap().name, "攻击+");
+        assert!(c.upgrade());
+        assert_eq!((c.name.as_str(), c.effect.damage()), ("攻击+", 2));
+        assert!(c.upgraded);
+        assert_eq!(c.remaining_cooldown_ms(), 1_000);
+        assert!(!c.upgrade());
+    }
+
+    #[test]
+    fn rarity_sets_price_and_weight() {
+        assert_eq!(CardRarity::from_label("稀有"), Some(CardRarity::Uncommon));
+        assert_eq!(CardRarity::from_label("传说"), None);
+        assert!(CardRarity::Common.price() < CardRarity::Rare.price());
+        assert!(CardRarity::Common.weight(1) > CardRarity::Rare.weight(1));
+        assert!(CardRarity::Uncommon.weight(6) > CardRarity::Uncommon.weight(1));
+        assert_eq!(CardRarity::Common.weight(6), CardRarity::Common.weight(1));
+        assert_eq!(CardRarity::Boss.price(), None);
+        assert_eq!(CardRarity::Boss.weight(6), 0);
+    }
+
     #[test]
     fn reduce_cooldown_saturates() {
         let mut c = Card::new("攻击", "造成 1 点伤害", CardEffect::Damage(1), 3_000);
@@ -1,31 +1,22 @@
-use crate::card::Card;
+use serde::{Deserialize, Serialize};
+
+use crate::card::{Card, Deck};
+use crate::mechanics::buff::BuffManager;
 use crate::mechanics::combat::Combatant;
+use crate::mechanics::passive::{self, PassiveRegistry};
+use crate::relic::{RelicDef, RelicModifiers, RelicRegistry};
 use crate::skill::Skill;
 
 pub const MAX_SKILLS: usize = 2;
+/// Cards drawn at the start of every round.
+pub const HAND_SIZE: usize = 4;
+pub const DEFAULT_MAX_ENERGY: i32 = 4;
 pub const DEFAULT_MAX_MANA: i32 = 100;
-
-#[derive(Clone, Debug, PartialEq, Eq)]
-pub enum PassiveSkill {
-    /// 预备：胜利后额外获得 1 金币
-    Prepared,
-}
-
-impl PassiveSkill {
-    pub fn name(&self) -> &'static str {
-        match self {
-            PassiveSkill::Prepared => "预备",
-        }
-    }
-
-    pub fn victory_bonus_gold(&self) -> i32 {
-        match self {
-            PassiveSkill::Prepared => 1,
-        }
-    }
-}
+/// Mana recovered at the start of every round.
+pub const MANA_REGEN_PER_ROUND: i32 = 20;
 
 /// The player-controlled character.
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Player {
     name: String,
     hp: i32,
@@ -33,10 +24,18 @@ pub struct Player {
     speed: i32,
     shield: i32,
     gold: i32,
+    energy: i32,
+    max_energy: i32,
     mana: i32,
     max_mana: i32,
-    passive: Option<PassiveSkill>,
-    pub hand: Vec<Card>,
+    /// Passive skill ids, in the order they were gained.
+    passives: Vec<String>,
+    #[serde(default)]
+    buffs: BuffManager,
+    /// Relic ids, in the order they were picked up.
+    #[serde(default)]
+    relics: Vec<String>,
+    deck: Deck,
     pub skills: Vec<Skill>,
 }
 
@@ -49,20 +48,25 @@ impl Player {
             speed: 3,
             shield: 0,
             gold: 0,
+            energy: DEFAULT_MAX_ENERGY,
+            max_energy: DEFAULT_MAX_ENERGY,
             mana: DEFAULT_MAX_MANA,
             max_mana: DEFAULT_MAX_MANA,
-            passive: None,
-            hand: Vec::new(),
+            passives: Vec::new(),
+            buffs: BuffManager::new(),
+            relics: Vec::new(),
+            deck: Deck::new(),
             skills: Vec::new(),
         }
     }
 
-    pub fn set_passive(&mut self, passive: PassiveSkill) {
-        self.passive = Some(passive);
-    }
-
-    pub fn passive(&self) -> Option<&PassiveSkill> {
-        self.passive.as_ref()
+    /// Learns a passive skill. Returns false for an unknown id or one already held.
+    pub fn add_passive(&mut self, id: &str) -> bool {
+        if PassiveRegistry::builtin().get(id).is_none() || self.passives.iter().any(|p| p == id) {
+            return false;
+        }
+        self.passives.push(id.to_string());
+        true
     }
 
     pub fn gold(&self) -> i32 {
@@ -83,18 +87,50 @@ impl Player {
         }
     }
 
-    #[allow(dead_code)]
+    pub fn energy(&self) -> i32 {
+        self.energy
+    }
+
+    pub fn max_energy(&self) -> i32 {
+        self.max_energy
+    }
+
+    /// Refill energy to max at round start, plus any extra from relics.
+    pub fn refill_energy(&mut self) {
+        self.energy = self.max_energy + self.relic_modifiers().energy_per_round;
+    }
+
+    /// Spend energy. Returns false if not enough energy.
+    pub fn spend_energy(&mut self, amount: i32) -> bool {
+        if self.energy >= amount {
+            self.energy -= amount;
+            true
+        } else {
+            false
+        }
+    }
+
     pub fn mana(&self) -> i32 {
         self.mana
     }
 
-    #[allow(dead_code)]
     pub fn max_mana(&self) -> i32 {
         self.max_mana
     }
 
+    /// Recover mana up to the maximum. Returns the amount actually recovered.
+    pub fn regen_mana(&mut self, amount: i32) -> i32 {
+        let before = self.mana;
+        self.mana = (self.mana + amount).min(self.max_mana);
+        self.mana - before
+    }
+
+    /// Whether there is enough energy, and mana for a spell, to play `card`.
+    pub fn can_afford(&self, card: &Card) -> bool {
+        self.energy >= card.energy_cost && (!card.is_spell() || self.mana >= card.mana_cost)
+    }
+
     /// Spend mana. Returns false if not enough mana.
-    #[allow(dead_code)]
     pub fn spend_mana(&mut self, amount: i32) -> bool {
         if self.mana >= amount {
             self.mana -= amount;
@@ -104,34 +140,90 @@ impl Player {
         }
     }
 
-    /// Whether the player has any spell cards equipped.
-    #[allow(dead_code)]
+    /// Whether the player owns any spell cards.
     pub fn has_spell_cards(&self) -> bool {
-        self.hand.iter().any(|c| c.is_spell())
+        self.deck.cards().iter().any(|c| c.is_spell())
     }
 
-    /// Reset HP, mana and cooldowns for a new battle.
+    /// Reset shield, energy, mana, buffs and skill cooldowns for a new battle. HP carries
+    /// over from the last one. The deck is shuffled separately, with the run's RNG.
     pub fn reset_for_battle(&mut self) {
-        self.hp = self.max_hp;
         self.shield = 0;
+        self.energy = self.max_energy;
         self.mana = self.max_mana;
-        for card in &mut self.hand {
-            card.set_initial_cooldown_ms(0);
-        }
+        self.buffs.clear();
         for skill in &mut self.skills {
             skill.remaining_cooldown_ms = 0;
         }
     }
 
+    /// Extra victory gold from relics.
     pub fn victory_bonus_gold(&self) -> i32 {
-        self.passive
-            .as_ref()
-            .map(PassiveSkill::victory_bonus_gold)
-            .unwrap_or(0)
+        self.relic_modifiers().victory_bonus_gold
+    }
+
+    pub fn relics(&self) -> &[String] {
+        &self.relics
+    }
+
+    /// Definitions of the relics held, skipping ids no longer in the registry.
+    pub fn relic_defs(&self) -> impl Iterator<Item = &'static RelicDef> + '_ {
+        self.relics
+            .iter()
+            .filter_map(|id| RelicRegistry::builtin().get(id))
+    }
+
+    /// Every held relic's modifiers added together.
+    pub fn relic_modifiers(&self) -> RelicModifiers {
+        let mut total = RelicModifiers::NONE;
+        for def in self.relic_defs() {
+            total.stack(&def.modifiers);
+        }
+        total
+    }
+
+    /// Picks up a relic, applying its one-off max HP / max energy bonus.
+    /// Returns false for an unknown id or one already held.
+    pub fn add_relic(&mut self, id: &str) -> bool {
+        let Some(def) = RelicRegistry::builtin().get(id) else {
+            return false;
+        };
+        if self.relics.iter().any(|r| r == id) {
+            return false;
+        }
+        self.relics.push(def.id.to_string());
+        self.gain_max_hp(def.modifiers.bonus_max_hp);
+        self.max_energy += def.modifiers.bonus_max_energy;
+        self.energy += def.modifiers.bonus_max_energy;
+        true
     }
 
+    /// Raises max HP, and current HP with it, by `amount`. Never drops either below 1.
+    pub fn gain_max_hp(&mut self, amount: i32) {
+        self.max_hp = (self.max_hp + amount).max(1);
+        self.hp = (self.hp + amount).clamp(1, self.max_hp);
+    }
+
+    /// Adds a card to the deck; it is dealt from the next battle on.
     pub fn add_card(&mut self, card: Card) {
-        self.hand.push(card);
+        self.deck.add(card);
+    }
+
+    pub fn deck(&self) -> &Deck {
+        &self.deck
+    }
+
+    pub fn deck_mut(&mut self) -> &mut Deck {
+        &mut self.deck
+    }
+
+    /// The cards drawn this round.
+    pub fn hand(&self) -> &[Card] {
+        self.deck.hand()
+    }
+
+    pub fn hand_mut(&mut self) -> &mut [Card] {
+        self.deck.hand_mut()
     }
 
     /// Equip a skill. Returns false if already at max capacity.
@@ -143,6 +235,11 @@ impl Player {
         true
     }
 
+    /// Unequip the skill at `index`, freeing its slot.
+    pub fn unequip_skill(&mut self, index: usize) -> Option<Skill> {
+        (index < self.skills.len()).then(|| self.skills.remove(index))
+    }
+
     /// Tick all skill cooldowns by elapsed milliseconds.
     pub fn tick_skill_cooldowns_ms(&mut self, elapsed_ms: u64) {
         for skill in &mut self.skills {
@@ -173,12 +270,27 @@ impl Combatant for Player {
     fn clear_shield(&mut self) {
         self.shield = 0;
     }
+    fn buffs(&self) -> &BuffManager {
+        &self.buffs
+    }
+    fn buffs_mut(&mut self) -> &mut BuffManager {
+        &mut self.buffs
+    }
     fn take_damage(&mut self, amount: i32) {
+        let amount = self.buffs.damage_taken(amount);
         let absorbed = amount.min(self.shield);
         self.shield -= absorbed;
         self.hp = (self.hp - (amount - absorbed)).max(0);
     }
+    fn passives(&self) -> &[String] {
+        &self.passives
+    }
+    fn dodge_chance(&self) -> f64 {
+        let dodge = self.relic_modifiers().dodge_chance + passive::dodge_chance(&self.passives);
+        dodge.clamp(0.0, 1.0)
+    }
     fn heal(&mut self, amount: i32) -> i32 {
+        let amount = self.buffs.heal_received(amount);
         let before = self.hp;
         self.hp = (self.hp + amount).min(self.max_hp);
         self.hp - before
@@ -188,7 +300,8 @@ impl Combatant for Player {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::card::attack::create_attack_card;
+    use crate::card::{Card, CardEffect};
+    use crate::mechanics::passive::{DODGE_MASTER, PREPARED, SWIFT};
     use crate::skill::emergency_heal::create_emergency_heal;
 
     #[test]
@@ -203,23 +316,33 @@ mod tests {
     fn new_player_defaults() {
         let p = Player::new("勇者", 3);
         assert_eq!(p.gold(), 0);
-        assert!(p.passive().is_none());
+        assert!(p.passives().is_empty());
         assert_eq!(p.victory_bonus_gold(), 0);
     }
 
     #[test]
-    fn passive_skill_bonus_gold() {
+    fn player_holds_several_passives() {
         let mut p = Player::new("勇者", 3);
-        p.set_passive(PassiveSkill::Prepared);
-        assert_eq!(p.passive().unwrap().name(), "预备");
-        assert_eq!(p.victory_bonus_gold(), 1);
+        assert!(p.add_passive(PREPARED));
+        assert!(p.add_passive(DODGE_MASTER));
+        assert!(p.add_passive(SWIFT));
+        assert!(!p.add_passive(PREPARED));
+        assert!(!p.add_passive("no_such_passive"));
+        assert_eq!(p.passives(), [PREPARED, DODGE_MASTER, SWIFT]);
+        assert!((p.dodge_chance() - 0.25).abs() < 1e-9);
     }
 
     #[test]
-    fn player_can_hold_cards() {
+    fn cards_join_the_deck_not_the_hand() {
         let mut p = Player::new("勇者", 3);
-        p.add_card(create_attack_card());
-        assert_eq!(p.hand.len(), 1);
+        p.add_card(Card::new(
+            "攻击",
+            "造成 1 点伤害",
+            CardEffect::Damage(1),
+            3_000,
+        ));
+        assert_eq!(p.deck().cards().len(), 1);
+        assert!(p.hand().is_empty());
     }
 
     #[test]
@@ -236,6 +359,10 @@ mod tests {
         assert!(p.equip_skill(create_emergency_heal()));
         assert!(!p.equip_skill(create_emergency_heal()));
         assert_eq!(p.skills.len(), MAX_SKILLS);
+
+        assert!(p.unequip_skill(MAX_SKILLS).is_none());
+        assert!(p.unequip_skill(0).is_some());
+        assert!(p.equip_skill(create_emergency_heal()), "卸下后腾出技能栏");
     }
 
     #[test]
@@ -248,6 +375,54 @@ mod tests {
         assert_eq!(p.hp(), 3);
     }
 
+    #[test]
+    fn energy_is_spent_and_refilled() {
+        let mut p = Player::new("勇者", 3);
+        let mut strike = Card::new("暴击", "造成 2 点伤害", CardEffect::Damage(2), 5_000);
+        strike.energy_cost = 2;
+        assert!(p.spend_energy(3));
+        assert!(!p.can_afford(&strike));
+        assert!(!p.spend_energy(2));
+        assert_eq!(p.energy(), 1);
+
+        p.refill_energy();
+        assert_eq!(p.energy(), p.max_energy());
+        assert!(p.can_afford(&strike));
+    }
+
+    #[test]
+    fn mana_is_spent_and_regenerated_up_to_max() {
+        let mut p = Player::new("勇者", 3);
+        let fireball = Card::new_spell("火球术", "造成 2 点伤害", CardEffect::Damage(2), 4_000, 40);
+        assert!(p.can_afford(&fireball));
+        assert!(p.spend_mana(80));
+        assert!(!p.can_afford(&fireball));
+        assert!(!p.spend_mana(40));
+        assert_eq!(p.mana(), 20);
+
+        assert_eq!(p.regen_mana(MANA_REGEN_PER_ROUND), 20);
+        assert!(p.can_afford(&fireball));
+        assert_eq!(p.regen_mana(100), 60);
+        assert_eq!(p.mana(), p.max_mana());
+    }
+
+    #[test]
+    fn relics_are_held_once_and_apply_their_bonuses() {
+        let mut p = Player::new("勇者", 3);
+        assert!(p.add_relic("life_spring"));
+        assert!(!p.add_relic("life_spring"));
+        assert!(!p.add_relic("no_such_relic"));
+        assert_eq!((p.hp(), p.max_hp()), (6, 6));
+
+        assert!(p.add_relic("gold_magnet"));
+        assert!(p.add_relic("infinity_gem"));
+        assert_eq!(p.victory_bonus_gold(), 2);
+        p.refill_energy();
+        assert_eq!(p.max_energy(), DEFAULT_MAX_ENERGY + 3);
+        assert_eq!(p.energy(), DEFAULT_MAX_ENERGY + 5);
+        assert_eq!(p.relics(), ["life_spring", "gold_magnet", "infinity_gem"]);
+    }
+
     #[test]
     fn tick_skill_cooldowns_ms() {
         let mut p = Player::new("勇者", 3);
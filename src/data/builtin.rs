@@ -0,0 +1,49 @@
+//! The codex files under `docs/data`, compiled into the binary so the game runs
+//! wherever it is installed.
+
+/// `(id, info.json)` for every entry under `docs/data/<dir>/`.
+macro_rules! entries {
+    ($dir:literal: $($id:literal),* $(,)?) => {
+        &[$((
+            $id,
+            include_str!(concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/docs/data/",
+                $dir,
+                "/",
+                $id,
+                "/info.json"
+            )),
+        )),*]
+    };
+}
+
+pub(super) const CARDS: &[(&str, &str)] = entries!("cards":
+    "armor_break",
+    "attack",
+    "battle_plan",
+    "critical_strike",
+    "defense",
+    "dragon_breath",
+    "fireball",
+    "heal",
+    "poison_dagger",
+    "rejuvenation",
+    "whirlwind",
+);
+
+pub(super) const ENEMIES: &[(&str, &str)] = entries!("enemies":
+    "dragon",
+    "dragon_whelp",
+    "forest_wolf",
+    "giant_slime",
+    "goblin_bandit",
+    "goblin_rogue",
+    "skeleton_mage",
+    "slime",
+);
+
+pub(super) const ROSTER: &str = include_str!(concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/docs/data/enemies/roster.json"
+));
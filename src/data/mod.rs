@@ -0,0 +1,357 @@
+//! Game content loaded from the codex data in `docs/data`, so the web codex and
+//! the game read the same files.
+
+mod builtin;
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::card::CardCatalog;
+use crate::enemy::catalog::ROSTER_FILE;
+use crate::enemy::EnemyCatalog;
+
+/// Everything the engine needs from the data directory.
+#[derive(Debug, Clone)]
+pub struct GameData {
+    pub cards: CardCatalog,
+    pub enemies: EnemyCatalog,
+}
+
+impl GameData {
+    /// Loads `cards/` and `enemies/` under `dir`.
+    pub fn load(dir: &Path) -> Result<Self, CatalogError> {
+        let cards = CardCatalog::from_dir(&dir.join("cards"))?;
+        let enemies = EnemyCatalog::from_dir(&dir.join("enemies"), &cards)?;
+        Ok(Self { cards, enemies })
+    }
+
+    /// The `docs/data` files compiled into the binary, parsed once.
+    ///
+    /// Needs no files at run time. The tests parse the same embedded files, so a build
+    /// that passed them cannot fail here.
+    pub fn builtin() -> &'static GameData {
+        static BUILTIN: OnceLock<GameData> = OnceLock::new();
+        BUILTIN.get_or_init(|| Self::embedded().unwrap_or_else(|e| panic!("内置游戏数据无效：{e}")))
+    }
+
+    fn embedded() -> Result<Self, CatalogError> {
+        let cards = CardCatalog::from_entries(parse_entries(Path::new("cards"), builtin::CARDS)?)?;
+        let enemies_dir = Path::new("enemies");
+        let roster = parse_json(&enemies_dir.join(ROSTER_FILE), builtin::ROSTER)?;
+        let enemies = EnemyCatalog::from_entries(
+            parse_entries(enemies_dir, builtin::ENEMIES)?,
+            roster,
+            &cards,
+        )?;
+        Ok(Self { cards, enemies })
+    }
+}
+
+/// Why a data catalog could not be loaded.
+#[derive(Debug)]
+pub enum CatalogError {
+    Io {
+        path: PathBuf,
+        source: io::Error,
+    },
+    Format {
+        path: PathBuf,
+        source: serde_json::Error,
+    },
+    MissingStat {
+        entry: String,
+        key: &'static str,
+    },
+    InvalidStat {
+        entry: String,
+        key: &'static str,
+        value: String,
+    },
+    /// An entry the rules depend on is not in the catalog.
+    MissingEntry(String),
+    /// `entry` refers to an id that does not exist.
+    UnknownReference {
+        entry: String,
+        id: String,
+    },
+}
+
+impl fmt::Display for CatalogError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CatalogError::Io { path, source } => {
+                write!(f, "无法读取 {}：{source}", path.display())
+            }
+            CatalogError::Format { path, source } => {
+                write!(f, "{} 格式错误：{source}", path.display())
+            }
+            CatalogError::MissingStat { entry, key } => {
+                write!(f, "「{entry}」缺少属性「{key}」")
+            }
+            CatalogError::InvalidStat { entry, key, value } => {
+                write!(f, "「{entry}」的属性「{key}」无效：{value}")
+            }
+            CatalogError::MissingEntry(id) => write!(f, "数据目录缺少必需的条目 {id}"),
+            CatalogError::UnknownReference { entry, id } => {
+                write!(f, "「{entry}」引用了不存在的条目 {id}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for CatalogError {}
+
+/// The parts of a codex `info.json` the game cares about.
+#[derive(Debug, Deserialize)]
+pub(crate) struct Info {
+    pub name: String,
+    #[serde(default)]
+    pub stats: BTreeMap<String, Value>,
+    #[serde(default)]
+    pub skills: Vec<InfoSkill>,
+    /// Stats that change when the entry is upgraded, e.g. `{ "伤害值": 2 }`.
+    #[serde(default)]
+    pub upgrade: BTreeMap<String, Value>,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct InfoSkill {
+    pub name: String,
+    #[serde(rename = "type", default)]
+    pub kind: String,
+}
+
+impl Info {
+    pub fn stats(&self) -> Stats<'_> {
+        Stats {
+            entry: &self.name,
+            stats: &self.stats,
+        }
+    }
+}
+
+pub(crate) fn read_json<T: DeserializeOwned>(path: &Path) -> Result<T, CatalogError> {
+    let json = fs::read_to_string(path).map_err(|source| CatalogError::Io {
+        path: path.to_path_buf(),
+        source,
+    })?;
+    parse_json(path, &json)
+}
+
+/// Parses JSON already read from `path`; the path only labels errors.
+pub(crate) fn parse_json<T: DeserializeOwned>(path: &Path, json: &str) -> Result<T, CatalogError> {
+    serde_json::from_str(json).map_err(|source| CatalogError::Format {
+        path: path.to_path_buf(),
+        source,
+    })
+}
+
+/// Like [`read_entries`], for `(id, info.json)` pairs already in memory.
+fn parse_entries<T: DeserializeOwned>(
+    dir: &Path,
+    files: &[(&str, &str)],
+) -> Result<Vec<(String, T)>, CatalogError> {
+    let mut entries = files
+        .iter()
+        .map(|(id, json)| {
+            Ok((
+                id.to_string(),
+                parse_json(&dir.join(id).join("info.json"), json)?,
+            ))
+        })
+        .collect::<Result<Vec<_>, CatalogError>>()?;
+    entries.sort_by(|a, b| a.0.cmp(&b.0));
+    Ok(entries)
+}
+
+/// Reads every `<id>/info.json` under `dir`, sorted by id. Directories without one are skipped.
+pub(crate) fn read_entries<T: DeserializeOwned>(
+    dir: &Path,
+) -> Result<Vec<(String, T)>, CatalogError> {
+    let io_err = |source| CatalogError::Io {
+        path: dir.to_path_buf(),
+        source,
+    };
+    let mut entries = Vec::new();
+    for entry in fs::read_dir(dir).map_err(io_err)? {
+        let entry_dir = entry.map_err(io_err)?.path();
+        let path = entry_dir.join("info.json");
+        if !path.is_file() {
+            continue;
+        }
+        let id = entry_dir
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        entries.push((id, read_json(&path)?));
+    }
+    entries.sort_by(|a, b| a.0.cmp(&b.0));
+    Ok(entries)
+}
+
+/// Typed access to an entry's stats table, reporting errors against the entry name.
+pub(crate) struct Stats<'a> {
+    entry: &'a str,
+    stats: &'a BTreeMap<String, Value>,
+}
+
+impl<'a> Stats<'a> {
+    pub fn get(&self, key: &'static str) -> Option<&'a Value> {
+        self.stats.get(key)
+    }
+
+    pub fn require(&self, key: &'static str) -> Result<&'a Value, CatalogError> {
+        self.get(key).ok_or(CatalogError::MissingStat {
+            entry: self.entry.to_string(),
+            key,
+        })
+    }
+
+    pub fn invalid(&self, key: &'static str, value: &Value) -> CatalogError {
+        CatalogError::InvalidStat {
+            entry: self.entry.to_string(),
+            key,
+            value: value.to_string(),
+        }
+    }
+
+    /// A required non-negative whole number.
+    pub fn count(&self, key: &'static str) -> Result<i32, CatalogError> {
+        let value = self.require(key)?;
+        value
+            .as_i64()
+            .and_then(|n| i32::try_from(n).ok())
+            .filter(|n| *n >= 0)
+            .ok_or_else(|| self.invalid(key, value))
+    }
+
+    /// Like [`Stats::count`], but `default` when the key is absent.
+    pub fn count_or(&self, key: &'static str, default: i32) -> Result<i32, CatalogError> {
+        match self.get(key) {
+            None => Ok(default),
+            Some(_) => self.count(key),
+        }
+    }
+
+    /// A duration in seconds, written as a number or as text like "5 秒".
+    pub fn millis_or(&self, key: &'static str, default: u64) -> Result<u64, CatalogError> {
+        let Some(value) = self.get(key) else {
+            return Ok(default);
+        };
+        let secs = match value {
+            Value::Number(n) => n.as_f64(),
+            Value::String(s) => s.trim().trim_end_matches('秒').trim().parse().ok(),
+            _ => None,
+        };
+        secs.filter(|s| *s >= 0.0)
+            .map(|s| (s * 1_000.0).round() as u64)
+            .ok_or_else(|| self.invalid(key, value))
+    }
+
+    /// A probability, written as a fraction (0.1) or a percentage ("10%").
+    pub fn chance_or(&self, key: &'static str, default: f64) -> Result<f64, CatalogError> {
+        match self.get(key) {
+            None => Ok(default),
+            Some(value) => parse_chance(value).ok_or_else(|| self.invalid(key, value)),
+        }
+    }
+
+    /// A yes/no flag, written as a boolean or as "是"/"否".
+    pub fn flag_or(&self, key: &'static str, default: bool) -> Result<bool, CatalogError> {
+        let Some(value) = self.get(key) else {
+            return Ok(default);
+        };
+        match value {
+            Value::Bool(b) => Ok(*b),
+            Value::String(s) if s.trim() == "是" => Ok(true),
+            Value::String(s) if s.trim() == "否" => Ok(false),
+            _ => Err(self.invalid(key, value)),
+        }
+    }
+}
+
+/// Reads a probability written as a fraction (0.1) or a percentage ("10%").
+pub(crate) fn parse_chance(value: &Value) -> Option<f64> {
+    let chance = match value {
+        Value::Number(n) => n.as_f64(),
+        Value::String(s) => s
+            .trim()
+            .strip_suffix('%')
+            .and_then(|p| p.trim().parse::<f64>().ok())
+            .map(|p| p / 100.0),
+        _ => None,
+    };
+    chance.filter(|c| (0.0..=1.0).contains(c))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn info(json: &str) -> Info {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn builtin_data_loads() {
+        let data = GameData::builtin();
+        assert!(data.cards.get("attack").is_some());
+        assert!(data.enemies.get("slime").is_some());
+    }
+
+    #[test]
+    fn builtin_data_embeds_every_entry_in_docs() {
+        let dir = Path::new(concat!(env!("CARGO_MANIFEST_DIR"), "/docs/data"));
+        let on_disk = GameData::load(dir).unwrap();
+        let builtin = GameData::builtin();
+        assert!(
+            on_disk.cards.ids().eq(builtin.cards.ids()),
+            "新卡牌要加进 data/builtin.rs"
+        );
+        assert!(
+            on_disk.enemies.ids().eq(builtin.enemies.ids()),
+            "新敌人要加进 data/builtin.rs"
+        );
+    }
+
+    #[test]
+    fn stats_parse_durations_chances_and_flags() {
+        let info = info(
+            r#"{ "name": "测试", "stats": { "冷却": "5 秒", "间隔": 1.5,
+                 "闪避率": "15%", "暴击率": 0.25, "消耗": "是", "坏": "很久" } }"#,
+        );
+        let stats = info.stats();
+        assert_eq!(stats.millis_or("冷却", 0).unwrap(), 5_000);
+        assert_eq!(stats.millis_or("间隔", 0).unwrap(), 1_500);
+        assert_eq!(stats.millis_or("不存在", 3_000).unwrap(), 3_000);
+        assert!((stats.chance_or("闪避率", 0.0).unwrap() - 0.15).abs() < 1e-9);
+        assert!((stats.chance_or("暴击率", 0.0).unwrap() - 0.25).abs() < 1e-9);
+        assert!(stats.millis_or("坏", 0).is_err());
+        assert!(stats.chance_or("坏", 0.0).is_err());
+        assert!(stats.flag_or("消耗", false).unwrap());
+        assert!(!stats.flag_or("不存在", false).unwrap());
+        assert!(stats.flag_or("坏", false).is_err());
+    }
+
+    #[test]
+    fn stats_errors_name_the_entry() {
+        let info = info(r#"{ "name": "坏牌", "stats": { "伤害值": -1 } }"#);
+        let stats = info.stats();
+        assert_eq!(
+            stats.count("伤害值").unwrap_err().to_string(),
+            "「坏牌」的属性「伤害值」无效：-1"
+        );
+        assert_eq!(
+            stats.count("护盾值").unwrap_err().to_string(),
+            "「坏牌」缺少属性「护盾值」"
+        );
+    }
+}
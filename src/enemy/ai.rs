@@ -0,0 +1,218 @@
+use rand::rngs::StdRng;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::card::Card;
+
+/// What an enemy AI can see when picking a card.
+pub struct AiContext<'a> {
+    pub deck: &'a [Card],
+    pub hp: i32,
+    pub max_hp: i32,
+    pub opponent_hp: i32,
+}
+
+impl AiContext<'_> {
+    fn is_ready(&self, index: usize) -> bool {
+        self.deck.get(index).is_some_and(Card::is_ready)
+    }
+}
+
+/// Decides which card an enemy plays next.
+pub trait EnemyAi {
+    /// Returns the deck index to play now, or `None` to hold off.
+    /// Only cards that are ready may be returned; the engine plays whatever is chosen.
+    fn choose(&mut self, ctx: &AiContext<'_>, rng: &mut StdRng) -> Option<usize>;
+}
+
+/// Plays cards in a fixed, repeating order, waiting for each one to come off cooldown.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScriptedAi {
+    pattern: Vec<usize>,
+    step: usize,
+}
+
+impl ScriptedAi {
+    pub fn new(pattern: Vec<usize>) -> Self {
+        Self { pattern, step: 0 }
+    }
+}
+
+impl EnemyAi for ScriptedAi {
+    fn choose(&mut self, ctx: &AiContext<'_>, _rng: &mut StdRng) -> Option<usize> {
+        let index = *self.pattern.get(self.step)?;
+        if !ctx.is_ready(index) {
+            return None;
+        }
+        self.step = (self.step + 1) % self.pattern.len();
+        Some(index)
+    }
+}
+
+/// Picks one of the ready cards at random, in proportion to its weight.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WeightedRandomAi {
+    /// One weight per deck card; 0 means never.
+    weights: Vec<u32>,
+}
+
+impl WeightedRandomAi {
+    pub fn new(weights: Vec<u32>) -> Self {
+        Self { weights }
+    }
+
+    /// Every card equally likely.
+    pub fn uniform(deck_len: usize) -> Self {
+        Self::new(vec![1; deck_len])
+    }
+}
+
+impl EnemyAi for WeightedRandomAi {
+    fn choose(&mut self, ctx: &AiContext<'_>, rng: &mut StdRng) -> Option<usize> {
+        let ready: Vec<(usize, u32)> = self
+            .weights
+            .iter()
+            .enumerate()
+            .filter(|(i, w)| **w > 0 && ctx.is_ready(*i))
+            .map(|(i, w)| (i, *w))
+            .collect();
+        let total: u32 = ready.iter().map(|(_, w)| w).sum();
+        if total == 0 {
+            return None;
+        }
+        let mut roll = rng.gen_range(0..total);
+        for (index, weight) in ready {
+            if roll < weight {
+                return Some(index);
+            }
+            roll -= weight;
+        }
+        None
+    }
+}
+
+/// Plays a specific card once HP drops below a fraction of max HP.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HpRule {
+    pub below: f64,
+    pub card: usize,
+}
+
+/// Checks [`HpRule`]s in order, otherwise falls back to weighted random play.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HpThresholdAi {
+    rules: Vec<HpRule>,
+    fallback: WeightedRandomAi,
+}
+
+impl HpThresholdAi {
+    pub fn new(rules: Vec<HpRule>, fallback: WeightedRandomAi) -> Self {
+        Self { rules, fallback }
+    }
+}
+
+impl EnemyAi for HpThresholdAi {
+    fn choose(&mut self, ctx: &AiContext<'_>, rng: &mut StdRng) -> Option<usize> {
+        let hp_fraction = ctx.hp as f64 / ctx.max_hp.max(1) as f64;
+        self.rules
+            .iter()
+            .find(|rule| hp_fraction < rule.below && ctx.is_ready(rule.card))
+            .map(|rule| rule.card)
+            .or_else(|| self.fallback.choose(ctx, rng))
+    }
+}
+
+/// The AI an enemy runs. Stored with the enemy so a save keeps scripted progress.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Behavior {
+    Scripted(ScriptedAi),
+    WeightedRandom(WeightedRandomAi),
+    HpThreshold(HpThresholdAi),
+}
+
+impl Behavior {
+    pub fn ai_mut(&mut self) -> &mut dyn EnemyAi {
+        match self {
+            Behavior::Scripted(ai) => ai,
+            Behavior::WeightedRandom(ai) => ai,
+            Behavior::HpThreshold(ai) => ai,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::card::CardEffect;
+    use rand::SeedableRng;
+
+    fn deck() -> Vec<Card> {
+        vec![
+            Card::new("攻击", "造成 1 点伤害", CardEffect::Damage(1), 3_000),
+            Card::new("防御", "获得 1 点护盾", CardEffect::Shield(1), 3_000),
+            Card::new("治愈", "恢复 1 点生命值", CardEffect::Heal(1), 4_000),
+        ]
+    }
+
+    fn ctx(deck: &[Card], hp: i32) -> AiContext<'_> {
+        AiContext {
+            deck,
+            hp,
+            max_hp: 4,
+            opponent_hp: 3,
+        }
+    }
+
+    #[test]
+    fn scripted_follows_pattern_and_waits() {
+        let mut deck = deck();
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut ai = ScriptedAi::new(vec![0, 0, 1]);
+        assert_eq!(ai.choose(&ctx(&deck, 4), &mut rng), Some(0));
+
+        deck[0].trigger_cooldown();
+        assert_eq!(ai.choose(&ctx(&deck, 4), &mut rng), None, "等待攻击牌冷却");
+
+        deck[0].tick_cooldown_ms(3_000);
+        assert_eq!(ai.choose(&ctx(&deck, 4), &mut rng), Some(0));
+        assert_eq!(ai.choose(&ctx(&deck, 4), &mut rng), Some(1));
+        assert_eq!(ai.choose(&ctx(&deck, 4), &mut rng), Some(0), "循环回到开头");
+    }
+
+    #[test]
+    fn weighted_random_only_picks_ready_weighted_cards() {
+        let mut deck = deck();
+        deck[1].trigger_cooldown();
+        let mut rng = StdRng::seed_from_u64(3);
+        let mut ai = WeightedRandomAi::new(vec![1, 5, 0]);
+        for _ in 0..50 {
+            assert_eq!(ai.choose(&ctx(&deck, 4), &mut rng), Some(0));
+        }
+        deck[0].trigger_cooldown();
+        assert_eq!(ai.choose(&ctx(&deck, 4), &mut rng), None);
+    }
+
+    #[test]
+    fn hp_threshold_heals_when_low() {
+        let deck = deck();
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut ai = HpThresholdAi::new(
+            vec![HpRule {
+                below: 0.5,
+                card: 2,
+            }],
+            WeightedRandomAi::new(vec![1, 0, 0]),
+        );
+        assert_eq!(ai.choose(&ctx(&deck, 4), &mut rng), Some(0));
+        assert_eq!(ai.choose(&ctx(&deck, 1), &mut rng), Some(2));
+    }
+
+    #[test]
+    fn behavior_round_trips_through_json() {
+        let behavior = Behavior::Scripted(ScriptedAi::new(vec![0, 1]));
+        let json = serde_json::to_string(&behavior).unwrap();
+        assert!(json.contains(r#""type":"scripted""#));
+        assert_eq!(serde_json::from_str::<Behavior>(&json).unwrap(), behavior);
+    }
+}
@@ -0,0 +1,597 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use rand::distributions::{Distribution, WeightedIndex};
+use rand::Rng;
+use serde::Deserialize;
+use serde_json::Value;
+
+use super::ai::{HpRule, HpThresholdAi, ScriptedAi, WeightedRandomAi};
+use super::{Behavior, Enemy, Phase};
+use crate::card::{Card, CardCatalog};
+use crate::data::{self, CatalogError, Info};
+use crate::mechanics::buff::{BuffManager, BuffRegistry};
+use crate::mechanics::effect::{Effect, EffectTarget};
+use crate::mechanics::passive::PassiveRegistry;
+
+/// File under `enemies/` that says which monsters appear and how often.
+pub const ROSTER_FILE: &str = "roster.json";
+
+/// Stats for one kind of monster, as read from its codex entry.
+#[derive(Debug, Clone)]
+pub struct EnemyDef {
+    pub id: String,
+    pub name: String,
+    pub max_hp: i32,
+    pub speed: i32,
+    pub starting_shield: i32,
+    pub dodge_chance: f64,
+    /// Passive skill ids.
+    pub passives: Vec<String>,
+    pub deck: Vec<Card>,
+    pub behavior: Behavior,
+    /// Empty for single-phase enemies.
+    pub phases: Vec<Phase>,
+}
+
+impl EnemyDef {
+    pub fn spawn(&self) -> Enemy {
+        Enemy {
+            id: self.id.clone(),
+            name: self.name.clone(),
+            hp: self.max_hp,
+            max_hp: self.max_hp,
+            speed: self.speed,
+            shield: self.starting_shield,
+            dodge_chance: self.dodge_chance,
+            passives: self.passives.clone(),
+            deck: self.deck.clone(),
+            behavior: self.behavior.clone(),
+            buffs: BuffManager::new(),
+            stolen_gold: 0,
+            phases: self.phases.clone(),
+            phase: 0,
+        }
+    }
+}
+
+/// An enemy `info.json`: the codex entry plus the deck and AI the game uses.
+#[derive(Deserialize)]
+pub(crate) struct EnemyInfo {
+    #[serde(flatten)]
+    info: Info,
+    /// Card ids; defaults to a lone `attack`.
+    #[serde(default)]
+    deck: Vec<String>,
+    ai: Option<AiSpec>,
+    /// Stages of a multi-phase fight, entered as HP drops.
+    #[serde(default)]
+    phases: Vec<PhaseSpec>,
+}
+
+/// How `ai` is written in data, naming cards by id rather than deck position.
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum AiSpec {
+    Scripted {
+        pattern: Vec<String>,
+    },
+    WeightedRandom {
+        weights: BTreeMap<String, u32>,
+    },
+    HpThreshold {
+        rules: Vec<HpRuleSpec>,
+        /// Weights for when no rule applies; every card equally likely if omitted.
+        #[serde(default)]
+        weights: BTreeMap<String, u32>,
+    },
+}
+
+/// How a phase is written in data. Everything but `below` and `message` is optional and
+/// leaves that part of the enemy as it was.
+#[derive(Deserialize)]
+struct PhaseSpec {
+    /// Fraction of max HP, e.g. 0.6 or "60%"; the phase begins once HP drops below it.
+    below: Value,
+    /// Shown as the phase begins, to warn the player.
+    message: String,
+    speed: Option<i32>,
+    /// Card ids replacing the deck.
+    deck: Option<Vec<String>>,
+    ai: Option<AiSpec>,
+    /// Passive skill names replacing the current passives.
+    passives: Option<Vec<String>>,
+    enrage: Option<EnrageSpec>,
+    /// Id of an enemy called into battle.
+    summon: Option<String>,
+}
+
+/// A buff the enemy gives itself as a phase begins.
+#[derive(Deserialize)]
+struct EnrageSpec {
+    /// Buff name, e.g. "力量".
+    buff: String,
+    stacks: u32,
+}
+
+#[derive(Deserialize)]
+struct HpRuleSpec {
+    /// Fraction of max HP, e.g. 0.5 or "50%".
+    below: Value,
+    card: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct RosterFile {
+    normal: Vec<RosterEntry>,
+    /// Tougher monsters for elite nodes; elite nodes draw normal ones if empty.
+    #[serde(default)]
+    elite: Vec<RosterEntry>,
+    boss: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct RosterEntry {
+    id: String,
+    weight: u32,
+}
+
+/// Every monster definition plus the roster that decides who shows up.
+#[derive(Debug, Clone)]
+pub struct EnemyCatalog {
+    enemies: BTreeMap<String, EnemyDef>,
+    normal: Vec<RosterEntry>,
+    weights: WeightedIndex<u32>,
+    elite: Vec<RosterEntry>,
+    elite_weights: Option<WeightedIndex<u32>>,
+    boss: String,
+}
+
+impl EnemyCatalog {
+    /// Loads every `<id>/info.json` and the roster under `enemies_dir`.
+    ///
+    /// Attack cards must exist in `cards`, and phase summons among the loaded enemies.
+    pub fn from_dir(enemies_dir: &Path, cards: &CardCatalog) -> Result<Self, CatalogError> {
+        let roster = data::read_json(&enemies_dir.join(ROSTER_FILE))?;
+        Self::from_entries(data::read_entries(enemies_dir)?, roster, cards)
+    }
+
+    /// Builds the catalog from parsed `(id, info.json)` entries and the roster.
+    pub(crate) fn from_entries(
+        entries: Vec<(String, EnemyInfo)>,
+        roster: RosterFile,
+        cards: &CardCatalog,
+    ) -> Result<Self, CatalogError> {
+        let mut enemies = BTreeMap::new();
+        for (id, info) in entries {
+            let def = parse_enemy(id, info, cards)?;
+            enemies.insert(def.id.clone(), def);
+        }
+        for def in enemies.values() {
+            let mut summons = def.phases.iter().filter_map(|phase| phase.summon.as_ref());
+            if let Some(id) = summons.find(|id| !enemies.contains_key(*id)) {
+                return Err(unknown_reference(&def.name, id));
+            }
+        }
+
+        let unknown = |id: &str| CatalogError::UnknownReference {
+            entry: ROSTER_FILE.to_string(),
+            id: id.to_string(),
+        };
+        let entries = roster.normal.iter().chain(&roster.elite);
+        for id in entries.map(|e| &e.id).chain([&roster.boss]) {
+            if !enemies.contains_key(id) {
+                return Err(unknown(id));
+            }
+        }
+        let invalid_weights = |tier: &str| CatalogError::InvalidStat {
+            entry: ROSTER_FILE.to_string(),
+            key: "weight",
+            value: format!("{tier}敌人的权重必须至少有一个大于 0"),
+        };
+        let weights = WeightedIndex::new(roster.normal.iter().map(|e| e.weight))
+            .map_err(|_| invalid_weights("普通"))?;
+        let elite_weights = if roster.elite.is_empty() {
+            None
+        } else {
+            let weights = WeightedIndex::new(roster.elite.iter().map(|e| e.weight))
+                .map_err(|_| invalid_weights("精英"))?;
+            Some(weights)
+        };
+
+        Ok(Self {
+            enemies,
+            normal: roster.normal,
+            weights,
+            elite: roster.elite,
+            elite_weights,
+            boss: roster.boss,
+        })
+    }
+
+    pub fn ids(&self) -> impl Iterator<Item = &str> {
+        self.enemies.keys().map(String::as_str)
+    }
+
+    pub fn get(&self, id: &str) -> Option<&EnemyDef> {
+        self.enemies.get(id)
+    }
+
+    /// Draws a normal-stage monster according to the roster weights.
+    pub fn pick_normal(&self, rng: &mut impl Rng) -> &EnemyDef {
+        let entry = &self.normal[self.weights.sample(rng)];
+        &self.enemies[&entry.id]
+    }
+
+    /// Draws an elite according to the roster weights, or a normal monster if the roster
+    /// lists no elites.
+    pub fn pick_elite(&self, rng: &mut impl Rng) -> &EnemyDef {
+        match &self.elite_weights {
+            Some(weights) => &self.enemies[&self.elite[weights.sample(rng)].id],
+            None => self.pick_normal(rng),
+        }
+    }
+
+    pub fn boss(&self) -> &EnemyDef {
+        &self.enemies[&self.boss]
+    }
+}
+
+/// Builds a definition from the codex stats table plus `deck`, `ai` and `phases`.
+///
+/// `生命值` and `速度` are required; `初始护盾` and `闪避率` ("10%") are optional.
+/// Skills typed `被动` must name a registered passive. Without `ai`, every ready card is
+/// equally likely.
+fn parse_enemy(
+    id: String,
+    entry: EnemyInfo,
+    cards: &CardCatalog,
+) -> Result<EnemyDef, CatalogError> {
+    let info = &entry.info;
+    let stats = info.stats();
+    let deck_ids = if entry.deck.is_empty() {
+        vec!["attack".to_string()]
+    } else {
+        entry.deck
+    };
+    let deck = build_deck(&info.name, &deck_ids, cards)?;
+    let behavior = build_behavior(&info.name, &deck_ids, entry.ai)?;
+    let passive_names: Vec<_> = info
+        .skills
+        .iter()
+        .filter(|s| s.kind == "被动")
+        .map(|s| s.name.clone())
+        .collect();
+    let passives = passive_ids(&info.name, &passive_names)?;
+    let phases = parse_phases(&info.name, deck_ids, entry.phases, cards)?;
+
+    Ok(EnemyDef {
+        id,
+        name: info.name.clone(),
+        max_hp: stats.count("生命值")?,
+        speed: stats.count("速度")?,
+        starting_shield: stats.count_or("初始护盾", 0)?,
+        dodge_chance: stats.chance_or("闪避率", 0.0)?,
+        passives,
+        deck,
+        behavior,
+        phases,
+    })
+}
+
+fn unknown_reference(entry: &str, id: &str) -> CatalogError {
+    CatalogError::UnknownReference {
+        entry: entry.to_string(),
+        id: id.to_string(),
+    }
+}
+
+fn build_deck(entry: &str, ids: &[String], cards: &CardCatalog) -> Result<Vec<Card>, CatalogError> {
+    ids.iter()
+        .map(|id| {
+            cards
+                .get(id)
+                .cloned()
+                .ok_or_else(|| unknown_reference(entry, id))
+        })
+        .collect()
+}
+
+/// Turns an `ai` spec into the AI for a deck of `deck_ids`.
+fn build_behavior(
+    entry: &str,
+    deck_ids: &[String],
+    ai: Option<AiSpec>,
+) -> Result<Behavior, CatalogError> {
+    let index_of = |id: &str| {
+        deck_ids
+            .iter()
+            .position(|d| d == id)
+            .ok_or_else(|| unknown_reference(entry, id))
+    };
+    let weights_of = |weights: &BTreeMap<String, u32>| -> Result<WeightedRandomAi, CatalogError> {
+        if weights.is_empty() {
+            return Ok(WeightedRandomAi::uniform(deck_ids.len()));
+        }
+        let mut by_index = vec![0; deck_ids.len()];
+        for (id, weight) in weights {
+            by_index[index_of(id)?] = *weight;
+        }
+        Ok(WeightedRandomAi::new(by_index))
+    };
+
+    let behavior = match ai {
+        None => Behavior::WeightedRandom(WeightedRandomAi::uniform(deck_ids.len())),
+        Some(AiSpec::Scripted { pattern }) => {
+            if pattern.is_empty() {
+                return Err(CatalogError::InvalidStat {
+                    entry: entry.to_string(),
+                    key: "pattern",
+                    value: "[]".to_string(),
+                });
+            }
+            let pattern = pattern
+                .iter()
+                .map(|id| index_of(id))
+                .collect::<Result<_, _>>()?;
+            Behavior::Scripted(ScriptedAi::new(pattern))
+        }
+        Some(AiSpec::WeightedRandom { weights }) => Behavior::WeightedRandom(weights_of(&weights)?),
+        Some(AiSpec::HpThreshold { rules, weights }) => {
+            let rules = rules
+                .iter()
+                .map(|rule| {
+                    Ok(HpRule {
+                        below: parse_fraction(entry, "below", &rule.below)?,
+                        card: index_of(&rule.card)?,
+                    })
+                })
+                .collect::<Result<_, CatalogError>>()?;
+            Behavior::HpThreshold(HpThresholdAi::new(rules, weights_of(&weights)?))
+        }
+    };
+    Ok(behavior)
+}
+
+fn parse_fraction(entry: &str, key: &'static str, value: &Value) -> Result<f64, CatalogError> {
+    data::parse_chance(value).ok_or_else(|| CatalogError::InvalidStat {
+        entry: entry.to_string(),
+        key,
+        value: value.to_string(),
+    })
+}
+
+/// Looks passive skills up by their codex names.
+fn passive_ids(entry: &str, names: &[String]) -> Result<Vec<String>, CatalogError> {
+    names
+        .iter()
+        .map(|name| {
+            PassiveRegistry::builtin()
+                .by_name(name)
+                .map(|def| def.id.to_string())
+                .ok_or_else(|| unknown_reference(entry, name))
+        })
+        .collect()
+}
+
+/// Builds the phases in the order they are entered, highest threshold first.
+///
+/// A phase's `ai` refers to the cards of its own `deck`, or of the deck in play before it.
+fn parse_phases(
+    entry: &str,
+    mut deck_ids: Vec<String>,
+    specs: Vec<PhaseSpec>,
+    cards: &CardCatalog,
+) -> Result<Vec<Phase>, CatalogError> {
+    let mut specs = specs
+        .into_iter()
+        .map(|spec| Ok((parse_fraction(entry, "below", &spec.below)?, spec)))
+        .collect::<Result<Vec<_>, CatalogError>>()?;
+    specs.sort_by(|(a, _), (b, _)| b.total_cmp(a));
+
+    let mut phases = Vec::new();
+    for (below, spec) in specs {
+        let deck = match spec.deck {
+            Some(ids) => {
+                let deck = build_deck(entry, &ids, cards)?;
+                deck_ids = ids;
+                Some(deck)
+            }
+            None => None,
+        };
+        let behavior = match (&deck, spec.ai) {
+            (None, None) => None,
+            (_, ai) => Some(build_behavior(entry, &deck_ids, ai)?),
+        };
+        let passives = match spec.passives {
+            Some(names) => Some(passive_ids(entry, &names)?),
+            None => None,
+        };
+        let enrage = match spec.enrage {
+            Some(enrage) => {
+                let def = BuffRegistry::builtin()
+                    .by_name(&enrage.buff)
+                    .ok_or_else(|| unknown_reference(entry, &enrage.buff))?;
+                Some(Effect::ApplyBuff {
+                    target: EffectTarget::Caster,
+                    id: def.id.to_string(),
+                    stacks: enrage.stacks,
+                    duration: 0,
+                })
+            }
+            None => None,
+        };
+        phases.push(Phase {
+            below,
+            message: spec.message,
+            speed: spec.speed,
+            deck,
+            behavior,
+            passives,
+            enrage,
+            summon: spec.summon,
+        });
+    }
+    Ok(phases)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::GameData;
+    use crate::mechanics::buff::STRENGTH;
+    use crate::mechanics::combat::Combatant;
+    use crate::mechanics::passive::{COUNTER, UNDEAD_SHIELD};
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    fn parse(json: &str) -> Result<EnemyDef, CatalogError> {
+        let entry: EnemyInfo = serde_json::from_str(json).unwrap();
+        parse_enemy("test".to_string(), entry, &GameData::builtin().cards)
+    }
+
+    #[test]
+    fn builtin_roster_has_four_normal_enemies_two_elites_and_a_boss() {
+        let catalog = &GameData::builtin().enemies;
+        let mut rng = StdRng::seed_from_u64(7);
+        let mut seen = std::collections::BTreeSet::new();
+        for _ in 0..200 {
+            seen.insert(catalog.pick_normal(&mut rng).id.clone());
+        }
+        assert_eq!(
+            seen.into_iter().collect::<Vec<_>>(),
+            ["forest_wolf", "goblin_rogue", "skeleton_mage", "slime"]
+        );
+        assert_eq!(catalog.boss().name, "巨龙");
+        let elites: std::collections::BTreeSet<_> = (0..50)
+            .map(|_| catalog.pick_elite(&mut rng).id.as_str())
+            .collect();
+        assert_eq!(
+            elites.into_iter().collect::<Vec<_>>(),
+            ["giant_slime", "goblin_bandit"]
+        );
+        assert_eq!(catalog.boss().deck[0].name, "龙息");
+    }
+
+    #[test]
+    fn passives_come_from_codex_skills() {
+        let def = parse(
+            r#"{ "name": "石像鬼", "stats": { "生命值": 6, "速度": 1, "初始护盾": 2 },
+                 "skills": [
+                   { "name": "亡灵护盾", "type": "被动", "description": "开局获得护盾" },
+                   { "name": "俯冲", "type": "主动", "description": "造成伤害" }
+                 ] }"#,
+        )
+        .unwrap();
+        assert_eq!(def.starting_shield, 2);
+        assert_eq!(def.passives, [UNDEAD_SHIELD]);
+        assert_eq!(def.spawn().shield(), 2);
+    }
+
+    #[test]
+    fn deck_defaults_to_attack_with_uniform_ai() {
+        let def = parse(r#"{ "name": "幽灵", "stats": { "生命值": 2, "速度": 1 } }"#).unwrap();
+        assert_eq!(def.deck.len(), 1);
+        assert_eq!(def.deck[0].name, "攻击");
+        assert_eq!(
+            def.behavior,
+            Behavior::WeightedRandom(WeightedRandomAi::uniform(1))
+        );
+    }
+
+    #[test]
+    fn ai_refers_to_cards_by_id() {
+        let def = parse(
+            r#"{ "name": "石像鬼", "stats": { "生命值": 6, "速度": 1 },
+                 "deck": ["attack", "defense", "heal"],
+                 "ai": { "type": "hp_threshold",
+                         "rules": [ { "below": "50%", "card": "heal" } ],
+                         "weights": { "attack": 2, "defense": 1 } } }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            def.behavior,
+            Behavior::HpThreshold(HpThresholdAi::new(
+                vec![HpRule {
+                    below: 0.5,
+                    card: 2
+                }],
+                WeightedRandomAi::new(vec![2, 1, 0])
+            ))
+        );
+    }
+
+    #[test]
+    fn phases_are_sorted_and_keep_what_they_leave_out() {
+        let def = parse(
+            r#"{ "name": "石像鬼", "stats": { "生命值": 10, "速度": 1 },
+                 "deck": ["attack", "defense"],
+                 "phases": [
+                   { "below": "30%", "message": "狂暴", "ai": { "type": "scripted", "pattern": ["heal"] },
+                     "enrage": { "buff": "力量", "stacks": 2 } },
+                   { "below": 0.6, "message": "变形", "speed": 3, "deck": ["attack", "heal"],
+                     "passives": ["反击"] }
+                 ] }"#,
+        )
+        .unwrap();
+        assert_eq!(def.phases.len(), 2);
+        let (second, third) = (&def.phases[0], &def.phases[1]);
+        assert_eq!((second.below, second.message.as_str()), (0.6, "变形"));
+        assert_eq!(second.deck.as_ref().unwrap()[1].name, "治愈");
+        assert_eq!(
+            second.behavior,
+            Some(Behavior::WeightedRandom(WeightedRandomAi::uniform(2)))
+        );
+        assert_eq!(second.passives.as_deref(), Some(&[COUNTER.to_string()][..]));
+
+        assert!(third.deck.is_none() && third.speed.is_none());
+        assert_eq!(
+            third.behavior,
+            Some(Behavior::Scripted(ScriptedAi::new(vec![1])))
+        );
+        assert_eq!(
+            third.enrage,
+            Some(Effect::ApplyBuff {
+                target: EffectTarget::Caster,
+                id: STRENGTH.to_string(),
+                stacks: 2,
+                duration: 0,
+            })
+        );
+    }
+
+    #[test]
+    fn invalid_decks_and_ai_are_errors() {
+        assert!(matches!(
+            parse(r#"{ "name": "幽灵", "stats": { "速度": 1 } }"#),
+            Err(CatalogError::MissingStat {
+                key: "生命值", ..
+            })
+        ));
+        assert!(matches!(
+            parse(r#"{ "name": "幽灵", "stats": { "生命值": 2, "速度": 1 }, "deck": ["咒语"] }"#),
+            Err(CatalogError::UnknownReference { id, .. }) if id == "咒语"
+        ));
+        assert!(matches!(
+            parse(
+                r#"{ "name": "幽灵", "stats": { "生命值": 2, "速度": 1 },
+                     "ai": { "type": "scripted", "pattern": ["heal"] } }"#
+            ),
+            Err(CatalogError::UnknownReference { id, .. }) if id == "heal"
+        ));
+        assert!(matches!(
+            parse(
+                r#"{ "name": "幽灵", "stats": { "生命值": 2, "速度": 1 },
+                     "skills": [ { "name": "石肤", "type": "被动", "description": "" } ] }"#
+            ),
+            Err(CatalogError::UnknownReference { id, .. }) if id == "石肤"
+        ));
+        assert!(matches!(
+            parse(
+                r#"{ "name": "幽灵", "stats": { "生命值": 2, "速度": 1 },
+                     "phases": [ { "below": "50%", "message": "", "enrage": { "buff": "暴怒", "stacks": 1 } } ] }"#
+            ),
+            Err(CatalogError::UnknownReference { id, .. }) if id == "暴怒"
+        ));
+    }
+}
@@ -1,11 +1,341 @@
-pub mod dragon;
-pub mod forest_wolf;
-pub mod goblin_rogue;
-pub mod skeleton_mage;
-pub mod slime;
-
-pub use dragon::Dragon;
-pub use forest_wolf::ForestWolf;
-pub use goblin_rogue::GoblinRogue;
-pub use skeleton_mage::SkeletonMage;
-pub use slime::Slime;
+pub mod ai;
+pub mod catalog;
+pub mod phase;
+
+pub use ai::{Behavior, EnemyAi};
+pub use catalog::{EnemyCatalog, EnemyDef};
+pub use phase::Phase;
+
+use rand::rngs::StdRng;
+use serde::{Deserialize, Serialize};
+
+use crate::card::Card;
+use crate::mechanics::buff::BuffManager;
+use crate::mechanics::combat::Combatant;
+use crate::mechanics::passive;
+
+/// A monster in battle, spawned from an [`EnemyDef`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Enemy {
+    id: String,
+    name: String,
+    hp: i32,
+    max_hp: i32,
+    speed: i32,
+    shield: i32,
+    dodge_chance: f64,
+    /// Passive skill ids, from the codex entry.
+    passives: Vec<String>,
+    deck: Vec<Card>,
+    behavior: Behavior,
+    #[serde(default)]
+    buffs: BuffManager,
+    /// Gold taken from the player, handed back when this enemy is defeated.
+    #[serde(default)]
+    stolen_gold: i32,
+    /// Later stages of the fight, in the order they are entered.
+    #[serde(default)]
+    phases: Vec<Phase>,
+    /// How many of `phases` have begun.
+    #[serde(default)]
+    phase: usize,
+}
+
+impl Enemy {
+    /// The catalog id this enemy was spawned from.
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    pub fn deck(&self) -> &[Card] {
+        &self.deck
+    }
+
+    pub fn deck_mut(&mut self) -> &mut [Card] {
+        &mut self.deck
+    }
+
+    /// The current stage of the fight, counting from 1.
+    pub fn phase(&self) -> usize {
+        self.phase + 1
+    }
+
+    /// Stages in the whole fight, the opening one included.
+    pub fn phase_count(&self) -> usize {
+        self.phases.len() + 1
+    }
+
+    /// Moves to the next phase if HP has dropped far enough, applying its speed, deck,
+    /// AI and passives. Returns the phase so the caller can announce it and resolve its
+    /// enrage; call again in case HP crossed several thresholds at once.
+    pub fn enter_next_phase(&mut self) -> Option<Phase> {
+        let next = self.phases.get(self.phase)?;
+        if !next.is_reached(self.hp, self.max_hp) {
+            return None;
+        }
+        let next = next.clone();
+        self.phase += 1;
+        if let Some(speed) = next.speed {
+            self.speed = speed;
+        }
+        if let Some(deck) = &next.deck {
+            self.deck = deck.clone();
+        }
+        if let Some(behavior) = &next.behavior {
+            self.behavior = behavior.clone();
+        }
+        if let Some(passives) = &next.passives {
+            self.passives = passives.clone();
+        }
+        Some(next)
+    }
+
+    pub fn stolen_gold(&self) -> i32 {
+        self.stolen_gold
+    }
+
+    pub fn steal_gold(&mut self, amount: i32) {
+        self.stolen_gold += amount;
+    }
+
+    /// Empties the enemy's purse, returning what it held.
+    pub fn take_stolen_gold(&mut self) -> i32 {
+        std::mem::take(&mut self.stolen_gold)
+    }
+
+    /// A copy that fights on once this enemy falls: current HP as its max, same deck and
+    /// cooldowns, no buffs, shield or gold, and no [`passive::SPLIT`] so it cannot split again.
+    pub fn split_off(&self) -> Enemy {
+        Enemy {
+            name: format!("{}的分身", self.name),
+            max_hp: self.hp,
+            shield: 0,
+            passives: self
+                .passives
+                .iter()
+                .filter(|id| *id != passive::SPLIT)
+                .cloned()
+                .collect(),
+            buffs: BuffManager::new(),
+            stolen_gold: 0,
+            ..self.clone()
+        }
+    }
+
+    /// Commits the AI to the next card it will play within `horizon_ms`.
+    ///
+    /// Looks ahead to each moment a card comes off cooldown and asks the AI what it
+    /// would play then. Returns the deck index and how long until that card is ready.
+    pub fn plan_card(
+        &mut self,
+        horizon_ms: u64,
+        opponent_hp: i32,
+        rng: &mut StdRng,
+    ) -> Option<(usize, u64)> {
+        let mut ready_times: Vec<u64> = self
+            .deck
+            .iter()
+            .map(Card::remaining_cooldown_ms)
+            .filter(|&ms| ms <= horizon_ms)
+            .collect();
+        ready_times.sort_unstable();
+        ready_times.dedup();
+
+        for wait_ms in ready_times {
+            let mut view = self.deck.clone();
+            for card in &mut view {
+                card.tick_cooldown_ms(wait_ms);
+            }
+            let ctx = ai::AiContext {
+                deck: &view,
+                hp: self.hp,
+                max_hp: self.max_hp,
+                opponent_hp,
+            };
+            let choice = self
+                .behavior
+                .ai_mut()
+                .choose(&ctx, rng)
+                .filter(|&i| view.get(i).is_some_and(Card::is_ready));
+            if let Some(index) = choice {
+                return Some((index, wait_ms));
+            }
+        }
+        None
+    }
+}
+
+impl Combatant for Enemy {
+    fn name(&self) -> &str {
+        &self.name
+    }
+    fn hp(&self) -> i32 {
+        self.hp
+    }
+    fn max_hp(&self) -> i32 {
+        self.max_hp
+    }
+    fn speed(&self) -> i32 {
+        self.speed
+    }
+    fn shield(&self) -> i32 {
+        self.shield
+    }
+    fn add_shield(&mut self, amount: i32) {
+        self.shield += amount;
+    }
+    fn clear_shield(&mut self) {
+        self.shield = 0;
+    }
+    fn buffs(&self) -> &BuffManager {
+        &self.buffs
+    }
+    fn buffs_mut(&mut self) -> &mut BuffManager {
+        &mut self.buffs
+    }
+    fn take_damage(&mut self, amount: i32) {
+        let amount = self.buffs.damage_taken(amount);
+        let absorbed = amount.min(self.shield);
+        self.shield -= absorbed;
+        self.hp = (self.hp - (amount - absorbed)).max(0);
+    }
+    fn heal(&mut self, amount: i32) -> i32 {
+        let amount = self.buffs.heal_received(amount);
+        let before = self.hp;
+        self.hp = (self.hp + amount).min(self.max_hp);
+        self.hp - before
+    }
+    fn passives(&self) -> &[String] {
+        &self.passives
+    }
+    fn dodge_chance(&self) -> f64 {
+        (self.dodge_chance + passive::dodge_chance(&self.passives)).clamp(0.0, 1.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::GameData;
+    use crate::mechanics::passive::{COUNTER, DODGE_MASTER, DRAGON_SCALE, SWIFT, UNDEAD_SHIELD};
+    use rand::SeedableRng;
+
+    fn spawn(id: &str) -> Enemy {
+        GameData::builtin().enemies.get(id).unwrap().spawn()
+    }
+
+    #[test]
+    fn slime_takes_damage() {
+        let mut s = spawn("slime");
+        assert_eq!(s.name(), "史莱姆");
+        assert_eq!(s.hp(), 3);
+        s.take_damage(1);
+        assert_eq!(s.hp(), 2);
+        assert!(s.is_alive());
+    }
+
+    #[test]
+    fn goblin_rogue_dodges() {
+        let g = spawn("goblin_rogue");
+        assert_eq!(g.hp(), 4);
+        assert_eq!(g.speed(), 4);
+        assert!((g.dodge_chance() - 0.1).abs() < f64::EPSILON);
+        assert_eq!(g.passives(), [DODGE_MASTER]);
+    }
+
+    #[test]
+    fn skeleton_mage_shield_comes_from_its_passive() {
+        let s = spawn("skeleton_mage");
+        assert_eq!(s.hp(), 5);
+        assert_eq!(s.speed(), 2);
+        assert_eq!(s.shield(), 0, "护盾在战斗开始时由亡灵护盾给予");
+        assert_eq!(s.passives(), [UNDEAD_SHIELD]);
+    }
+
+    #[test]
+    fn forest_wolf_is_fast() {
+        let w = spawn("forest_wolf");
+        assert_eq!(w.hp(), 3);
+        assert_eq!(w.speed(), 5);
+        assert!((w.dodge_chance() - 0.15).abs() < f64::EPSILON);
+        assert_eq!(w.passives(), [SWIFT]);
+    }
+
+    #[test]
+    fn dragon_changes_phase_as_hp_drops() {
+        let mut d = spawn("dragon");
+        assert_eq!((d.phase(), d.phase_count()), (1, 3));
+        d.take_damage(3);
+        assert!(d.enter_next_phase().is_none(), "5/8 仍高于 60%");
+
+        d.take_damage(1);
+        let phase = d.enter_next_phase().expect("4/8 跌破 60%");
+        assert!(phase.enrage.is_none());
+        assert_eq!(d.phase(), 2);
+        assert_eq!(d.speed(), 5);
+        assert_eq!(d.deck().len(), 2);
+        assert_eq!(d.passives(), [DRAGON_SCALE, COUNTER]);
+        assert!(d.enter_next_phase().is_none());
+
+        d.take_damage(2);
+        assert!(d.enter_next_phase().unwrap().enrage.is_some());
+        assert_eq!((d.phase(), d.speed(), d.deck().len()), (3, 6, 2));
+        assert!(d.enter_next_phase().is_none(), "没有更多阶段");
+    }
+
+    #[test]
+    fn split_off_copies_current_hp_but_cannot_split_again() {
+        let mut slime = spawn("giant_slime");
+        assert_eq!(slime.passives(), [passive::SPLIT]);
+        slime.take_damage(4);
+        slime.steal_gold(2);
+        let copy = slime.split_off();
+        assert_eq!(copy.name(), "巨型史莱姆的分身");
+        assert_eq!((copy.hp(), copy.max_hp()), (2, 2));
+        assert!(copy.passives().is_empty());
+        assert_eq!(copy.stolen_gold(), 0);
+        assert_eq!(copy.deck().len(), slime.deck().len());
+    }
+
+    #[test]
+    fn goblin_bandit_keeps_stolen_gold_until_taken() {
+        let mut g = spawn("goblin_bandit");
+        assert_eq!(g.passives(), [DODGE_MASTER, passive::PICKPOCKET]);
+        g.steal_gold(1);
+        g.steal_gold(1);
+        assert_eq!(g.take_stolen_gold(), 2);
+        assert_eq!(g.stolen_gold(), 0);
+    }
+
+    #[test]
+    fn slime_heals_when_low() {
+        let mut s = spawn("slime");
+        let mut rng = StdRng::seed_from_u64(0);
+        let heal = s.deck().iter().position(|c| c.name == "治愈").unwrap();
+        s.take_damage(2);
+        assert_eq!(s.plan_card(5_000, 3, &mut rng), Some((heal, 0)));
+    }
+
+    #[test]
+    fn plan_waits_for_cooldowns_within_horizon() {
+        let mut g = spawn("goblin_rogue");
+        for card in g.deck_mut() {
+            card.set_initial_cooldown_ms(1_000);
+        }
+        let mut rng = StdRng::seed_from_u64(0);
+        assert_eq!(g.plan_card(500, 3, &mut rng), None);
+        assert!(matches!(g.plan_card(5_000, 3, &mut rng), Some((_, 1_000))));
+    }
+
+    #[test]
+    fn dragon_stats() {
+        let mut d = spawn("dragon");
+        assert_eq!(d.max_hp(), 8);
+        assert_eq!(d.speed(), 4);
+        assert!((d.dodge_chance() - 0.05).abs() < f64::EPSILON);
+        assert_eq!(d.passives(), [DRAGON_SCALE]);
+        d.take_damage(3);
+        assert_eq!(d.hp(), 5);
+        assert!(d.is_alive());
+    }
+}
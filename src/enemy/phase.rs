@@ -0,0 +1,33 @@
+use serde::{Deserialize, Serialize};
+
+use super::Behavior;
+use crate::card::Card;
+use crate::mechanics::effect::Effect;
+
+/// One stage of a multi-phase fight, entered the first time HP drops below `below` of
+/// max HP. Parts left as `None` stay as they were in the phase before.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Phase {
+    /// Fraction of max HP.
+    pub below: f64,
+    /// Telegraphs the phase to the player as it begins.
+    pub message: String,
+    pub speed: Option<i32>,
+    /// Replaces the deck; `behavior` is then always set to match it.
+    pub deck: Option<Vec<Card>>,
+    pub behavior: Option<Behavior>,
+    /// Passive ids replacing the current ones.
+    pub passives: Option<Vec<String>>,
+    /// Resolved by the enemy as the phase begins, e.g. a strength buff.
+    pub enrage: Option<Effect>,
+    /// Id of an enemy that joins the battle as the phase begins.
+    #[serde(default)]
+    pub summon: Option<String>,
+}
+
+impl Phase {
+    /// Whether an enemy at `hp` of `max_hp` has reached this phase.
+    pub fn is_reached(&self, hp: i32, max_hp: i32) -> bool {
+        f64::from(hp) < self.below * f64::from(max_hp)
+    }
+}
@@ -0,0 +1,1242 @@
+use std::io::{self, BufRead};
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+use std::time::Duration;
+
+use super::engine::{GameEngine, RewardItem, ShopItem};
+use super::event::{Command, EnemyIntent, GameEvent, Rejection, Side};
+use super::frontend::Frontend;
+use super::map::{EventOutcome, MapNode};
+use crate::card::CardEffect;
+use crate::character::player::MAX_SKILLS;
+use crate::mechanics::buff::BuffRegistry;
+use crate::mechanics::combat::Combatant;
+use crate::mechanics::passive::PassiveRegistry;
+use crate::relic::RelicRegistry;
+use crate::skill;
+
+/// The terminal front end: reads numbered choices from stdin and prints events in Chinese.
+pub struct CliFrontend {
+    input_rx: Receiver<String>,
+    /// Set when a card leaves or joins the hand, so the renumbered actions are shown
+    /// before the next command is read.
+    hand_changed: bool,
+}
+
+impl CliFrontend {
+    pub fn new() -> Self {
+        Self {
+            input_rx: Self::spawn_input_reader(),
+            hand_changed: false,
+        }
+    }
+
+    fn spawn_input_reader() -> Receiver<String> {
+        let (tx, rx) = mpsc::channel();
+        thread::spawn(move || {
+            let stdin = io::stdin();
+            for line in stdin.lock().lines().map_while(Result::ok) {
+                if tx.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+        rx
+    }
+
+    /// Asks which owned card a shop service is for; `None` goes back to the shelf.
+    fn choose_deck_card(&self, engine: &GameEngine, item: &ShopItem) -> Option<usize> {
+        let cards = service_cards(engine, item);
+        println!("{}", render_service_cards(engine, item));
+        loop {
+            println!("请输入选择（0-{}）：", cards.len());
+            match self.wait_for_input().trim().parse::<usize>() {
+                Ok(0) => return None,
+                Ok(n) if n <= cards.len() => return Some(cards[n - 1]),
+                _ => println!("无效输入。"),
+            }
+        }
+    }
+
+    /// Asks which equipped skill to give up for a new one; `None` goes back to the shelf.
+    fn choose_skill_to_replace(&self, engine: &GameEngine) -> Option<usize> {
+        let skills = &engine.player().skills;
+        println!("技能栏已满，选择要替换的技能（卖出的金币抵扣价格）：");
+        for (i, skill) in skills.iter().enumerate() {
+            let gold = engine.skill_sell_price(i).unwrap_or(0);
+            println!("  [{}] 「{}」：抵扣 {gold} 金币", i + 1, skill.name);
+        }
+        println!("  [0] 返回");
+        loop {
+            println!("请输入选择（0-{}）：", skills.len());
+            match self.wait_for_input().trim().parse::<usize>() {
+                Ok(0) => return None,
+                Ok(n) if n <= skills.len() => return Some(n - 1),
+                _ => println!("无效输入。"),
+            }
+        }
+    }
+
+    /// Asks which card or skill to sell; `None` goes back to the shelf.
+    fn choose_sale(&self, engine: &GameEngine) -> Option<Command> {
+        let sales = sale_commands(engine);
+        println!("{}", render_sales(engine));
+        loop {
+            println!("请输入选择（0-{}）：", sales.len());
+            match self.wait_for_input().trim().parse::<usize>() {
+                Ok(0) => return None,
+                Ok(n) if n <= sales.len() => return Some(sales[n - 1]),
+                _ => println!("无效输入。"),
+            }
+        }
+    }
+
+    fn wait_for_input(&self) -> String {
+        loop {
+            if let Ok(line) = self.input_rx.recv_timeout(Duration::from_millis(100)) {
+                return line;
+            }
+            // For piped input, check if channel is disconnected
+            thread::sleep(Duration::from_millis(50));
+        }
+    }
+}
+
+impl Default for CliFrontend {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Frontend for CliFrontend {
+    fn on_event(&mut self, engine: &GameEngine, event: &GameEvent) {
+        let text = render_event(engine, event);
+        if !text.is_empty() {
+            println!("{text}");
+        }
+        if matches!(
+            event,
+            GameEvent::CardPlayed {
+                side: Side::Player,
+                ..
+            } | GameEvent::CardsDrawn { .. }
+        ) {
+            self.hand_changed = true;
+        }
+    }
+
+    fn poll_command(&mut self, engine: &GameEngine) -> Option<Command> {
+        if std::mem::take(&mut self.hand_changed) && engine.player().is_alive() {
+            println!("{}", render_actions_brief(engine));
+        }
+        while let Ok(line) = self.input_rx.try_recv() {
+            match parse_battle_command(engine, &line) {
+                Ok(command) => return Some(command),
+                Err(msg) => println!("{msg}"),
+            }
+        }
+        None
+    }
+
+    fn shop_command(&mut self, engine: &GameEngine) -> Option<Command> {
+        // Even a sold-out shop still buys from the player, so only 0 leaves.
+        let offers = engine.shop_offers().len();
+        loop {
+            if offers == 0 {
+                println!("请输入选择（0 离开，s 出售）：");
+            } else {
+                println!("请输入选择（0-{}，s 出售）：", offers);
+            }
+            let line = self.wait_for_input();
+            if line.trim().eq_ignore_ascii_case("s") {
+                if let Some(command) = self.choose_sale(engine) {
+                    return Some(command);
+                }
+                println!("{}", render_offers(engine));
+                continue;
+            }
+            match line.trim().parse::<usize>() {
+                Ok(0) => {
+                    println!("继续冒险！\n");
+                    return None;
+                }
+                Ok(n) if n <= offers => {
+                    let offer = n - 1;
+                    let item = &engine.shop_offers()[offer].item;
+                    let command = if item.needs_card() {
+                        self.choose_deck_card(engine, item)
+                            .map(|card| Command::BuyService { offer, card })
+                    } else if item.is_skill() && engine.player().skills.len() >= MAX_SKILLS {
+                        self.choose_skill_to_replace(engine)
+                            .map(|skill| Command::ReplaceSkill { offer, skill })
+                    } else {
+                        Some(Command::BuyItem(offer))
+                    };
+                    if command.is_some() {
+                        return command;
+                    }
+                    println!("{}", render_offers(engine));
+                }
+                _ => println!("无效输入。"),
+            }
+        }
+    }
+
+    fn choose_path(&mut self, engine: &GameEngine) -> usize {
+        let choices = engine.map().choices().len();
+        if choices <= 1 {
+            return 0;
+        }
+        loop {
+            println!("请输入选择（1-{}）：", choices);
+            match self.wait_for_input().trim().parse::<usize>() {
+                Ok(n) if (1..=choices).contains(&n) => return n - 1,
+                _ => println!("无效输入。"),
+            }
+        }
+    }
+
+    fn choose_relic(&mut self, engine: &GameEngine) -> Option<usize> {
+        let offers = engine.relic_offers().len();
+        loop {
+            println!("请输入选择（0-{}）：", offers);
+            match self.wait_for_input().trim().parse::<usize>() {
+                Ok(0) => return None,
+                Ok(n) if n <= offers => return Some(n - 1),
+                _ => println!("无效输入。"),
+            }
+        }
+    }
+
+    fn choose_reward(&mut self, engine: &GameEngine) -> Option<usize> {
+        let offers = engine.reward_offers().len();
+        loop {
+            println!("请输入选择（0-{}）：", offers);
+            match self.wait_for_input().trim().parse::<usize>() {
+                Ok(0) => return None,
+                Ok(n) if n <= offers => return Some(n - 1),
+                _ => println!("无效输入。"),
+            }
+        }
+    }
+
+    fn choose_upgrade(&mut self, engine: &GameEngine) -> Option<usize> {
+        let cards = upgradable_cards(engine);
+        loop {
+            println!("请输入选择（0-{}）：", cards.len());
+            match self.wait_for_input().trim().parse::<usize>() {
+                Ok(0) => return None,
+                Ok(n) if n <= cards.len() => return Some(cards[n - 1]),
+                _ => println!("无效输入。"),
+            }
+        }
+    }
+
+    fn wants_to_suspend(&mut self, _engine: &GameEngine) -> bool {
+        println!("按回车进入下一站，输入 s 保存并退出：");
+        self.wait_for_input().trim().eq_ignore_ascii_case("s")
+    }
+}
+
+/// Maps a typed number to a card (listed first) or a skill (listed after the cards),
+/// optionally followed by the number of the enemy to aim at, e.g. "1 2".
+fn parse_battle_command(engine: &GameEngine, line: &str) -> Result<Command, String> {
+    let card_count = engine.player().hand().len();
+    let total_actions = card_count + engine.player().skills.len();
+    let enemy_count = engine.enemies().len();
+    let invalid = || {
+        let mut msg = format!("无效输入，请输入 1 到 {total_actions} 之间的数字");
+        if enemy_count > 1 {
+            msg.push_str(&format!(
+                "，可再加 1 到 {enemy_count} 的敌人编号指定目标（如 1 2）"
+            ));
+        }
+        msg.push('。');
+        msg
+    };
+
+    let numbers: Vec<_> = line
+        .split_whitespace()
+        .map(|word| word.parse::<usize>().ok())
+        .collect();
+    let (action, target) = match numbers[..] {
+        [Some(action)] => (action, None),
+        [Some(action), Some(target)] if (1..=enemy_count).contains(&target) => {
+            (action, Some(target - 1))
+        }
+        _ => return Err(invalid()),
+    };
+    match action {
+        n if n >= 1 && n <= card_count => Ok(Command::PlayCard {
+            card: n - 1,
+            target,
+        }),
+        n if n > card_count && n <= total_actions => Ok(Command::UseSkill {
+            skill: n - card_count - 1,
+            target,
+        }),
+        _ => Err(invalid()),
+    }
+}
+
+/// An enemy's name, numbered like the status lines when more than one is in the battle.
+fn enemy_name(engine: &GameEngine, index: usize) -> String {
+    let enemies = engine.enemies();
+    let name = enemies.get(index).map_or("？", |enemy| enemy.name());
+    if enemies.len() > 1 {
+        format!("{name}[{}]", index + 1)
+    } else {
+        name.to_string()
+    }
+}
+
+fn side_name(engine: &GameEngine, side: Side) -> String {
+    match side {
+        Side::Player => engine.player().name().to_string(),
+        Side::Enemy(index) => enemy_name(engine, index),
+    }
+}
+
+/// Icon and display name of a buff id; unknown ids show as-is.
+fn buff_label(id: &str) -> (&'static str, &str) {
+    match BuffRegistry::builtin().get(id) {
+        Some(def) => (def.icon, def.name),
+        None => ("✨", id),
+    }
+}
+
+/// Icon and display name of a relic id; unknown ids show as-is.
+fn relic_label(id: &str) -> (&'static str, &str) {
+    match RelicRegistry::builtin().get(id) {
+        Some(def) => (def.icon, def.name),
+        None => ("🎁", id),
+    }
+}
+
+/// Icon and display name of a passive id; unknown ids show as-is.
+fn passive_label(id: &str) -> (&'static str, &str) {
+    match PassiveRegistry::builtin().get(id) {
+        Some(def) => (def.icon, def.name),
+        None => ("✨", id),
+    }
+}
+
+/// Renders one event as terminal text. Empty when the event has nothing to show.
+fn render_event(engine: &GameEngine, event: &GameEvent) -> String {
+    match event {
+        GameEvent::RunStarted { floors, seed } => [
+            "╔══════════════════════════════════╗".to_string(),
+            "║     小二的回合制卡牌游戏          ║".to_string(),
+            "╚══════════════════════════════════╝".to_string(),
+            String::new(),
+            format!("📜 冒险开始！穿越 {floors} 层地图，尽头等待着 Boss！"),
+            format!("🎲 随机种子：{seed}（使用 --seed {seed} 可复现本局）"),
+        ]
+        .join("\n"),
+        GameEvent::RunResumed { stage } => format!("📂 读取存档成功，从第 {stage} 层继续冒险！"),
+        GameEvent::RunSuspended { stage } => format!("💾 冒险暂停，下次将从第 {stage} 层继续。"),
+        GameEvent::RouteOffered => render_route(engine),
+        GameEvent::NodeEntered { stage, kind } => {
+            format!("\n🧭 第 {stage} 层：{} {}", kind.icon(), kind.label())
+        }
+        GameEvent::UpgradeOffered => {
+            let deck = engine.player().deck().cards();
+            let mut lines = vec!["\n🔥 篝火旁可以打磨一张卡牌，或者休整一番：".to_string()];
+            for (i, index) in upgradable_cards(engine).into_iter().enumerate() {
+                let card = &deck[index];
+                if let Some(upgraded) = card.upgraded_version() {
+                    lines.push(format!(
+                        "  [{}] 升级「{}」→「{}」：{}",
+                        i + 1,
+                        card.name,
+                        upgraded.name,
+                        upgraded.description
+                    ));
+                }
+            }
+            lines.push("  [0] 休整（最大生命值 +1）".to_string());
+            lines.join("\n")
+        }
+        GameEvent::CardUpgraded { from, to } => format!("⬆️「{from}」升级为「{to}」！"),
+        GameEvent::Rested { max_hp } => {
+            let player = engine.player();
+            format!(
+                "🔥 你在篝火旁休整，最大生命值 +{max_hp}（{}/{}）",
+                player.hp(),
+                player.max_hp()
+            )
+        }
+        GameEvent::EventResolved(outcome) => {
+            let gold = engine.player().gold();
+            match outcome {
+                EventOutcome::FoundGold(amount) => {
+                    format!("❓ 你在路边捡到一个钱袋，获得 {amount} 金币！\n🪙 当前金币：{gold}")
+                }
+                EventOutcome::Blessing(amount) => {
+                    format!("❓ 古老的祭坛散发出温暖的光芒，最大生命值 +{amount}！")
+                }
+                EventOutcome::FoundRelic => "❓ 你在废墟中发现了一件遗物！".to_string(),
+                EventOutcome::Trap(0) => "❓ 你踩中了陷阱，幸好身上没有金币可丢。".to_string(),
+                EventOutcome::Trap(amount) => {
+                    format!("❓ 你踩中了陷阱，慌乱中丢失了 {amount} 金币。\n🪙 当前金币：{gold}")
+                }
+            }
+        }
+        GameEvent::BattleStarted { stage, is_boss } => render_welcome(engine, *stage, *is_boss),
+        GameEvent::RoundStarted { round } => render_round_start(engine, *round),
+        GameEvent::IntentDeclared(intent) => render_intent(engine, intent),
+        GameEvent::CardPlayed {
+            side: Side::Player,
+            name,
+        } => format!("\n▶ 你使用了「{name}」！"),
+        GameEvent::CardPlayed {
+            side: Side::Enemy(index),
+            name,
+        } => format!("\n▶ {} 使用了「{name}」！", enemy_name(engine, *index)),
+        GameEvent::DeckReshuffled => "  🔀 抽牌堆已空，弃牌堆重新洗入抽牌堆。".to_string(),
+        GameEvent::CardsDrawn { count: 0 } => "  🃏 没有可以抽的牌了。".to_string(),
+        GameEvent::CardsDrawn { count } => format!("  🃏 抽了 {count} 张牌！"),
+        GameEvent::CardExhausted { name } => {
+            format!("  🔥「{name}」被消耗，本场战斗不再出现。")
+        }
+        GameEvent::SkillUsed { name } => format!("\n▶ 你使用了技能「{name}」！"),
+        GameEvent::ActionRejected(reason) => render_rejection(reason),
+        GameEvent::Dodged { target } => format!("  💨 {} 闪避了攻击！", side_name(engine, *target)),
+        GameEvent::ShieldAbsorbed { target, amount } => format!(
+            "  🛡️ {}的护盾抵消了 {} 点伤害！",
+            side_name(engine, *target),
+            amount
+        ),
+        GameEvent::DamageDealt { target, amount } => format!(
+            "  对 {} 造成了 {} 点伤害！",
+            side_name(engine, *target),
+            amount
+        ),
+        GameEvent::AttackBlocked { .. } => "  攻击被完全抵挡！".to_string(),
+        GameEvent::ShieldGained {
+            target: Side::Player,
+            amount,
+        } => format!("  🛡️ 获得了 {amount} 点护盾！"),
+        GameEvent::ShieldGained {
+            target: Side::Enemy(index),
+            amount,
+        } => format!(
+            "  🛡️ {} 获得了 {amount} 点护盾！",
+            enemy_name(engine, *index)
+        ),
+        GameEvent::Healed {
+            target: Side::Player,
+            amount: 0,
+        } => "  ❤️ 生命值已满，未恢复。".to_string(),
+        GameEvent::Healed {
+            target: Side::Player,
+            amount,
+        } => format!("  ❤️ 恢复了 {amount} 点生命值！"),
+        GameEvent::Healed {
+            target: Side::Enemy(_),
+            amount: 0,
+        } => String::new(),
+        GameEvent::Healed {
+            target: Side::Enemy(index),
+            amount,
+        } => format!(
+            "  ❤️ {} 恢复了 {amount} 点生命值！",
+            enemy_name(engine, *index)
+        ),
+        GameEvent::CardCooldownsReduced {
+            side: Side::Player,
+            amount_ms,
+        } => format!(
+            "  🌀 当前所有卡牌冷却减少了 {} 秒！",
+            amount_ms.div_ceil(1_000)
+        ),
+        GameEvent::CardCooldownsReduced {
+            side: Side::Enemy(index),
+            amount_ms,
+        } => format!(
+            "  🌀 {} 的所有卡牌冷却减少了 {} 秒！",
+            enemy_name(engine, *index),
+            amount_ms.div_ceil(1_000)
+        ),
+        GameEvent::BuffApplied {
+            target,
+            buff,
+            stacks,
+        } => {
+            let (icon, name) = buff_label(buff);
+            format!(
+                "  {icon} {}获得了 {stacks} 层「{name}」！",
+                side_name(engine, *target)
+            )
+        }
+        GameEvent::BuffTriggered { target, buff } => {
+            let (icon, name) = buff_label(buff);
+            format!(
+                "  {icon} {}的「{name}」生效了！",
+                side_name(engine, *target)
+            )
+        }
+        GameEvent::BuffExpired {
+            target: Side::Player,
+            buff,
+        } => format!("  💨 「{}」效果消失了", buff_label(buff).1),
+        GameEvent::BuffExpired {
+            target: Side::Enemy(index),
+            buff,
+        } => format!(
+            "  💨 {} 的「{}」效果消失了",
+            enemy_name(engine, *index),
+            buff_label(buff).1
+        ),
+        GameEvent::DamageReflected { target, amount } => format!(
+            "  🌵 {} 点伤害被反弹给 {}！",
+            amount,
+            side_name(engine, *target)
+        ),
+        GameEvent::ActionSkipped { side, name, buff } => {
+            let (icon, buff) = buff_label(buff);
+            format!(
+                "\n{icon} {}处于「{buff}」状态，「{name}」落空了！",
+                side_name(engine, *side)
+            )
+        }
+        GameEvent::Idle { side: Side::Player } => "\n⌛ 你在本回合未行动。".to_string(),
+        GameEvent::Idle {
+            side: Side::Enemy(index),
+        } => {
+            format!("⌛ {} 在本回合未行动。", enemy_name(engine, *index))
+        }
+        GameEvent::PhaseChanged {
+            enemy,
+            phase,
+            message,
+        } => format!(
+            "\n🔥 {message}\n  ⚠️ {} 进入第 {phase} 阶段！（速度 {}）",
+            enemy_name(engine, *enemy),
+            engine.enemies()[*enemy].speed()
+        ),
+        GameEvent::EnemySplit { enemy } => {
+            format!("  🫧 {} 分裂了！", enemy_name(engine, *enemy))
+        }
+        GameEvent::EnemyJoined { enemy } => format!(
+            "  ⚔️ {} 加入了战斗！{}",
+            enemy_name(engine, *enemy),
+            engine.enemies()[*enemy].display_status()
+        ),
+        GameEvent::BattlefieldFull => "  ⛔ 战场上已站满敌人，援军无法加入。".to_string(),
+        GameEvent::GoldStolen { enemy, amount } => format!(
+            "  🫳 {} 偷走了 {amount} 金币！（剩余 {}）",
+            enemy_name(engine, *enemy),
+            engine.player().gold()
+        ),
+        GameEvent::GoldRecovered { amount } => {
+            format!("💰 夺回了被偷走的 {amount} 金币！")
+        }
+        GameEvent::BattleWon { .. } => [
+            "╔══════════════════════════════════╗",
+            "║          你胜利了！              ║",
+            "╚══════════════════════════════════╝",
+        ]
+        .join("\n"),
+        GameEvent::BattleLost { stage } => [
+            "╔══════════════════════════════════╗".to_string(),
+            "║          你被击败了…             ║".to_string(),
+            "╚══════════════════════════════════╝".to_string(),
+            format!("\n💀 在第 {stage} 层倒下了…"),
+        ]
+        .join("\n"),
+        GameEvent::GoldGained {
+            amount,
+            base,
+            bonus,
+        } => {
+            let gained = if *bonus > 0 {
+                format!("\n💰 获得了 {amount} 金币！（基础 {base} + 遗物 +{bonus}）")
+            } else {
+                format!("\n💰 获得了 {amount} 金币！")
+            };
+            format!("{gained}\n🪙 当前金币：{}", engine.player().gold())
+        }
+        GameEvent::BattleSummary => {
+            let mut lines = vec![
+                "\n最终状态：".to_string(),
+                format!("  {}", engine.player().display_status()),
+            ];
+            for enemy in engine.enemies() {
+                lines.push(format!("  {}", enemy.display_status()));
+            }
+            lines.join("\n")
+        }
+        GameEvent::RunCleared { gold } => {
+            format!("\n🏆 恭喜通关！你击败了所有敌人！\n🪙 最终金币：{gold}")
+        }
+        GameEvent::ShopOpened => format!(
+            "\n╔══════════════════════════════════╗\n\
+             ║           商  店                 ║\n\
+             ╚══════════════════════════════════╝\n\
+             🪙 当前金币：{}\n\n{}",
+            engine.player().gold(),
+            render_offers(engine)
+        ),
+        GameEvent::ItemBought { name } => {
+            let mut text = format!(
+                "✅ 购买了「{name}」！\n🪙 剩余金币：{}\n",
+                engine.player().gold()
+            );
+            if !engine.shop_offers().is_empty() {
+                text.push_str("\n还要继续购买吗？\n");
+                text.push_str(&render_offers(engine));
+            }
+            text
+        }
+        GameEvent::ItemSold { name, gold } => {
+            let mut text = format!(
+                "💰 卖出「{name}」，获得 {gold} 金币！\n🪙 当前金币：{}\n",
+                engine.player().gold()
+            );
+            text.push('\n');
+            text.push_str(&render_offers(engine));
+            text
+        }
+        GameEvent::CardRemoved { name } => format!("🗑️「{name}」被移出了牌组。"),
+        GameEvent::ShopSoldOut => format!("\n{}", render_offers(engine)),
+        GameEvent::ShopClosed => String::new(),
+        GameEvent::RewardOffered => {
+            let mut lines = vec!["\n🎴 战利品：选一张卡牌加入牌组".to_string()];
+            for (i, item) in engine.reward_offers().iter().enumerate() {
+                lines.push(format!("  [{}] {}", i + 1, render_reward(engine, item)));
+            }
+            lines.push("  [0] 跳过".to_string());
+            lines.join("\n")
+        }
+        GameEvent::RewardTaken { name } => format!("🎴「{name}」收入囊中！"),
+        GameEvent::RelicRewardOffered => {
+            let mut lines = vec!["\n🎁 选择一件遗物：".to_string()];
+            for (i, def) in engine.relic_offers().iter().enumerate() {
+                lines.push(format!(
+                    "  [{}] {} {} [{}] - {}",
+                    i + 1,
+                    def.icon,
+                    def.name,
+                    def.tier.label(),
+                    def.description
+                ));
+            }
+            lines.push("  [0] 跳过".to_string());
+            lines.join("\n")
+        }
+        GameEvent::RelicGained { relic } => {
+            let description = RelicRegistry::builtin()
+                .get(relic)
+                .map_or("", |def| def.description);
+            let (icon, name) = relic_label(relic);
+            format!("{icon} 获得遗物「{name}」：{description}")
+        }
+        GameEvent::RelicTriggered { relic } => {
+            let (icon, name) = relic_label(relic);
+            format!("  {icon} 遗物「{name}」生效！")
+        }
+        GameEvent::PassiveTriggered { side, passive } => {
+            let (icon, name) = passive_label(passive);
+            format!(
+                "  {icon} {} 的被动「{name}」生效！",
+                side_name(engine, *side)
+            )
+        }
+    }
+}
+
+fn render_rejection(reason: &Rejection) -> String {
+    match reason {
+        Rejection::NoSuchAction => "无效输入。".to_string(),
+        Rejection::NotEnoughEnergy { name, cost, energy } => {
+            format!("\n⚡ 能量不足！「{name}」需要 {cost} 点能量，当前 {energy} 点。本回合仍可使用技能。")
+        }
+        Rejection::OnCooldown {
+            name,
+            remaining_secs,
+        } => format!("\n⏳ 「{name}」仍在冷却中（剩余 {remaining_secs} 秒）。"),
+        Rejection::NotEnoughGold { price, gold } => {
+            format!("❌ 金币不足！需要 {price} 金币，当前 {gold} 金币。")
+        }
+        Rejection::NotEnoughMana { name, cost, mana } => {
+            format!("\n🔮 法力不足！「{name}」需要 {cost} 点法力，当前 {mana} 点。")
+        }
+        Rejection::NoSuchTarget { target } => {
+            format!("🎯 没有 {} 号敌人，或它已被击败。", target + 1)
+        }
+        Rejection::DeckTooSmall { min } => {
+            format!("❌ 牌组至少要保留 {min} 张牌，无法出售。")
+        }
+        Rejection::SkillSlotsFull { max } => {
+            format!("❌ 技能栏已满（最多{max}个），无法装备。金币已退还。")
+        }
+    }
+}
+
+fn render_offers(engine: &GameEngine) -> String {
+    let mut text = String::new();
+    if engine.shop_offers().is_empty() {
+        text.push_str("  商店已售罄！\n");
+    }
+    for (i, offer) in engine.shop_offers().iter().enumerate() {
+        let sale = if offer.on_sale { " 🏷️特价" } else { "" };
+        text.push_str(&format!(
+            "  [{}] {} （💰{}金币）{sale}\n",
+            i + 1,
+            offer.description,
+            offer.price
+        ));
+    }
+    text.push_str("  [s] 出售卡牌或卸下技能\n");
+    text.push_str("  [0] 离开商店，继续冒险\n");
+    text
+}
+
+/// One reward choice: a card with its rarity and costs, or the skill or relic offered instead.
+fn render_reward(engine: &GameEngine, item: &RewardItem) -> String {
+    match item {
+        RewardItem::Card(id) => match engine.data().cards.get(id) {
+            Some(card) => {
+                let mut cost = format!("，{}能量", card.energy_cost);
+                if card.is_spell() {
+                    cost.push_str(&format!("，{}法力", card.mana_cost));
+                }
+                format!(
+                    "🃏 {} [{}] - {}（{}秒冷却{cost}）",
+                    card.name,
+                    card.rarity.label(),
+                    card.description,
+                    card.cooldown_ms().div_ceil(1_000)
+                )
+            }
+            None => format!("🃏 {id}"),
+        },
+        RewardItem::Skill(id) => match skill::create(id) {
+            Some(skill) => format!(
+                "📜 技能「{}」 - {}（{}秒冷却）",
+                skill.name,
+                skill.description,
+                skill.cooldown_ms.div_ceil(1_000)
+            ),
+            None => format!("📜 {id}"),
+        },
+        RewardItem::Relic(id) => match RelicRegistry::builtin().get(id) {
+            Some(def) => format!(
+                "{} 遗物「{}」 [{}] - {}",
+                def.icon,
+                def.name,
+                def.tier.label(),
+                def.description
+            ),
+            None => format!("🎁 {id}"),
+        },
+    }
+}
+
+/// Deck indices of the cards a rest site can upgrade, one per card name.
+fn upgradable_cards(engine: &GameEngine) -> Vec<usize> {
+    service_cards(engine, &ShopItem::Upgrade)
+}
+
+/// Deck indices of the cards a card service can be bought for, one per card name.
+fn service_cards(engine: &GameEngine, item: &ShopItem) -> Vec<usize> {
+    let deck = engine.player().deck().cards();
+    (0..deck.len())
+        .filter(|&i| *item != ShopItem::Upgrade || deck[i].upgraded_version().is_some())
+        .filter(|&i| !deck[..i].iter().any(|c| c.name == deck[i].name))
+        .collect()
+}
+
+/// What each numbered line of [`render_sales`] sells: cards first, one per name, then skills.
+fn sale_commands(engine: &GameEngine) -> Vec<Command> {
+    let cards = service_cards(engine, &ShopItem::RemoveCard).into_iter();
+    cards
+        .map(Command::SellCard)
+        .chain((0..engine.player().skills.len()).map(Command::SellSkill))
+        .collect()
+}
+
+/// The numbered cards and skills the shop would buy back, with what it pays.
+fn render_sales(engine: &GameEngine) -> String {
+    let player = engine.player();
+    let mut lines = vec!["出售卡牌或卸下技能：".to_string()];
+    for (i, command) in sale_commands(engine).into_iter().enumerate() {
+        lines.push(match command {
+            Command::SellCard(index) => format!(
+                "  [{}] 卡牌「{}」：卖出得 {} 金币",
+                i + 1,
+                player.deck().cards()[index].name,
+                engine.card_sell_price(index).unwrap_or(0)
+            ),
+            Command::SellSkill(index) => format!(
+                "  [{}] 技能「{}」：卸下并卖出得 {} 金币",
+                i + 1,
+                player.skills[index].name,
+                engine.skill_sell_price(index).unwrap_or(0)
+            ),
+            _ => continue,
+        });
+    }
+    lines.push("  [0] 返回".to_string());
+    lines.join("\n")
+}
+
+/// The numbered cards a shop service can be bought for.
+fn render_service_cards(engine: &GameEngine, item: &ShopItem) -> String {
+    let deck = engine.player().deck().cards();
+    let mut lines = vec![match item {
+        ShopItem::Upgrade => "选择要升级的卡牌：".to_string(),
+        _ => "选择要移除的卡牌：".to_string(),
+    }];
+    for (i, index) in service_cards(engine, item).into_iter().enumerate() {
+        let card = &deck[index];
+        lines.push(match card.upgraded_version() {
+            Some(upgraded) if *item == ShopItem::Upgrade => format!(
+                "  [{}] 「{}」→「{}」：{}",
+                i + 1,
+                card.name,
+                upgraded.name,
+                upgraded.description
+            ),
+            _ => format!("  [{}] 「{}」：{}", i + 1, card.name, card.description),
+        });
+    }
+    lines.push("  [0] 返回".to_string());
+    lines.join("\n")
+}
+
+/// The whole map with the current node in brackets, then the numbered next steps.
+fn render_route(engine: &GameEngine) -> String {
+    let map = engine.map();
+    let node_name = |node: &MapNode| format!("{}{}", node.kind.icon(), node.kind.label());
+    let mut lines = vec!["\n🗺️ 冒险地图：".to_string()];
+    for (floor, nodes) in map.floors().iter().enumerate() {
+        let cells: Vec<String> = nodes
+            .iter()
+            .enumerate()
+            .map(|(i, node)| {
+                if map.position() == Some((floor, i)) {
+                    format!("【{}】", node_name(node))
+                } else {
+                    node_name(node)
+                }
+            })
+            .collect();
+        lines.push(format!("  第 {} 层：{}", floor + 1, cells.join("  ")));
+    }
+
+    lines.push("\n选择下一站：".to_string());
+    let floors = map.floors();
+    let next = map.next_floor();
+    for (n, &index) in map.choices().iter().enumerate() {
+        let node = &floors[next][index];
+        let mut line = format!("  [{}] {}", n + 1, node_name(node));
+        let onward: Vec<String> = node
+            .next
+            .iter()
+            .filter_map(|&j| floors.get(next + 1)?.get(j))
+            .map(node_name)
+            .collect();
+        if !onward.is_empty() {
+            line.push_str(&format!(" → {}", onward.join("、")));
+        }
+        lines.push(line);
+    }
+    lines.join("\n")
+}
+
+fn render_welcome(engine: &GameEngine, stage: u32, is_boss: bool) -> String {
+    let player = engine.player();
+    let enemies = engine.enemies();
+    let names: Vec<_> = (0..enemies.len()).map(|i| enemy_name(engine, i)).collect();
+    let foes = names.join("、");
+    let mut lines = Vec::new();
+    if is_boss {
+        lines.push("╔══════════════════════════════════╗".to_string());
+        lines.push("║        Boss 战！                 ║".to_string());
+        lines.push("╚══════════════════════════════════╝".to_string());
+        lines.push(format!("⚔️ Boss 战！ {} vs {foes}", player.name()));
+    } else if engine.is_elite_stage() {
+        lines.push(format!(
+            "\n👹 第 {} 层精英战！ {} vs {foes}",
+            stage,
+            player.name()
+        ));
+    } else {
+        lines.push(format!(
+            "\n⚔️ 第 {} 层！ {} vs {foes}",
+            stage,
+            player.name()
+        ));
+    }
+    let mut speeds = vec![format!("{}={}", player.name(), player.speed())];
+    speeds.extend(
+        enemies
+            .iter()
+            .zip(&names)
+            .map(|(enemy, name)| format!("{name}={}", enemy.speed())),
+    );
+    lines.push(format!("⚙️ 速度：{}", speeds.join("，")));
+
+    let mut passives: Vec<&str> = Vec::new();
+    for id in enemies.iter().flat_map(|enemy| enemy.passives()) {
+        if !passives.contains(&id.as_str()) {
+            passives.push(id);
+        }
+    }
+    for def in passives
+        .iter()
+        .filter_map(|id| PassiveRegistry::builtin().get(id))
+    {
+        lines.push(format!(
+            "✨ 敌方被动：{} {} - {}",
+            def.icon, def.name, def.description
+        ));
+    }
+    for (enemy, name) in enemies.iter().zip(&names) {
+        if enemy.phase_count() > 1 {
+            lines.push(format!(
+                "🔥 {name} 共有 {} 个阶段，生命值越低越危险！",
+                enemy.phase_count()
+            ));
+        }
+    }
+    if enemies.len() > 1 {
+        lines.push("🎯 敌人不止一个：输入「卡牌编号 敌人编号」指定目标，如 1 2。".to_string());
+    }
+    lines.join("\n")
+}
+
+/// e.g. "⚠️ 史莱姆 准备攻击 (1) 约 3 秒后".
+fn render_intent(engine: &GameEngine, intent: &EnemyIntent) -> String {
+    let action = match intent.effect.primary() {
+        CardEffect::Damage(_) | CardEffect::DamageAll(_) => {
+            format!("准备攻击 ({})", intent.effect.damage())
+        }
+        CardEffect::Shield(n) => format!("准备防御 ({n})"),
+        CardEffect::Heal(n) => format!("准备治疗 ({n})"),
+        CardEffect::ApplyBuff { id, stacks, .. } => {
+            format!("准备施加「{}」 ({stacks})", buff_label(id).1)
+        }
+        CardEffect::ReduceAllCardCooldownMs(_)
+        | CardEffect::DrawCards(_)
+        | CardEffect::GainGold(_)
+        | CardEffect::StealGold(_)
+        | CardEffect::Split
+        | CardEffect::Composite(_) => "准备行动".to_string(),
+    };
+    let when = match (intent.delay.as_millis() as f64 / 1_000.0).round() as u64 {
+        0 => "即将出手".to_string(),
+        secs => format!("约 {secs} 秒后"),
+    };
+    format!("⚠️ {} {action} {when}", enemy_name(engine, intent.enemy))
+}
+
+fn render_round_start(engine: &GameEngine, round: u32) -> String {
+    let player = engine.player();
+    let mut lines = vec![
+        format!(
+            "\n┌─── 第 {} 回合（{} 秒） ───┐",
+            round,
+            engine.round_duration().as_secs()
+        ),
+        format!("│  {}", player.display_status()),
+    ];
+    let enemies = engine.enemies();
+    for index in engine.living_enemies() {
+        let status = enemies[index].display_status();
+        lines.push(if enemies.len() == 1 {
+            format!("│  {status}")
+        } else if index == engine.target() {
+            format!("│  [{}] {status} 🎯", index + 1)
+        } else {
+            format!("│  [{}] {status}", index + 1)
+        });
+    }
+    lines.push(format!(
+        "│  ⚡ 能量：{}/{}",
+        player.energy(),
+        player.max_energy()
+    ));
+    if !player.relics().is_empty() {
+        let relics: Vec<_> = player
+            .relic_defs()
+            .map(|def| format!("{}{}", def.icon, def.name))
+            .collect();
+        lines.push(format!("│  🎒 遗物：{}", relics.join(" ")));
+    }
+    if player.has_spell_cards() {
+        lines.push(format!(
+            "│  🔮 法力：{}/{}",
+            player.mana(),
+            player.max_mana()
+        ));
+    }
+    let deck = player.deck();
+    lines.push(format!(
+        "│  🂠 抽牌堆 {} · 弃牌堆 {} · 消耗 {}",
+        deck.draw_pile().len(),
+        deck.discard_pile().len(),
+        deck.exhaust_pile().len()
+    ));
+    lines.push("└──────────────────────────┘".to_string());
+    lines.push("\n你的手牌：".to_string());
+    for (i, card) in player.hand().iter().enumerate() {
+        let status = if !card.is_ready() {
+            format!("冷却 {} 秒", card.remaining_cooldown_secs())
+        } else if player.energy() < card.energy_cost {
+            "能量不足".to_string()
+        } else if !player.can_afford(card) {
+            "法力不足".to_string()
+        } else {
+            "可用".to_string()
+        };
+        let mut cost = format!(" ⚡{}", card.energy_cost);
+        if card.is_spell() {
+            cost.push_str(&format!(" 🔮{}", card.mana_cost));
+        }
+        lines.push(format!("  [{}] {}{} [{}]", i + 1, card, cost, status));
+    }
+
+    if !player.skills.is_empty() {
+        lines.push("\n你的技能：".to_string());
+        let offset = player.hand().len();
+        for (i, skill) in player.skills.iter().enumerate() {
+            lines.push(format!("  [{}] {}", offset + i + 1, skill));
+        }
+    }
+    lines.push(String::new());
+    lines.push(format!(
+        "⏱️ 本回合持续 {} 秒：出牌消耗能量，能量每回合开始时回满；技能不消耗能量，可与卡牌同回合使用。回合结束时没打出的手牌进入弃牌堆。",
+        engine.round_duration().as_secs()
+    ));
+    lines.join("\n")
+}
+
+/// One line of what can be played now, renumbered after the hand changed,
+/// e.g. "  👉 [1] 防御 [2] 攻击 · 技能 [3] 紧急救治".
+fn render_actions_brief(engine: &GameEngine) -> String {
+    let player = engine.player();
+    let hand = player.hand();
+    let mut line = if hand.is_empty() {
+        "  👉 手牌已打完".to_string()
+    } else {
+        let cards: Vec<_> = hand
+            .iter()
+            .enumerate()
+            .map(|(i, card)| format!("[{}] {}", i + 1, card.name))
+            .collect();
+        format!("  👉 {}", cards.join(" "))
+    };
+    if !player.skills.is_empty() {
+        let skills: Vec<_> = player
+            .skills
+            .iter()
+            .enumerate()
+            .map(|(i, skill)| format!("[{}] {}", hand.len() + i + 1, skill.name))
+            .collect();
+        line.push_str(&format!(" · 技能 {}", skills.join(" ")));
+    }
+    line
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::GameData;
+
+    /// A fresh run with its starting deck dealt into the hand in order, plus `extra` cards.
+    fn dealt_engine(extra: &[&str]) -> GameEngine {
+        let data = GameData::builtin().clone();
+        let mut save = GameEngine::with_seed(1).snapshot();
+        for id in extra {
+            save.player.add_card(data.cards.card(id));
+        }
+        let cards = save.player.deck().cards().to_vec();
+        for card in cards {
+            save.player.deck_mut().add_to_hand(card);
+        }
+        GameEngine::from_save(save, data)
+    }
+
+    #[test]
+    fn numbers_map_to_cards_then_skills() {
+        let engine = dealt_engine(&[]);
+        let cards = engine.player().hand().len();
+        assert_eq!(
+            parse_battle_command(&engine, "1"),
+            Ok(Command::PlayCard {
+                card: 0,
+                target: None
+            })
+        );
+        assert_eq!(
+            parse_battle_command(&engine, &(cards + 1).to_string()),
+            Ok(Command::UseSkill {
+                skill: 0,
+                target: None
+            })
+        );
+        assert!(parse_battle_command(&engine, "0").is_err());
+        assert!(parse_battle_command(&engine, "abc").is_err());
+        assert!(parse_battle_command(&engine, "99").is_err());
+    }
+
+    #[test]
+    fn a_second_number_picks_the_target() {
+        let engine = dealt_engine(&[]);
+        assert_eq!(
+            parse_battle_command(&engine, " 2  1 "),
+            Ok(Command::PlayCard {
+                card: 1,
+                target: Some(0)
+            })
+        );
+        assert!(
+            parse_battle_command(&engine, "1 2").is_err(),
+            "只有一个敌人"
+        );
+        assert!(parse_battle_command(&engine, "1 0").is_err());
+        assert!(parse_battle_command(&engine, "1 1 1").is_err());
+    }
+
+    #[test]
+    fn damage_event_names_the_target() {
+        let engine = GameEngine::with_seed(1);
+        let text = render_event(
+            &engine,
+            &GameEvent::DamageDealt {
+                target: Side::Enemy(0),
+                amount: 2,
+            },
+        );
+        assert_eq!(
+            text,
+            format!("  对 {} 造成了 2 点伤害！", engine.enemies()[0].name())
+        );
+    }
+
+    #[test]
+    fn full_hp_heal_is_reported_for_player_only() {
+        let engine = GameEngine::with_seed(1);
+        let player = render_event(
+            &engine,
+            &GameEvent::Healed {
+                target: Side::Player,
+                amount: 0,
+            },
+        );
+        let enemy = render_event(
+            &engine,
+            &GameEvent::Healed {
+                target: Side::Enemy(0),
+                amount: 0,
+            },
+        );
+        assert!(player.contains("生命值已满"));
+        assert!(enemy.is_empty());
+    }
+
+    #[test]
+    fn intent_shows_action_amount_and_timing() {
+        let engine = GameEngine::with_seed(1);
+        let name = engine.enemies()[0].name().to_string();
+        let intent = |effect, delay_ms| {
+            render_event(
+                &engine,
+                &GameEvent::IntentDeclared(EnemyIntent {
+                    enemy: 0,
+                    card: 0,
+                    card_name: "攻击".to_string(),
+                    effect,
+                    delay: Duration::from_millis(delay_ms),
+                }),
+            )
+        };
+        assert_eq!(
+            intent(CardEffect::Damage(1), 2_600),
+            format!("⚠️ {name} 准备攻击 (1) 约 3 秒后")
+        );
+        assert_eq!(
+            intent(CardEffect::Heal(2), 300),
+            format!("⚠️ {name} 准备治疗 (2) 即将出手")
+        );
+    }
+
+    #[test]
+    fn spell_costs_and_mana_show_at_round_start() {
+        let engine = GameEngine::with_seed(1);
+        let text = render_round_start(&engine, 1);
+        assert!(!text.contains("法力"), "没有法术时不显示法力");
+
+        let engine = dealt_engine(&["fireball"]);
+        let text = render_round_start(&engine, 1);
+        assert!(text.contains("🔮 法力：100/100"));
+        assert!(text.contains("火球术 - 造成 2 点伤害 ⚡1 🔮40 [可用]"));
+        assert!(text.contains("🂠 抽牌堆 0 · 弃牌堆 0 · 消耗 0"));
+    }
+
+    #[test]
+    fn rest_sites_list_each_upgradable_card_once() {
+        let engine = dealt_engine(&["heal"]);
+        assert_eq!(upgradable_cards(&engine), [0, 3, 6]);
+        let text = render_event(&engine, &GameEvent::UpgradeOffered);
+        assert!(text.contains("  [1] 升级「攻击」→「攻击+」：造成 2 点伤害"));
+        assert!(text.contains("  [3] 升级「治愈」→「治愈+」：恢复 2 点生命值"));
+        assert!(text.ends_with("  [0] 休整（最大生命值 +1）"));
+    }
+
+    #[test]
+    fn brief_actions_follow_the_current_hand() {
+        assert_eq!(
+            render_actions_brief(&dealt_engine(&["heal"])),
+            "  👉 [1] 攻击 [2] 攻击 [3] 攻击 [4] 防御 [5] 防御 [6] 防御 [7] 治愈 · 技能 [8] 紧急救治 [9] 快速循环"
+        );
+        assert!(render_actions_brief(&GameEngine::with_seed(1))
+            .starts_with("  👉 手牌已打完 · 技能 [1]"));
+    }
+
+    #[test]
+    fn rewards_show_rarity_and_what_each_choice_is() {
+        let engine = dealt_engine(&[]);
+        assert_eq!(
+            render_reward(&engine, &RewardItem::Card("critical_strike".to_string())),
+            "🃏 暴击 [稀有] - 造成 2 点伤害（5秒冷却，2能量）"
+        );
+        assert_eq!(
+            render_reward(&engine, &RewardItem::Skill("war_cry")),
+            "📜 技能「战吼」 - 获得 2 点护盾（12秒冷却）"
+        );
+        assert!(render_reward(&engine, &RewardItem::Relic("merchant_badge")).contains("遗物「"));
+    }
+
+    #[test]
+    fn shop_services_list_each_card_once() {
+        let engine = dealt_engine(&["heal"]);
+        assert_eq!(
+            render_service_cards(&engine, &ShopItem::RemoveCard),
+            "选择要移除的卡牌：\n  [1] 「攻击」：造成 1 点伤害\n  [2] 「防御」：获得 1 点护盾，持续 1 回合\n  [3] 「治愈」：恢复 1 点生命值\n  [0] 返回"
+        );
+        assert!(render_service_cards(&engine, &ShopItem::Upgrade)
+            .contains("  [1] 「攻击」→「攻击+」：造成 2 点伤害"));
+    }
+
+    #[test]
+    fn sales_list_cards_once_then_skills() {
+        let engine = dealt_engine(&["critical_strike"]);
+        assert_eq!(
+            sale_commands(&engine),
+            [
+                Command::SellCard(0),
+                Command::SellCard(3),
+                Command::SellCard(6),
+                Command::SellSkill(0),
+                Command::SellSkill(1)
+            ]
+        );
+        let text = render_sales(&engine);
+        assert!(text.contains("  [3] 卡牌「暴击」：卖出得 2 金币"));
+        assert!(text.contains("  [4] 技能「紧急救治」：卸下并卖出得 2 金币"));
+    }
+}
@@ -0,0 +1,97 @@
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Source of time for the real-time battle loop.
+///
+/// The engine only ever asks "how long since the clock started" and "wait this long",
+/// so a simulated clock can drive battles as fast as the CPU allows.
+pub trait Clock {
+    /// Time elapsed since the clock was created.
+    fn now(&self) -> Duration;
+
+    /// Waits for `duration` to pass.
+    fn sleep(&mut self, duration: Duration);
+}
+
+/// Wall-clock time: `sleep` really blocks the thread.
+pub struct RealClock {
+    start: Instant,
+}
+
+impl RealClock {
+    pub fn new() -> Self {
+        Self {
+            start: Instant::now(),
+        }
+    }
+}
+
+impl Default for RealClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clock for RealClock {
+    fn now(&self) -> Duration {
+        self.start.elapsed()
+    }
+
+    fn sleep(&mut self, duration: Duration) {
+        thread::sleep(duration);
+    }
+}
+
+/// Simulated time: `sleep` advances the clock instantly by exactly the requested step.
+#[derive(Debug, Default)]
+pub struct ManualClock {
+    now: Duration,
+}
+
+impl ManualClock {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Moves the clock forward without being asked to sleep.
+    pub fn advance(&mut self, duration: Duration) {
+        self.now += duration;
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> Duration {
+        self.now
+    }
+
+    fn sleep(&mut self, duration: Duration) {
+        self.advance(duration);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn manual_clock_starts_at_zero() {
+        let clock = ManualClock::new();
+        assert_eq!(clock.now(), Duration::ZERO);
+    }
+
+    #[test]
+    fn manual_clock_sleep_advances_exactly() {
+        let mut clock = ManualClock::new();
+        clock.sleep(Duration::from_millis(100));
+        clock.advance(Duration::from_millis(50));
+        assert_eq!(clock.now(), Duration::from_millis(150));
+    }
+
+    #[test]
+    fn real_clock_is_monotonic() {
+        let clock = RealClock::new();
+        let a = clock.now();
+        let b = clock.now();
+        assert!(b >= a);
+    }
+}
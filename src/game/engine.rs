@@ -1,979 +1,3822 @@
-use std::io::{self, BufRead};
-use std::sync::mpsc::{self, Receiver};
-use std::thread;
-use std::time::{Duration, Instant};
-
-use rand::Rng;
-
-use crate::card::attack::create_attack_card;
-use crate::card::critical_strike::create_critical_strike_card;
-use crate::card::defense::create_defense_card;
-use crate::card::heal::create_heal_card;
-use crate::card::{Card, CardEffect};
-use crate::character::player::PassiveSkill;
+use std::time::Duration;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::card::{Card, CardRarity};
+use crate::character::player::{HAND_SIZE, MANA_REGEN_PER_ROUND, MAX_SKILLS};
 use crate::character::Player;
-use crate::enemy::{Dragon, ForestWolf, GoblinRogue, SkeletonMage, Slime};
+use crate::data::GameData;
+use crate::enemy::{Enemy, EnemyDef};
+use crate::game::clock::{Clock, RealClock};
+use crate::game::event::{Command, CommandOutcome, EnemyIntent, GameEvent, Rejection, Side};
+use crate::game::frontend::Frontend;
+use crate::game::map::{EventOutcome, NodeKind, RunMap};
+use crate::game::save::{SaveData, SAVE_VERSION};
+use crate::mechanics::buff::RoundStartEffect;
 use crate::mechanics::combat::Combatant;
+use crate::mechanics::effect::{Effect, EffectTarget};
+use crate::mechanics::passive::{
+    PassiveContext, PassiveHook, PassiveHooks, PassiveRegistry, PREPARED,
+};
+use crate::relic::{
+    RelicContext, RelicDef, RelicHook, RelicHooks, RelicModifiers, RelicRegistry, RelicTier,
+};
 use crate::skill::emergency_heal::create_emergency_heal;
 use crate::skill::fast_cycle::create_fast_cycle;
-use crate::skill::vampiric_touch::create_vampiric_touch;
-use crate::skill::war_cry::create_war_cry;
-use crate::skill::SkillEffect;
+use crate::skill::{self, LEARNABLE_SKILLS};
 
-const ROUND_DURATION: Duration = Duration::from_secs(5);
+pub const ROUND_DURATION: Duration = Duration::from_secs(5);
 const LOOP_TICK: Duration = Duration::from_millis(100);
 const PLAYER_INITIAL_CARD_COOLDOWN_MS: u64 = 1_000;
 const ENEMY_INITIAL_CARD_COOLDOWN_MS: u64 = 2_000;
-const STAGES_BEFORE_BOSS: u32 = 3;
-
-/// Drives the main game loop: multiple stages of battle, shop between stages, boss at the end.
+/// Card ids and copies every run starts with; the ids are the
+/// [`CORE_CARDS`](crate::card::catalog::CORE_CARDS) every catalog has to define.
+const STARTING_DECK: [(&str, usize); 2] = [("attack", 3), ("defense", 3)];
+/// How many cards the post-battle reward lets the player choose between.
+const CARD_REWARD_CHOICES: usize = 3;
+/// Chance that the last of those is swapped for a skill or a relic.
+const ALTERNATIVE_REWARD_CHANCE: f64 = 0.2;
+/// Chance that winning a normal battle offers a relic.
+const NORMAL_RELIC_REWARD_CHANCE: f64 = 0.4;
+/// How many relics a reward lets the player choose between.
+const RELIC_REWARD_CHOICES: usize = 3;
+/// Relics can shorten a round, but never below this.
+const MIN_ROUND_DURATION: Duration = Duration::from_secs(1);
+/// Max HP gained by resting at a rest site.
+const REST_MAX_HP_GAIN: i32 = 1;
+/// How many cards the shop stocks per visit.
+const SHOP_CARD_SLOTS: usize = 5;
+/// Goods prices rise by 1 every this many stages.
+const SHOP_MARKUP_STAGES: u32 = 3;
+/// What the shop charges for a skill, before markup and discounts.
+const SHOP_SKILL_PRICE: i32 = 5;
+/// What the shop charges for its services, before discounts.
+const SHOP_UPGRADE_PRICE: i32 = 4;
+const SHOP_REMOVAL_PRICE: i32 = 3;
+const SHOP_MAX_HP_PRICE: i32 = 4;
+const SHOP_HEAL_PRICE: i32 = 2;
+/// Max HP gained from the shop's max HP service.
+const SHOP_MAX_HP_GAIN: i32 = 1;
+/// Selling something back pays its base price divided by this.
+const SELL_BACK_DIVISOR: i32 = 2;
+/// Fewest cards a removal or a sale may leave, so a full hand can still be drawn.
+const MIN_DECK_SIZE: usize = HAND_SIZE;
+/// Most enemies standing in one battle; splits and summons beyond it fizzle out.
+pub const MAX_ENEMIES: usize = 3;
+/// Floors (counting from 0) from which normal battles may bring a second and a third monster.
+const SECOND_ENEMY_FLOOR: usize = 2;
+const THIRD_ENEMY_FLOOR: usize = 4;
+/// Chance of each extra monster once its floor is reached.
+const EXTRA_ENEMY_CHANCE: f64 = 0.4;
+
+/// Drives the main game loop: a walk across the run's map, one node per stage, boss at the end.
+///
+/// The engine holds the rules only. It reads typed [`Command`]s from a [`Frontend`] and
+/// reports everything that happens as [`GameEvent`]s.
 pub struct GameEngine {
     player: Player,
-    enemy: Box<dyn Combatant>,
+    /// Everyone on the enemy side of the current battle. Fallen enemies stay, so an index
+    /// names the same enemy for the whole battle.
+    enemies: Vec<Enemy>,
+    /// The enemy the player's attacks go to unless a command names another.
+    target: usize,
     round: u32,
-    stage: u32,
-    enemy_card: Card,
-}
-
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-enum PlayerActionResult {
-    None,
-    CardUsed,
-    SkillUsed,
+    map: RunMap,
+    data: GameData,
+    seed: u64,
+    /// Seed the RNG was re-derived from on entering the current node.
+    stage_seed: u64,
+    rng: StdRng,
+    clock: Box<dyn Clock>,
+    resumed: bool,
+    shop_offers: Vec<ShopOffer>,
+    /// Whether the player is at the shop counter, where cards and skills can be sold.
+    shop_open: bool,
+    relic_offers: Vec<&'static RelicDef>,
+    reward_offers: Vec<RewardItem>,
+    /// What each enemy will do this round, earliest first; an intent is dropped once
+    /// carried out.
+    enemy_intents: Vec<EnemyIntent>,
+    /// Engine-clock time the current round started.
+    round_started_at: Duration,
+    events: Vec<GameEvent>,
 }
 
 impl GameEngine {
+    /// Creates an engine with a fresh random seed.
     pub fn new() -> Self {
+        Self::with_seed(rand::random())
+    }
+
+    /// Creates an engine whose every random decision is derived from `seed`,
+    /// so the same seed replays the same run.
+    pub fn with_seed(seed: u64) -> Self {
+        Self::with_data(seed, GameData::builtin().clone())
+    }
+
+    /// Like [`GameEngine::with_seed`], but takes cards and enemies from `data`
+    /// instead of the built-in catalog.
+    pub fn with_data(seed: u64, data: GameData) -> Self {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let map = RunMap::generate(&mut rng);
         let mut player = Player::new("勇者", 3);
-        player.set_passive(PassiveSkill::Prepared);
-        player.add_card(create_attack_card());
-        player.add_card(create_defense_card());
+        player.add_passive(PREPARED);
+        for (id, copies) in STARTING_DECK {
+            for _ in 0..copies {
+                player.add_card(data.cards.card(id));
+            }
+        }
         player.equip_skill(create_emergency_heal());
         player.equip_skill(create_fast_cycle());
-        for card in &mut player.hand {
-            card.set_initial_cooldown_ms(PLAYER_INITIAL_CARD_COOLDOWN_MS);
-        }
 
-        let enemy = Self::random_normal_enemy();
-        let mut enemy_card = create_attack_card();
-        enemy_card.set_initial_cooldown_ms(ENEMY_INITIAL_CARD_COOLDOWN_MS);
+        // Replaced as soon as the first battle node is entered.
+        let enemy = Self::spawn(data.enemies.pick_normal(&mut rng));
 
         Self {
             player,
-            enemy,
+            enemies: vec![enemy],
+            target: 0,
             round: 1,
-            stage: 1,
-            enemy_card,
+            map,
+            data,
+            seed,
+            stage_seed: seed,
+            rng,
+            clock: Box::new(RealClock::new()),
+            resumed: false,
+            shop_offers: Vec::new(),
+            shop_open: false,
+            relic_offers: Vec::new(),
+            reward_offers: Vec::new(),
+            enemy_intents: Vec::new(),
+            round_started_at: Duration::ZERO,
+            events: Vec::new(),
         }
     }
 
-    fn random_normal_enemy() -> Box<dyn Combatant> {
-        match rand::thread_rng().gen_range(0..4u32) {
-            0 => Box::new(Slime::new("史莱姆", 3)),
-            1 => Box::new(GoblinRogue::new("哥布林刺客", 4)),
-            2 => Box::new(SkeletonMage::new("骷髅法师", 5)),
-            _ => Box::new(ForestWolf::new("森林狼", 3)),
-        }
+    /// Replaces the time source, e.g. with a `ManualClock` to run battles faster than real time.
+    pub fn with_clock(mut self, clock: Box<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
     }
 
-    fn create_boss() -> Box<dyn Combatant> {
-        Box::new(Dragon::new("巨龙", 8))
+    /// The seed this run was started with.
+    pub fn seed(&self) -> u64 {
+        self.seed
     }
 
-    fn create_boss_card() -> Card {
-        // Boss deals 2 damage per attack
-        Card::new("龙息", "造成 2 点伤害", CardEffect::Damage(2), 4_000)
+    /// The cards and enemies this run draws from.
+    pub fn data(&self) -> &GameData {
+        &self.data
     }
 
-    pub fn run(&mut self) {
-        let input_rx = Self::spawn_input_reader();
+    pub fn player(&self) -> &Player {
+        &self.player
+    }
 
-        println!("╔══════════════════════════════════╗");
-        println!("║     小二的回合制卡牌游戏          ║");
-        println!("╚══════════════════════════════════╝");
-        println!();
-        println!(
-            "📜 冒险开始！击败 {} 个敌人后将迎战 Boss！",
-            STAGES_BEFORE_BOSS
-        );
-        println!();
+    /// Every enemy in the current battle, fallen ones included.
+    pub fn enemies(&self) -> &[Enemy] {
+        &self.enemies
+    }
 
-        loop {
-            let is_boss = self.stage > STAGES_BEFORE_BOSS;
+    /// The enemy the player is aiming at: the one last picked while it stands,
+    /// otherwise the first still standing.
+    pub fn target(&self) -> usize {
+        if self.enemies.get(self.target).is_some_and(|e| e.is_alive()) {
+            return self.target;
+        }
+        self.enemies
+            .iter()
+            .position(|e| e.is_alive())
+            .unwrap_or(self.target)
+    }
 
-            if is_boss {
-                println!("╔══════════════════════════════════╗");
-                println!("║        Boss 战！                 ║");
-                println!("╚══════════════════════════════════╝");
-            }
+    /// Indices of the enemies still standing.
+    pub fn living_enemies(&self) -> Vec<usize> {
+        (0..self.enemies.len())
+            .filter(|&i| self.enemies[i].is_alive())
+            .collect()
+    }
 
-            self.print_welcome();
+    fn enemies_alive(&self) -> bool {
+        self.enemies.iter().any(|e| e.is_alive())
+    }
 
-            // Battle loop
-            while self.player.is_alive() && self.enemy.is_alive() {
-                self.play_round(&input_rx);
-                if !self.player.is_alive() || !self.enemy.is_alive() {
-                    break;
-                }
-                self.finish_round();
-            }
+    pub fn map(&self) -> &RunMap {
+        &self.map
+    }
 
-            if !self.player.is_alive() {
-                self.print_defeat();
-                return;
-            }
+    /// The floor of the current node, counting from 1; 0 before the first step.
+    pub fn stage(&self) -> u32 {
+        self.map.position().map_or(0, |(floor, _)| floor as u32 + 1)
+    }
 
-            // Victory
-            self.print_victory();
+    /// What waits at the current node.
+    pub fn node_kind(&self) -> Option<NodeKind> {
+        self.map.current().map(|node| node.kind)
+    }
 
-            if is_boss {
-                println!("\n🏆 恭喜通关！你击败了所有敌人！");
-                println!("🪙 最终金币：{}", self.player.gold());
-                return;
-            }
+    pub fn is_boss_stage(&self) -> bool {
+        self.node_kind() == Some(NodeKind::Boss)
+    }
 
-            // Shop between stages
-            self.run_shop(&input_rx);
+    pub fn is_elite_stage(&self) -> bool {
+        self.node_kind() == Some(NodeKind::Elite)
+    }
 
-            // Prepare next stage
-            self.stage += 1;
-            self.round = 1;
+    /// What the shop currently has for sale. Empty outside the shop.
+    pub fn shop_offers(&self) -> &[ShopOffer] {
+        &self.shop_offers
+    }
 
-            if self.stage > STAGES_BEFORE_BOSS {
-                // Boss stage
-                self.enemy = Self::create_boss();
-                let mut boss_card = Self::create_boss_card();
-                boss_card.set_initial_cooldown_ms(ENEMY_INITIAL_CARD_COOLDOWN_MS);
-                self.enemy_card = boss_card;
-            } else {
-                self.enemy = Self::random_normal_enemy();
-                let mut enemy_card = create_attack_card();
-                enemy_card.set_initial_cooldown_ms(ENEMY_INITIAL_CARD_COOLDOWN_MS);
-                self.enemy_card = enemy_card;
-            }
+    /// The relics on offer while the player picks a reward. Empty otherwise.
+    pub fn relic_offers(&self) -> &[&'static RelicDef] {
+        &self.relic_offers
+    }
 
-            self.player.reset_for_battle();
-            for card in &mut self.player.hand {
-                card.set_initial_cooldown_ms(PLAYER_INITIAL_CARD_COOLDOWN_MS);
-            }
-        }
+    /// What the post-battle reward currently being chosen offers.
+    pub fn reward_offers(&self) -> &[RewardItem] {
+        &self.reward_offers
     }
 
-    fn run_shop(&mut self, input_rx: &Receiver<String>) {
-        println!("\n╔══════════════════════════════════╗");
-        println!("║           商  店                 ║");
-        println!("╚══════════════════════════════════╝");
-        println!("🪙 当前金币：{}\n", self.player.gold());
+    /// How long a round lasts, including relic bonuses.
+    pub fn round_duration(&self) -> Duration {
+        let bonus_ms = self.player.relic_modifiers().round_time_bonus_ms;
+        let ms = ROUND_DURATION.as_millis() as i64 + bonus_ms;
+        Duration::from_millis(ms.max(0) as u64).max(MIN_ROUND_DURATION)
+    }
 
-        // Build shop items
-        let mut items: Vec<(&str, i32, ShopItem)> = Vec::new();
+    /// What the enemies are about to do this round, earliest first. An enemy has none
+    /// once it has acted, or if it cannot act this round.
+    pub fn enemy_intents(&self) -> &[EnemyIntent] {
+        &self.enemy_intents
+    }
 
-        // Only offer cards/skills the player doesn't already have
-        if !self.player.hand.iter().any(|c| c.name == "暴击") {
-            items.push((
-                "暴击卡 - 造成 2 点伤害（5秒冷却）",
-                4,
-                ShopItem::CriticalStrike,
-            ));
-        }
-        if !self.player.hand.iter().any(|c| c.name == "治愈") {
-            items.push(("治愈卡 - 恢复 1 点生命值（4秒冷却）", 3, ShopItem::HealCard));
-        }
-        if !self.player.skills.iter().any(|s| s.name == "吸血之触") {
-            items.push((
-                "吸血之触技能 - 对敌方造成1伤害并恢复1血（18秒冷却）",
-                5,
-                ShopItem::VampiricTouch,
-            ));
-        }
-        if !self.player.skills.iter().any(|s| s.name == "战吼") {
-            items.push(("战吼技能 - 获得 2 点护盾（12秒冷却）", 4, ShopItem::WarCry));
+    /// Time left until an intent is carried out.
+    pub fn time_until(&self, intent: &EnemyIntent) -> Duration {
+        let at = self.round_started_at + intent.delay;
+        at.saturating_sub(self.clock.now())
+    }
+
+    /// Takes every event emitted since the last drain.
+    pub fn drain_events(&mut self) -> Vec<GameEvent> {
+        std::mem::take(&mut self.events)
+    }
+
+    fn emit(&mut self, event: GameEvent) {
+        self.events.push(event);
+    }
+
+    fn flush_events(&mut self, frontend: &mut dyn Frontend) {
+        for event in std::mem::take(&mut self.events) {
+            frontend.on_event(self, &event);
         }
+    }
 
-        if items.is_empty() {
-            println!("  商店已售罄！你拥有了所有物品。");
-            println!("  按回车继续...");
-            self.wait_for_input(input_rx);
-            return;
+    /// Spawns a fresh enemy with its deck cooling down for the start of a battle.
+    fn spawn(def: &EnemyDef) -> Enemy {
+        let mut enemy = def.spawn();
+        for card in enemy.deck_mut() {
+            card.set_initial_cooldown_ms(ENEMY_INITIAL_CARD_COOLDOWN_MS);
         }
+        enemy
+    }
 
-        for (i, (desc, price, _)) in items.iter().enumerate() {
-            println!("  [{}] {} （💰{}金币）", i + 1, desc, price);
+    /// Plays a run until it is cleared, lost, or suspended between stages.
+    pub fn run(&mut self, frontend: &mut dyn Frontend) -> RunOutcome {
+        if self.resumed {
+            self.emit(GameEvent::RunResumed {
+                stage: self.stage(),
+            });
+        } else {
+            self.emit(GameEvent::RunStarted {
+                floors: self.map.floors().len() as u32,
+                seed: self.seed(),
+            });
+        }
+        if self.map.position().is_none() {
+            self.advance_map(frontend);
         }
-        println!("  [0] 不购买，继续冒险\n");
 
         loop {
-            println!("请输入选择（0-{}）：", items.len());
-
-            let line = self.wait_for_input(input_rx);
-            let choice: usize = match line.trim().parse() {
-                Ok(n) if n <= items.len() => n,
-                _ => {
-                    println!("无效输入。");
-                    continue;
-                }
-            };
-
-            if choice == 0 {
-                println!("继续冒险！\n");
-                break;
-            }
-
-            let (_desc, price, item) = &items[choice - 1];
-            let price = *price;
-
-            if !self.player.spend_gold(price) {
-                println!(
-                    "❌ 金币不足！需要 {} 金币，当前 {} 金币。",
-                    price,
-                    self.player.gold()
-                );
-                continue;
-            }
-
-            match item {
-                ShopItem::CriticalStrike => {
-                    self.player.add_card(create_critical_strike_card());
-                    println!("✅ 购买了「暴击」卡！");
-                }
-                ShopItem::HealCard => {
-                    self.player.add_card(create_heal_card());
-                    println!("✅ 购买了「治愈」卡！");
-                }
-                ShopItem::VampiricTouch => {
-                    if self.player.equip_skill(create_vampiric_touch()) {
-                        println!("✅ 装备了「吸血之触」技能！");
-                    } else {
-                        // Refund
-                        self.player.add_gold(price);
-                        println!("❌ 技能栏已满（最多3个），无法装备。金币已退还。");
-                        continue;
-                    }
-                }
-                ShopItem::WarCry => {
-                    if self.player.equip_skill(create_war_cry()) {
-                        println!("✅ 装备了「战吼」技能！");
-                    } else {
-                        self.player.add_gold(price);
-                        println!("❌ 技能栏已满（最多3个），无法装备。金币已退还。");
-                        continue;
+            match self.node_kind() {
+                Some(kind) if kind.is_battle() => {
+                    let won = self.fight(frontend);
+                    if !won {
+                        return RunOutcome::Defeated;
                     }
                 }
+                Some(NodeKind::Shop) => self.run_shop(frontend),
+                Some(NodeKind::Rest) => self.rest(frontend),
+                Some(NodeKind::Event) => self.resolve_event(),
+                Some(NodeKind::Treasure) => self.offer_relics(frontend, None),
+                _ => {}
             }
-
-            println!("🪙 剩余金币：{}\n", self.player.gold());
-
-            // Remove purchased item and continue shopping
-            items.remove(choice - 1);
-            if items.is_empty() {
-                println!("  商店已售罄！\n");
-                break;
+            self.flush_events(frontend);
+
+            if self.map.choices().is_empty() {
+                self.emit(GameEvent::RunCleared {
+                    gold: self.player.gold(),
+                });
+                self.flush_events(frontend);
+                return RunOutcome::Cleared;
             }
-
-            println!("还要继续购买吗？");
-            for (i, (desc, price, _)) in items.iter().enumerate() {
-                println!("  [{}] {} （💰{}金币）", i + 1, desc, price);
+            self.advance_map(frontend);
+
+            if frontend.wants_to_suspend(self) {
+                self.emit(GameEvent::RunSuspended {
+                    stage: self.stage(),
+                });
+                self.flush_events(frontend);
+                return RunOutcome::Suspended;
             }
-            println!("  [0] 不购买，继续冒险\n");
         }
     }
 
-    fn wait_for_input(&self, input_rx: &Receiver<String>) -> String {
-        loop {
-            if let Ok(line) = input_rx.recv_timeout(Duration::from_millis(100)) {
-                return line;
+    /// Lets the front end pick where to go next, then sets the node up.
+    ///
+    /// Each node gets its own RNG stream, started after the enemy is picked, so a save
+    /// taken here replays exactly like an uninterrupted run.
+    fn advance_map(&mut self, frontend: &mut dyn Frontend) {
+        let choices = self.map.choices();
+        if choices.is_empty() {
+            return;
+        }
+        self.emit(GameEvent::RouteOffered);
+        self.flush_events(frontend);
+        let pick = frontend.choose_path(self);
+        self.map
+            .advance(choices.get(pick).copied().unwrap_or(choices[0]));
+
+        let kind = self.node_kind().unwrap_or(NodeKind::Battle);
+        self.round = 1;
+        self.target = 0;
+        self.stage_seed = self.rng.gen();
+        match kind {
+            NodeKind::Battle => {
+                let count = encounter_size(self.stage() as usize - 1, &mut self.rng);
+                self.enemies = (0..count)
+                    .map(|_| Self::spawn(self.data.enemies.pick_normal(&mut self.rng)))
+                    .collect();
             }
-            // For piped input, check if channel is disconnected
-            thread::sleep(Duration::from_millis(50));
+            NodeKind::Elite => {
+                self.enemies = vec![Self::spawn(self.data.enemies.pick_elite(&mut self.rng))]
+            }
+            NodeKind::Boss => self.enemies = vec![Self::spawn(self.data.enemies.boss())],
+            _ => {}
         }
-    }
+        self.rng = StdRng::seed_from_u64(self.stage_seed);
 
-    fn spawn_input_reader() -> Receiver<String> {
-        let (tx, rx) = mpsc::channel();
-        thread::spawn(move || {
-            let stdin = io::stdin();
-            for line in stdin.lock().lines().map_while(Result::ok) {
-                if tx.send(line).is_err() {
-                    break;
-                }
-            }
+        if kind.is_battle() {
+            self.player.reset_for_battle();
+        }
+        self.emit(GameEvent::NodeEntered {
+            stage: self.stage(),
+            kind,
         });
-        rx
+        self.flush_events(frontend);
     }
 
-    fn play_round(&mut self, input_rx: &Receiver<String>) {
-        self.print_status();
-        self.print_actions();
-        println!("⏱️ 本回合持续 5 秒：卡牌每回合仅能使用一次；技能不受回合次数限制，可与卡牌同回合使用。");
-
-        let round_start = Instant::now();
-        let round_end = round_start + ROUND_DURATION;
-        let mut last_tick = round_start;
-        let enemy_action_at = self.plan_enemy_action_time(round_start, round_end);
-
-        let mut player_used_card = false;
-        let mut player_did_any_action = false;
-        let mut enemy_acted = false;
-
-        while Instant::now() < round_end && self.player.is_alive() && self.enemy.is_alive() {
-            let now = Instant::now();
-            let elapsed = now.saturating_duration_since(last_tick);
-            if elapsed > Duration::ZERO {
-                self.tick_cooldowns(elapsed);
-                last_tick = now;
-            }
-
-            while let Ok(line) = input_rx.try_recv() {
-                match self.try_execute_player_action(&line, player_used_card) {
-                    PlayerActionResult::None => {}
-                    PlayerActionResult::CardUsed => {
-                        player_used_card = true;
-                        player_did_any_action = true;
-                    }
-                    PlayerActionResult::SkillUsed => {
-                        player_did_any_action = true;
-                    }
-                }
-            }
-
-            if !enemy_acted
-                && self.enemy.is_alive()
-                && enemy_action_at.is_some_and(|planned| now >= planned)
-                && self.enemy_card.is_ready()
-            {
-                self.execute_enemy_action();
-                enemy_acted = true;
-            }
-
-            if !self.player.is_alive() || !self.enemy.is_alive() {
+    /// Plays the battle at the current node and hands out its rewards.
+    /// Returns false if the player fell.
+    ///
+    /// The battle is won once every enemy is down, including split copies and summons.
+    fn fight(&mut self, frontend: &mut dyn Frontend) -> bool {
+        self.start_battle();
+        self.flush_events(frontend);
+
+        while self.player.is_alive() && self.enemies_alive() {
+            self.play_round(frontend);
+            if !self.player.is_alive() || !self.enemies_alive() {
                 break;
             }
+            self.finish_round();
+        }
 
-            let now_after = Instant::now();
-            if now_after >= round_end {
-                break;
+        if !self.player.is_alive() {
+            for index in self.living_enemies() {
+                self.trigger_passives(Side::Enemy(index), |hooks| hooks.on_kill, 0);
             }
-            let remaining = round_end.duration_since(now_after);
-            thread::sleep(remaining.min(LOOP_TICK));
+            self.emit(GameEvent::BattleLost {
+                stage: self.stage(),
+            });
+            self.emit(GameEvent::BattleSummary);
+            self.flush_events(frontend);
+            return false;
         }
 
-        let now = Instant::now();
-        let elapsed = now.saturating_duration_since(last_tick);
-        if elapsed > Duration::ZERO {
-            self.tick_cooldowns(elapsed);
+        self.claim_victory();
+        // The run ends after the last stage, so there is nothing left to spend a reward on.
+        if self.map.choices().is_empty() {
+            return true;
+        }
+        self.offer_rewards(frontend);
+        let min_tier = if self.is_boss_stage() {
+            Some(RelicTier::Rare)
+        } else if self.is_elite_stage() {
+            Some(RelicTier::Uncommon)
+        } else {
+            None
+        };
+        if min_tier.is_some() || self.rng.gen_bool(NORMAL_RELIC_REWARD_CHANCE) {
+            self.offer_relics(frontend, min_tier);
         }
+        true
+    }
 
-        if self.player.is_alive() && !player_did_any_action {
-            println!("⌛ 你在本回合未行动。");
+    /// A rest site upgrades a card of the player's choice, or else raises max HP a little.
+    fn rest(&mut self, frontend: &mut dyn Frontend) {
+        let can_upgrade = self
+            .player
+            .deck()
+            .cards()
+            .iter()
+            .any(|card| card.upgraded_version().is_some());
+        if can_upgrade {
+            self.emit(GameEvent::UpgradeOffered);
+            self.flush_events(frontend);
+            if let Some(index) = frontend.choose_upgrade(self) {
+                if self.upgrade_card(index) {
+                    return;
+                }
+            }
         }
-        if self.enemy.is_alive() && !enemy_acted {
-            println!("⌛ {} 在本回合未行动。", self.enemy.name());
+        self.player.gain_max_hp(REST_MAX_HP_GAIN);
+        self.emit(GameEvent::Rested {
+            max_hp: REST_MAX_HP_GAIN,
+        });
+    }
+
+    /// Upgrades owned card `index`, announcing it. Returns false if it cannot be upgraded.
+    fn upgrade_card(&mut self, index: usize) -> bool {
+        let Some(from) = self
+            .player
+            .deck()
+            .cards()
+            .get(index)
+            .map(|c| c.name.clone())
+        else {
+            return false;
+        };
+        if !self.player.deck_mut().upgrade(index) {
+            return false;
         }
-        println!();
+        let to = self.player.deck().cards()[index].name.clone();
+        self.emit(GameEvent::CardUpgraded { from, to });
+        true
     }
 
-    fn finish_round(&mut self) {
-        self.player.clear_shield();
-        self.enemy.clear_shield();
-        self.round += 1;
+    /// Rolls what happens at an event node and applies it.
+    fn resolve_event(&mut self) {
+        let outcome = match self.rng.gen_range(0..4) {
+            0 => EventOutcome::FoundGold(self.rng.gen_range(2..=4)),
+            1 => EventOutcome::Blessing(1),
+            2 => EventOutcome::FoundRelic,
+            _ => EventOutcome::Trap(self.rng.gen_range(1..=2).min(self.player.gold())),
+        };
+        let relic = match outcome {
+            EventOutcome::FoundRelic => RelicRegistry::builtin()
+                .pick_reward(1, self.player.relics(), None, &mut self.rng)
+                .first()
+                .copied(),
+            _ => None,
+        };
+        let outcome = match (outcome, relic) {
+            (EventOutcome::FoundRelic, None) => EventOutcome::FoundGold(3),
+            (outcome, _) => outcome,
+        };
+
+        match outcome {
+            EventOutcome::FoundGold(amount) => self.player.add_gold(amount),
+            EventOutcome::Blessing(amount) => self.player.gain_max_hp(amount),
+            EventOutcome::FoundRelic => {}
+            EventOutcome::Trap(amount) => self.player.add_gold(-amount),
+        }
+        self.emit(GameEvent::EventResolved(outcome));
+        if let Some(def) = relic {
+            self.player.add_relic(def.id);
+            self.emit(GameEvent::RelicGained {
+                relic: def.id.to_string(),
+            });
+        }
     }
 
-    fn plan_enemy_action_time(&self, round_start: Instant, round_end: Instant) -> Option<Instant> {
-        let earliest = round_start + Duration::from_millis(self.enemy_card.remaining_cooldown_ms());
-        if earliest >= round_end {
-            return None;
+    /// Shuffles the deck, announces the battle and fires battle-start passives, player
+    /// first, then relics.
+    ///
+    /// The shuffle happens here rather than on entering the node, so a run saved in
+    /// between deals the same cards once resumed.
+    fn start_battle(&mut self) {
+        let deck = self.player.deck_mut();
+        deck.start_battle(&mut self.rng);
+        for card in deck.battle_cards_mut() {
+            card.set_initial_cooldown_ms(PLAYER_INITIAL_CARD_COOLDOWN_MS);
+        }
+        self.emit(GameEvent::BattleStarted {
+            stage: self.stage(),
+            is_boss: self.is_boss_stage(),
+        });
+        for side in self.sides() {
+            self.trigger_passives(side, |hooks| hooks.on_battle_start, 0);
         }
+        self.trigger_relics(|hooks| hooks.on_battle_start, 0);
+    }
 
-        let latest = round_end - Duration::from_millis(300);
-        if earliest >= latest {
-            return Some(earliest);
+    /// Captures the run for saving. Only meaningful between stages.
+    pub fn snapshot(&self) -> SaveData {
+        SaveData {
+            version: SAVE_VERSION,
+            seed: self.seed,
+            stage_seed: self.stage_seed,
+            round: self.round,
+            player: self.player.clone(),
+            enemies: self.enemies.clone(),
+            map: self.map.clone(),
         }
+    }
 
-        let window_ms = latest.duration_since(earliest).as_millis() as u64;
-        let random_delay_ms = rand::thread_rng().gen_range(0..=window_ms);
-        Some(earliest + Duration::from_millis(random_delay_ms))
+    /// Rebuilds a suspended run; `run` then continues with the saved stage.
+    pub fn from_save(save: SaveData, data: GameData) -> Self {
+        let mut engine = Self::with_data(save.seed, data);
+        engine.stage_seed = save.stage_seed;
+        engine.rng = StdRng::seed_from_u64(save.stage_seed);
+        engine.map = save.map;
+        engine.round = save.round;
+        engine.player = save.player;
+        engine.enemies = save.enemies;
+        engine.resumed = true;
+        engine
     }
 
-    fn tick_cooldowns(&mut self, elapsed: Duration) {
-        let elapsed_ms = elapsed.as_millis() as u64;
-        if elapsed_ms == 0 {
+    /// Lets the player pick one of a few relics they do not own yet, or none.
+    fn offer_relics(&mut self, frontend: &mut dyn Frontend, min_tier: Option<RelicTier>) {
+        self.relic_offers = RelicRegistry::builtin().pick_reward(
+            RELIC_REWARD_CHOICES,
+            self.player.relics(),
+            min_tier,
+            &mut self.rng,
+        );
+        if self.relic_offers.is_empty() {
             return;
         }
-        for card in &mut self.player.hand {
-            card.tick_cooldown_ms(elapsed_ms);
+        self.emit(GameEvent::RelicRewardOffered);
+        self.flush_events(frontend);
+
+        if let Some(def) = frontend
+            .choose_relic(self)
+            .and_then(|index| self.relic_offers.get(index).copied())
+        {
+            self.player.add_relic(def.id);
+            self.emit(GameEvent::RelicGained {
+                relic: def.id.to_string(),
+            });
         }
-        self.enemy_card.tick_cooldown_ms(elapsed_ms);
-        self.player.tick_skill_cooldowns_ms(elapsed_ms);
+        self.relic_offers.clear();
+        self.flush_events(frontend);
     }
 
-    fn print_welcome(&self) {
-        let is_boss = self.stage > STAGES_BEFORE_BOSS;
-        if is_boss {
-            println!(
-                "⚔️ Boss 战！ {} vs {}",
-                self.player.name(),
-                self.enemy.name()
-            );
+    /// Lets the player add one of a few cards to the deck, or none. Elites guarantee a
+    /// rarer card; now and then the last choice is a skill or a relic instead.
+    fn offer_rewards(&mut self, frontend: &mut dyn Frontend) {
+        let min_rarity = self.is_elite_stage().then_some(CardRarity::Uncommon);
+        let alternative = if self.rng.gen_bool(ALTERNATIVE_REWARD_CHANCE) {
+            self.pick_alternative_reward()
         } else {
-            println!(
-                "⚔️ 第 {} 关！ {} vs {}",
-                self.stage,
-                self.player.name(),
-                self.enemy.name()
-            );
+            None
+        };
+        let cards = CARD_REWARD_CHOICES - usize::from(alternative.is_some());
+        self.reward_offers = self
+            .data
+            .cards
+            .pick_reward(cards, self.stage(), min_rarity, &mut self.rng)
+            .into_iter()
+            .map(|id| RewardItem::Card(id.to_string()))
+            .chain(alternative)
+            .collect();
+        if self.reward_offers.is_empty() {
+            return;
         }
-        println!(
-            "⚙️ 速度：{}={}，{}={}",
-            self.player.name(),
-            self.player.speed(),
-            self.enemy.name(),
-            self.enemy.speed()
-        );
-        let dodge = self.enemy.dodge_chance();
-        if dodge > 0.0 {
-            println!(
-                "🌀 敌方被动：每次受击有 {}% 概率完全闪避伤害！",
-                (dodge * 100.0).round() as u32
-            );
+        self.emit(GameEvent::RewardOffered);
+        self.flush_events(frontend);
+
+        if let Some(item) = frontend
+            .choose_reward(self)
+            .and_then(|index| self.reward_offers.get(index).cloned())
+        {
+            self.take_reward(item);
         }
-        if self.enemy.shield() > 0 {
-            println!("🛡️ 敌方被动：初始拥有 {} 点护盾！", self.enemy.shield());
+        self.reward_offers.clear();
+        self.flush_events(frontend);
+    }
+
+    /// A skill the player lacks and has a free slot for, or a relic they lack; a coin
+    /// flip decides when both are possible.
+    fn pick_alternative_reward(&mut self) -> Option<RewardItem> {
+        let skills: Vec<&'static str> = if self.player.skills.len() < MAX_SKILLS {
+            LEARNABLE_SKILLS
+                .into_iter()
+                .filter(|id| !self.owns_skill(id))
+                .collect()
+        } else {
+            Vec::new()
+        };
+        let relic = RelicRegistry::builtin()
+            .pick_reward(1, self.player.relics(), None, &mut self.rng)
+            .first()
+            .map(|def| RewardItem::Relic(def.id));
+        if skills.is_empty() || (relic.is_some() && self.rng.gen_bool(0.5)) {
+            return relic;
         }
-        println!();
+        Some(RewardItem::Skill(
+            skills[self.rng.gen_range(0..skills.len())],
+        ))
     }
 
-    fn print_status(&self) {
-        println!("┌─── 第 {} 回合（5 秒） ───┐", self.round);
-        println!("│  {}", self.player.display_status());
-        println!("│  {}", self.enemy.display_status());
-        println!("└──────────────────────────┘");
+    /// Whether the skill with this id is already equipped.
+    fn owns_skill(&self, id: &str) -> bool {
+        skill::create(id).is_some_and(|new| self.player.skills.iter().any(|s| s.name == new.name))
     }
 
-    fn print_actions(&self) {
-        println!("\n你的手牌：");
-        for (i, card) in self.player.hand.iter().enumerate() {
-            let status = if card.is_ready() {
-                "可用".to_string()
-            } else {
-                format!("冷却 {} 秒", card.remaining_cooldown_secs())
-            };
-            println!("  [{}] {} [{}]", i + 1, card, status);
+    fn take_reward(&mut self, item: RewardItem) {
+        match item {
+            RewardItem::Card(id) => {
+                let card = self.data.cards.card(&id);
+                let name = card.name.clone();
+                self.player.add_card(card);
+                self.emit(GameEvent::RewardTaken { name });
+            }
+            RewardItem::Skill(id) => {
+                let Some(skill) = skill::create(id) else {
+                    return;
+                };
+                let name = skill.name.clone();
+                if self.player.equip_skill(skill) {
+                    self.emit(GameEvent::RewardTaken { name });
+                }
+            }
+            RewardItem::Relic(id) => {
+                self.player.add_relic(id);
+                self.emit(GameEvent::RelicGained {
+                    relic: id.to_string(),
+                });
+            }
         }
+    }
 
-        if !self.player.skills.is_empty() {
-            println!("\n你的技能：");
-            let offset = self.player.hand.len();
-            for (i, skill) in self.player.skills.iter().enumerate() {
-                println!("  [{}] {}", offset + i + 1, skill);
-            }
+    fn run_shop(&mut self, frontend: &mut dyn Frontend) {
+        self.open_shop();
+        self.flush_events(frontend);
+
+        while let Some(command) = frontend.shop_command(self) {
+            self.execute(command);
+            self.flush_events(frontend);
         }
-        println!();
+
+        self.shop_offers.clear();
+        self.shop_open = false;
+        self.emit(GameEvent::ShopClosed);
+        self.flush_events(frontend);
     }
 
-    fn try_execute_player_action(
-        &mut self,
-        line: &str,
-        player_used_card: bool,
-    ) -> PlayerActionResult {
-        let total_actions = self.player.hand.len() + self.player.skills.len();
-        if total_actions == 0 {
-            return PlayerActionResult::None;
+    /// Stocks the shop for this visit and announces it: a few cards weighted by rarity,
+    /// a skill and a relic the player lacks, one of those goods on sale, then the card
+    /// upgrade, card removal, healing and max HP services.
+    ///
+    /// Stock is rolled afresh on every visit and is not refilled while the shop is open.
+    /// Healing is only offered to a hurt player: HP carries over from one battle to the next.
+    fn open_shop(&mut self) {
+        let mut goods: Vec<ShopItem> = self
+            .data
+            .cards
+            .pick_reward(SHOP_CARD_SLOTS, self.stage(), None, &mut self.rng)
+            .into_iter()
+            .map(|id| ShopItem::Card(id.to_string()))
+            .collect();
+        let skills: Vec<&'static str> = LEARNABLE_SKILLS
+            .into_iter()
+            .filter(|id| !self.owns_skill(id))
+            .collect();
+        if !skills.is_empty() {
+            goods.push(ShopItem::Skill(skills[self.rng.gen_range(0..skills.len())]));
+        }
+        if let Some(def) = RelicRegistry::builtin()
+            .pick_reward(1, self.player.relics(), None, &mut self.rng)
+            .first()
+        {
+            goods.push(ShopItem::Relic(def.id));
+        }
+        let sale = (!goods.is_empty()).then(|| self.rng.gen_range(0..goods.len()));
+
+        let mut offers: Vec<ShopOffer> = goods
+            .into_iter()
+            .enumerate()
+            .map(|(i, item)| self.shop_offer(item, Some(i) == sale))
+            .collect();
+        let deck = self.player.deck().cards();
+        if deck.iter().any(|card| card.upgraded_version().is_some()) {
+            offers.push(self.shop_offer(ShopItem::Upgrade, false));
+        }
+        if deck.len() > MIN_DECK_SIZE {
+            offers.push(self.shop_offer(ShopItem::RemoveCard, false));
         }
+        if self.player.hp() < self.player.max_hp() {
+            offers.push(self.shop_offer(ShopItem::Heal, false));
+        }
+        offers.push(self.shop_offer(ShopItem::MaxHp(SHOP_MAX_HP_GAIN), false));
+        self.shop_offers = offers;
+        self.shop_open = true;
+        self.emit(GameEvent::ShopOpened);
+    }
 
-        let choice = match line.trim().parse::<usize>() {
-            Ok(n) if n >= 1 && n <= total_actions => n - 1,
-            _ => {
-                println!("无效输入，请输入 1 到 {} 之间的数字。", total_actions);
-                return PlayerActionResult::None;
+    /// Describes and prices a shop item. Goods cost a little more every few stages and
+    /// half as much on sale; relic discounts come off everything.
+    fn shop_offer(&self, item: ShopItem, on_sale: bool) -> ShopOffer {
+        let markup = (self.stage().saturating_sub(1) / SHOP_MARKUP_STAGES) as i32;
+        let (description, price) = match &item {
+            ShopItem::Card(id) => {
+                let card = self.data.cards.card(id);
+                let mut cost = format!("，{}能量", card.energy_cost);
+                if card.is_spell() {
+                    cost.push_str(&format!("，{}法力", card.mana_cost));
+                }
+                let description = format!(
+                    "{}卡 [{}] - {}（{}秒冷却{cost}）",
+                    card.name,
+                    card.rarity.label(),
+                    card.description,
+                    card.cooldown_ms().div_ceil(1_000)
+                );
+                (description, card.rarity.price().unwrap_or(0) + markup)
             }
+            ShopItem::Skill(id) => {
+                let skill = skill::create(id).expect("shop skills come from LEARNABLE_SKILLS");
+                let description = format!(
+                    "{}技能 - {}（{}秒冷却）",
+                    skill.name,
+                    skill.description,
+                    skill.cooldown_ms.div_ceil(1_000)
+                );
+                (description, SHOP_SKILL_PRICE + markup)
+            }
+            ShopItem::Relic(id) => {
+                let def = RelicRegistry::builtin()
+                    .get(id)
+                    .expect("shop relics come from the registry");
+                let description = format!(
+                    "{} {}遗物 [{}] - {}",
+                    def.icon,
+                    def.name,
+                    def.tier.label(),
+                    def.description
+                );
+                (description, def.tier.price() + markup)
+            }
+            ShopItem::Upgrade => ("卡牌升级 - 挑一张卡牌升级".to_string(), SHOP_UPGRADE_PRICE),
+            ShopItem::RemoveCard => (
+                "卡牌移除 - 从牌组中永久移除一张卡牌".to_string(),
+                SHOP_REMOVAL_PRICE,
+            ),
+            ShopItem::Heal => (
+                format!(
+                    "治疗 - 恢复全部生命值（当前 {}/{}）",
+                    self.player.hp(),
+                    self.player.max_hp()
+                ),
+                SHOP_HEAL_PRICE,
+            ),
+            ShopItem::MaxHp(amount) => (
+                format!("滋补汤药 - 最大生命值 +{amount}"),
+                SHOP_MAX_HP_PRICE,
+            ),
         };
+        let price = if on_sale { price / 2 } else { price };
+        let discount = self.player.relic_modifiers().shop_discount;
+        ShopOffer {
+            item,
+            description,
+            price: (price - discount).max(0),
+            on_sale,
+        }
+    }
 
-        let card_count = self.player.hand.len();
-        if choice < card_count {
-            if player_used_card {
-                println!("\n⛔ 本回合已使用过卡牌，但仍可使用技能。");
-                return PlayerActionResult::None;
-            }
-            let (card_name, effect) = {
-                let card = &mut self.player.hand[choice];
-                if !card.is_ready() {
-                    println!(
-                        "\n⏳ 「{}」仍在冷却中（剩余 {} 秒）。",
-                        card.name,
-                        card.remaining_cooldown_secs()
-                    );
-                    return PlayerActionResult::None;
-                }
-                card.trigger_cooldown();
-                (card.name.clone(), card.effect.clone())
-            };
+    /// Whether a service bought for owned card `card` can be carried out; goods take no card.
+    fn service_applies(&self, item: &ShopItem, card: Option<usize>) -> bool {
+        let deck = self.player.deck().cards();
+        match (item, card) {
+            (ShopItem::Upgrade, Some(card)) => deck
+                .get(card)
+                .is_some_and(|card| card.upgraded_version().is_some()),
+            (ShopItem::RemoveCard, Some(card)) => card < deck.len() && deck.len() > MIN_DECK_SIZE,
+            (ShopItem::Upgrade | ShopItem::RemoveCard, None) | (_, Some(_)) => false,
+            (ShopItem::Heal, None) => self.player.hp() < self.player.max_hp(),
+            (_, None) => true,
+        }
+    }
 
-            println!("\n▶ 你使用了「{}」！", card_name);
-            match effect {
-                CardEffect::Damage(amount) => self.log_damage(amount, "enemy"),
-                CardEffect::Shield(amount) => {
-                    self.player.add_shield(amount);
-                    println!("  🛡️ 获得了 {} 点护盾！", amount);
-                }
-                CardEffect::Heal(amount) => {
-                    let healed = self.player.heal(amount);
-                    if healed > 0 {
-                        println!("  ❤️ 恢复了 {} 点生命值！", healed);
-                    } else {
-                        println!("  ❤️ 生命值已满，未恢复。");
-                    }
-                }
-            }
-            println!();
-            return PlayerActionResult::CardUsed;
+    fn buy_item(&mut self, index: usize, card: Option<usize>) -> CommandOutcome {
+        let Some(offer) = self.shop_offers.get(index).cloned() else {
+            return self.reject(Rejection::NoSuchAction);
+        };
+        if !self.service_applies(&offer.item, card) {
+            return self.reject(Rejection::NoSuchAction);
         }
 
-        let skill_idx = choice - card_count;
-        if !self.player.skills[skill_idx].is_ready() {
-            println!(
-                "\n⏳ 「{}」仍在冷却中（剩余 {} 秒）。",
-                self.player.skills[skill_idx].name,
-                self.player.skills[skill_idx].remaining_cooldown_secs()
-            );
-            return PlayerActionResult::None;
+        if !self.player.spend_gold(offer.price) {
+            let gold = self.player.gold();
+            return self.reject(Rejection::NotEnoughGold {
+                price: offer.price,
+                gold,
+            });
         }
 
-        let skill = self.player.skills[skill_idx].clone();
-        println!("\n▶ 你使用了技能「{}」！", skill.name);
-        match skill.effect {
-            SkillEffect::Heal(amount) => {
-                let healed = self.player.heal(amount);
-                if healed > 0 {
-                    println!("  ❤️ 恢复了 {} 点生命值！", healed);
-                } else {
-                    println!("  ❤️ 生命值已满，未恢复。");
-                }
+        let card = card.unwrap_or_default();
+        let name = match offer.item {
+            ShopItem::Card(id) => {
+                let card = self.data.cards.card(&id);
+                let name = card.name.clone();
+                self.player.add_card(card);
+                name
             }
-            SkillEffect::ReduceAllCardCooldownMs(amount_ms) => {
-                for card in &mut self.player.hand {
-                    card.reduce_cooldown_ms(amount_ms);
+            ShopItem::Skill(id) => {
+                let skill = skill::create(id).expect("shop skills come from LEARNABLE_SKILLS");
+                let name = skill.name.clone();
+                if !self.player.equip_skill(skill) {
+                    self.player.add_gold(offer.price);
+                    return self.reject(Rejection::SkillSlotsFull { max: MAX_SKILLS });
                 }
-                println!("  🌀 当前所有卡牌冷却减少了 1 秒！");
+                name
+            }
+            ShopItem::Relic(id) => {
+                self.player.add_relic(id);
+                self.emit(GameEvent::RelicGained {
+                    relic: id.to_string(),
+                });
+                RelicRegistry::builtin()
+                    .get(id)
+                    .map_or(id, |def| def.name)
+                    .to_string()
+            }
+            ShopItem::Upgrade => {
+                self.upgrade_card(card);
+                "卡牌升级".to_string()
             }
-            SkillEffect::DamageAndHeal { damage, heal } => {
-                self.log_damage(damage, "enemy");
-                let healed = self.player.heal(heal);
-                if healed > 0 {
-                    println!("  ❤️ 同时恢复了 {} 点生命值！", healed);
+            ShopItem::RemoveCard => {
+                if let Some(removed) = self.player.deck_mut().remove(card) {
+                    self.emit(GameEvent::CardRemoved { name: removed.name });
                 }
+                "卡牌移除".to_string()
             }
-            SkillEffect::GainShield(amount) => {
-                self.player.add_shield(amount);
-                println!("  🛡️ 获得了 {} 点护盾！", amount);
+            ShopItem::Heal => {
+                let missing = self.player.max_hp() - self.player.hp();
+                self.player.heal(missing);
+                "治疗".to_string()
             }
-        }
-        self.player.skills[skill_idx].trigger_cooldown();
-        println!();
-        PlayerActionResult::SkillUsed
-    }
+            ShopItem::MaxHp(amount) => {
+                self.player.gain_max_hp(amount);
+                "滋补汤药".to_string()
+            }
+        };
 
-    fn execute_enemy_action(&mut self) {
-        if !self.enemy_card.is_ready() {
-            return;
+        self.shop_offers.remove(index);
+        self.emit(GameEvent::ItemBought { name });
+        if self.shop_offers.is_empty() {
+            self.emit(GameEvent::ShopSoldOut);
         }
+        CommandOutcome::ItemBought
+    }
 
-        let card_name = self.enemy_card.name.clone();
-        let effect = self.enemy_card.effect.clone();
-        self.enemy_card.trigger_cooldown();
-
-        println!("\n▶ {} 使用了「{}」！", self.enemy.name(), card_name);
-        match effect {
-            CardEffect::Damage(amount) => self.log_damage(amount, "player"),
-            CardEffect::Shield(amount) => {
-                self.enemy.add_shield(amount);
-                println!("  🛡️ {} 获得了 {} 点护盾！", self.enemy.name(), amount);
-            }
-            CardEffect::Heal(amount) => {
-                let healed = self.enemy.heal(amount);
-                if healed > 0 {
-                    println!("  ❤️ {} 恢复了 {} 点生命值！", self.enemy.name(), healed);
-                }
-            }
-        }
-        println!();
+    /// What the shop pays for owned card `index`: part of its base price.
+    pub fn card_sell_price(&self, index: usize) -> Option<i32> {
+        let card = self.player.deck().cards().get(index)?;
+        Some(card.rarity.price().unwrap_or(0) / SELL_BACK_DIVISOR)
     }
 
-    /// Applies damage to the specified target side, printing shield / damage info.
-    fn log_damage(&mut self, amount: i32, target_side: &str) {
-        // Dodge check: only enemy can dodge
-        if target_side == "enemy" {
-            let dodge = self.enemy.dodge_chance();
-            if dodge > 0.0 && rand::thread_rng().gen_bool(dodge) {
-                println!("  💨 {} 闪避了攻击！", self.enemy.name());
-                return;
-            }
-        }
+    /// What the shop pays for the equipped skill at `index`: part of its base price.
+    pub fn skill_sell_price(&self, index: usize) -> Option<i32> {
+        self.player
+            .skills
+            .get(index)
+            .map(|_| SHOP_SKILL_PRICE / SELL_BACK_DIVISOR)
+    }
 
-        let (target_name, shield_before) = match target_side {
-            "enemy" => (self.enemy.name().to_string(), self.enemy.shield()),
-            _ => (self.player.name().to_string(), self.player.shield()),
+    fn sell_card(&mut self, index: usize) -> CommandOutcome {
+        let Some(gold) = self.card_sell_price(index).filter(|_| self.shop_open) else {
+            return self.reject(Rejection::NoSuchAction);
         };
-
-        match target_side {
-            "enemy" => self.enemy.take_damage(amount),
-            _ => self.player.take_damage(amount),
+        if self.player.deck().cards().len() <= MIN_DECK_SIZE {
+            return self.reject(Rejection::DeckTooSmall { min: MIN_DECK_SIZE });
         }
+        let card = self.player.deck_mut().remove(index).expect("priced above");
+        self.player.add_gold(gold);
+        self.emit(GameEvent::ItemSold {
+            name: card.name,
+            gold,
+        });
+        CommandOutcome::ItemSold
+    }
 
-        let shield_after = match target_side {
-            "enemy" => self.enemy.shield(),
-            _ => self.player.shield(),
+    fn sell_skill(&mut self, index: usize) -> CommandOutcome {
+        let Some(gold) = self.skill_sell_price(index).filter(|_| self.shop_open) else {
+            return self.reject(Rejection::NoSuchAction);
         };
+        let skill = self.player.unequip_skill(index).expect("priced above");
+        self.player.add_gold(gold);
+        self.emit(GameEvent::ItemSold {
+            name: skill.name,
+            gold,
+        });
+        CommandOutcome::ItemSold
+    }
 
-        let absorbed = shield_before - shield_after;
-        let actual = amount - absorbed;
-
-        if absorbed > 0 {
-            println!("  🛡️ {}的护盾抵消了 {} 点伤害！", target_name, absorbed);
+    /// Sells equipped skill `skill` and buys the skill at shop offer `offer` in its slot.
+    /// The sale counts towards the price; nothing changes if the two together fall short.
+    fn replace_skill(&mut self, offer: usize, skill: usize) -> CommandOutcome {
+        let item = self.shop_offers.get(offer).map(|o| o.item.clone());
+        let (Some(ShopItem::Skill(_)), Some(refund)) = (item, self.skill_sell_price(skill)) else {
+            return self.reject(Rejection::NoSuchAction);
+        };
+        let price = self.shop_offers[offer].price;
+        let gold = self.player.gold();
+        if gold + refund < price {
+            return self.reject(Rejection::NotEnoughGold { price, gold });
         }
-        if actual > 0 {
-            println!("  对 {} 造成了 {} 点伤害！", target_name, actual);
-        } else {
-            println!("  攻击被完全抵挡！");
+        if self.sell_skill(skill) != CommandOutcome::ItemSold {
+            return CommandOutcome::Rejected;
         }
+        let outcome = self.buy_item(offer, None);
+        if outcome == CommandOutcome::ItemBought {
+            let bought = self.player.skills.pop().expect("just equipped");
+            self.player.skills.insert(skill, bought);
+        }
+        outcome
     }
 
-    fn print_victory(&mut self) {
-        println!("╔══════════════════════════════════╗");
-        println!("║          你胜利了！              ║");
-        println!("╚══════════════════════════════════╝");
-
-        let is_boss = self.stage > STAGES_BEFORE_BOSS;
-        let base_reward = if is_boss {
-            rand::thread_rng().gen_range(5..=8)
-        } else {
-            rand::thread_rng().gen_range(1..=3)
-        };
-        let bonus = self.player.victory_bonus_gold();
-        let total = base_reward + bonus;
-        self.player.add_gold(total);
-
-        if bonus > 0 {
-            let passive_name = self.player.passive().map(|p| p.name()).unwrap_or("被动");
-            println!(
-                "\n💰 获得了 {} 金币！（基础 {} + {} +{}）",
-                total, base_reward, passive_name, bonus
-            );
-        } else {
-            println!("\n💰 获得了 {} 金币！", total);
+    fn play_round(&mut self, frontend: &mut dyn Frontend) {
+        let round_start = self.clock.now();
+        let round_duration = self.round_duration();
+        let round_end = round_start + round_duration;
+        let mut last_tick = round_start;
+        self.round_started_at = round_start;
+        self.player.refill_energy();
+        self.player.regen_mana(MANA_REGEN_PER_ROUND);
+
+        self.emit(GameEvent::RoundStarted { round: self.round });
+        self.draw_cards(HAND_SIZE);
+        self.trigger_round_start_buffs();
+        for side in self.sides() {
+            self.trigger_passives(side, |hooks| hooks.on_round_start, 0);
+        }
+        self.trigger_relics(|hooks| hooks.on_round_start, 0);
+        self.advance_enemy_phases();
+        self.enemy_intents.clear();
+        if self.player.is_alive() {
+            for index in self.living_enemies() {
+                if let Some(intent) = self.plan_enemy_intent(index, round_duration) {
+                    self.enemy_intents.push(intent);
+                }
+            }
+            self.enemy_intents.sort_by_key(|intent| intent.delay);
+        }
+        for intent in self.enemy_intents.clone() {
+            self.emit(GameEvent::IntentDeclared(intent));
         }
-        println!("🪙 当前金币：{}", self.player.gold());
+        self.flush_events(frontend);
 
-        println!("\n最终状态：");
-        println!("  {}", self.player.display_status());
-        println!("  {}", self.enemy.display_status());
-    }
+        let mut player_did_any_action = false;
+        let mut enemies_acted = Vec::new();
 
-    fn print_defeat(&self) {
-        println!("╔══════════════════════════════════╗");
-        println!("║          你被击败了…             ║");
-        println!("╚══════════════════════════════════╝");
-        println!("\n💀 在第 {} 关倒下了…", self.stage);
-        println!("\n最终状态：");
-        println!("  {}", self.player.display_status());
-        println!("  {}", self.enemy.display_status());
-    }
-}
+        while self.clock.now() < round_end && self.player.is_alive() && self.enemies_alive() {
+            let now = self.clock.now();
+            let elapsed_ms = now.saturating_sub(last_tick).as_millis() as u64;
+            if elapsed_ms > 0 {
+                self.tick_cooldowns(elapsed_ms);
+                last_tick = now;
+            }
 
-#[derive(Debug, Clone)]
-enum ShopItem {
-    CriticalStrike,
-    HealCard,
-    VampiricTouch,
-    WarCry,
-}
+            while let Some(command) = frontend.poll_command(self) {
+                match self.execute(command) {
+                    CommandOutcome::CardPlayed | CommandOutcome::SkillUsed => {
+                        player_did_any_action = true;
+                    }
+                    CommandOutcome::Rejected
+                    | CommandOutcome::ItemBought
+                    | CommandOutcome::ItemSold => {}
+                }
+                self.flush_events(frontend);
+            }
 
-#[cfg(test)]
-impl GameEngine {
-    /// Test-only constructor: use a specific enemy, all cards/skills ready (cooldown 0).
-    fn new_with_enemy(enemy: Box<dyn Combatant>) -> Self {
-        let mut player = Player::new("勇者", 3);
-        player.set_passive(PassiveSkill::Prepared);
-        player.add_card(create_attack_card());
-        player.add_card(create_defense_card());
-        player.equip_skill(create_emergency_heal());
-        player.equip_skill(create_fast_cycle());
+            self.enemy_intents
+                .retain(|intent| self.enemies[intent.enemy].is_alive());
+            while let Some(i) = self.enemy_intents.iter().position(|intent| {
+                now >= round_start + intent.delay
+                    && self.enemies[intent.enemy].deck()[intent.card].is_ready()
+            }) {
+                let intent = self.enemy_intents.remove(i);
+                self.play_enemy_card(intent.enemy, intent.card);
+                self.flush_events(frontend);
+                enemies_acted.push(intent.enemy);
+                if !self.player.is_alive() {
+                    break;
+                }
+                self.enemy_intents
+                    .retain(|intent| self.enemies[intent.enemy].is_alive());
+            }
 
-        let mut enemy_card = create_attack_card();
-        enemy_card.set_initial_cooldown_ms(0);
+            if !self.player.is_alive() || !self.enemies_alive() {
+                break;
+            }
 
-        Self {
-            player,
-            enemy,
-            round: 1,
-            stage: 1,
-            enemy_card,
+            let now_after = self.clock.now();
+            if now_after >= round_end {
+                break;
+            }
+            let remaining = round_end - now_after;
+            self.clock.sleep(remaining.min(LOOP_TICK));
         }
-    }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::enemy::{Dragon, ForestWolf, GoblinRogue, SkeletonMage, Slime};
+        let elapsed_ms = self.clock.now().saturating_sub(last_tick).as_millis() as u64;
+        self.tick_cooldowns(elapsed_ms);
 
-    fn prepare_ready_actions(engine: &mut GameEngine) {
-        for card in &mut engine.player.hand {
-            card.set_initial_cooldown_ms(0);
+        if self.player.is_alive() && !player_did_any_action {
+            self.emit(GameEvent::Idle { side: Side::Player });
         }
-        for skill in &mut engine.player.skills {
-            skill.remaining_cooldown_ms = 0;
+        for index in self.living_enemies() {
+            if !enemies_acted.contains(&index) {
+                self.emit(GameEvent::Idle {
+                    side: Side::Enemy(index),
+                });
+            }
         }
+        self.flush_events(frontend);
     }
 
-    // ── Battle simulation tests ─────────────────────────────────────────────
+    /// The player followed by every enemy still standing, in the order hooks fire.
+    fn sides(&self) -> Vec<Side> {
+        let enemies = self.living_enemies().into_iter().map(Side::Enemy);
+        std::iter::once(Side::Player).chain(enemies).collect()
+    }
+
+    /// Who is standing: the player first, then every enemy.
+    fn standing(&self) -> Vec<bool> {
+        let enemies = self.enemies.iter().map(|e| e.is_alive());
+        std::iter::once(self.player.is_alive())
+            .chain(enemies)
+            .collect()
+    }
+
+    /// Whether anyone standing in an earlier [`GameEngine::standing`] has fallen since.
+    fn someone_fell(&self, before: &[bool]) -> bool {
+        self.standing()
+            .iter()
+            .zip(before)
+            .any(|(now, was)| *was && !*now)
+    }
+
+    /// Runs every `on_round_start` buff hook, player first.
+    fn trigger_round_start_buffs(&mut self) {
+        for target in self.sides() {
+            for (buff, effect) in self.combatant(target).buffs().round_start_effects() {
+                if !self.combatant(target).is_alive() {
+                    break;
+                }
+                self.emit(GameEvent::BuffTriggered { target, buff });
+                match effect {
+                    RoundStartEffect::Damage(amount) => {
+                        self.land_hit(amount, target);
+                    }
+                    RoundStartEffect::Heal(amount) => self.heal(target, amount),
+                }
+            }
+        }
+    }
+
+    /// The boss script, run at round start before enemies plan their moves: enters every
+    /// phase an enemy's HP has reached, telegraphs it, then resolves its enrage and summon.
+    /// A new deck starts cooling down like at the start of a battle.
+    fn advance_enemy_phases(&mut self) {
+        for index in 0..self.enemies.len() {
+            while self.enemies[index].is_alive() {
+                let Some(phase) = self.enemies[index].enter_next_phase() else {
+                    break;
+                };
+                if phase.deck.is_some() {
+                    for card in self.enemies[index].deck_mut() {
+                        card.set_initial_cooldown_ms(ENEMY_INITIAL_CARD_COOLDOWN_MS);
+                    }
+                }
+                self.emit(GameEvent::PhaseChanged {
+                    enemy: index,
+                    phase: self.enemies[index].phase(),
+                    message: phase.message,
+                });
+                if let Some(effect) = &phase.enrage {
+                    self.resolve_effect(Side::Enemy(index), effect);
+                }
+                if let Some(def) = phase.summon.and_then(|id| self.data.enemies.get(&id)) {
+                    let summoned = Self::spawn(def);
+                    self.add_enemy(summoned);
+                }
+            }
+        }
+    }
+
+    /// Brings a split copy or a summon into the battle, if there is room for it.
+    fn add_enemy(&mut self, enemy: Enemy) {
+        if self.living_enemies().len() >= MAX_ENEMIES {
+            self.emit(GameEvent::BattlefieldFull);
+            return;
+        }
+        self.enemies.push(enemy);
+        self.emit(GameEvent::EnemyJoined {
+            enemy: self.enemies.len() - 1,
+        });
+    }
+
+    /// Fires one hook of every passive `side` holds, in the order they were gained.
+    /// Stops once a side that was still standing falls.
+    fn trigger_passives(
+        &mut self,
+        side: Side,
+        hook: fn(&PassiveHooks) -> Option<PassiveHook>,
+        damage: i32,
+    ) {
+        let standing = self.standing();
+        let passives = self.combatant(side).passives().to_vec();
+        for def in PassiveRegistry::builtin().resolve(&passives) {
+            if self.someone_fell(&standing) {
+                break;
+            }
+            let Some(hook) = hook(&def.hooks) else {
+                continue;
+            };
+            let holder = self.combatant(side);
+            let ctx = PassiveContext {
+                round: self.round,
+                damage,
+                hp: holder.hp(),
+                max_hp: holder.max_hp(),
+                roll: self.rng.gen(),
+            };
+            if let Some(effect) = hook(&ctx) {
+                self.emit(GameEvent::PassiveTriggered {
+                    side,
+                    passive: def.id.to_string(),
+                });
+                self.resolve_effect(side, &effect);
+            }
+        }
+    }
+
+    /// Fires one hook of every relic the player holds, in the order they were picked up.
+    /// Stops once the player falls, or once the last enemy does unless all were already down.
+    fn trigger_relics(&mut self, hook: fn(&RelicHooks) -> Option<RelicHook>, damage: i32) {
+        let enemies_were_alive = self.enemies_alive();
+        let relics: Vec<_> = self.player.relic_defs().collect();
+        for def in relics {
+            if !self.player.is_alive() || (enemies_were_alive && !self.enemies_alive()) {
+                break;
+            }
+            let Some(hook) = hook(&def.hooks) else {
+                continue;
+            };
+            let ctx = RelicContext {
+                round: self.round,
+                damage,
+                roll: self.rng.gen(),
+            };
+            if let Some(effect) = hook(&ctx) {
+                self.emit(GameEvent::RelicTriggered {
+                    relic: def.id.to_string(),
+                });
+                self.resolve_effect(Side::Player, &effect);
+            }
+        }
+    }
+
+    fn finish_round(&mut self) {
+        self.trigger_relics(|hooks| hooks.on_round_end, 0);
+        self.enemy_intents.clear();
+        for target in self.sides() {
+            for buff in self.combatant_mut(target).buffs_mut().tick() {
+                self.emit(GameEvent::BuffExpired { target, buff });
+            }
+        }
+        self.player.clear_shield();
+        for enemy in &mut self.enemies {
+            enemy.clear_shield();
+        }
+        self.player.deck_mut().discard_hand();
+        self.round += 1;
+    }
+
+    /// Draws up to `count` cards into the player's hand. Returns how many were drawn.
+    fn draw_cards(&mut self, count: usize) -> usize {
+        let drawn = self.player.deck_mut().draw(count, &mut self.rng);
+        if drawn.reshuffled {
+            self.emit(GameEvent::DeckReshuffled);
+        }
+        drawn.count
+    }
+
+    /// Decides which card enemy `index` plays this round and how long after the round
+    /// starts. Returns `None` if none of its cards can come off cooldown before the round ends.
+    fn plan_enemy_intent(&mut self, index: usize, round: Duration) -> Option<EnemyIntent> {
+        let round_ms = round.as_millis() as u64;
+        let (card, ready_ms) = self.enemies[index].plan_card(
+            round_ms.saturating_sub(1),
+            self.player.hp(),
+            &mut self.rng,
+        )?;
+
+        let latest_ms = round_ms.saturating_sub(300);
+        let delay_ms = if ready_ms >= latest_ms {
+            ready_ms
+        } else {
+            ready_ms + self.rng.gen_range(0..=latest_ms - ready_ms)
+        };
+
+        let card_ref = &self.enemies[index].deck()[card];
+        Some(EnemyIntent {
+            enemy: index,
+            card,
+            card_name: card_ref.name.clone(),
+            effect: card_ref.effect.clone(),
+            delay: Duration::from_millis(delay_ms),
+        })
+    }
+
+    /// Advances every cooldown by an explicit step in milliseconds.
+    fn tick_cooldowns(&mut self, elapsed_ms: u64) {
+        if elapsed_ms == 0 {
+            return;
+        }
+        for card in self.player.deck_mut().battle_cards_mut() {
+            card.tick_cooldown_ms(elapsed_ms);
+        }
+        for card in self.enemies.iter_mut().flat_map(|e| e.deck_mut()) {
+            card.tick_cooldown_ms(elapsed_ms);
+        }
+        self.player.tick_skill_cooldowns_ms(elapsed_ms);
+    }
+
+    /// Applies a player command and reports what happened through events.
+    pub fn execute(&mut self, command: Command) -> CommandOutcome {
+        match command {
+            Command::PlayCard { card, target } => self.play_card(card, target),
+            Command::UseSkill { skill, target } => self.use_skill(skill, target),
+            Command::BuyItem(index) => self.buy_item(index, None),
+            Command::BuyService { offer, card } => self.buy_item(offer, Some(card)),
+            Command::ReplaceSkill { offer, skill } => self.replace_skill(offer, skill),
+            Command::SellCard(index) => self.sell_card(index),
+            Command::SellSkill(index) => self.sell_skill(index),
+        }
+    }
+
+    fn reject(&mut self, reason: Rejection) -> CommandOutcome {
+        self.emit(GameEvent::ActionRejected(reason));
+        CommandOutcome::Rejected
+    }
+
+    /// Checks that a command's target is an enemy still standing, then aims at it.
+    fn take_aim(&mut self, target: Option<usize>) -> Result<(), Rejection> {
+        let Some(target) = target else {
+            return Ok(());
+        };
+        if !self.enemies.get(target).is_some_and(|e| e.is_alive()) {
+            return Err(Rejection::NoSuchTarget { target });
+        }
+        self.target = target;
+        Ok(())
+    }
+
+    fn play_card(&mut self, index: usize, target: Option<usize>) -> CommandOutcome {
+        let Some(card) = self.player.hand().get(index) else {
+            return self.reject(Rejection::NoSuchAction);
+        };
+        if !card.is_ready() {
+            let reason = Rejection::OnCooldown {
+                name: card.name.clone(),
+                remaining_secs: card.remaining_cooldown_secs(),
+            };
+            return self.reject(reason);
+        }
+        if self.player.energy() < card.energy_cost {
+            let reason = Rejection::NotEnoughEnergy {
+                name: card.name.clone(),
+                cost: card.energy_cost,
+                energy: self.player.energy(),
+            };
+            return self.reject(reason);
+        }
+        if card.is_spell() && self.player.mana() < card.mana_cost {
+            let reason = Rejection::NotEnoughMana {
+                name: card.name.clone(),
+                cost: card.mana_cost,
+                mana: self.player.mana(),
+            };
+            return self.reject(reason);
+        }
+        if let Err(reason) = self.take_aim(target) {
+            return self.reject(reason);
+        }
+
+        let cooldown_reduction_ms = self.player.relic_modifiers().cooldown_reduction_ms;
+        let Some(mut card) = self.player.deck_mut().take_from_hand(index) else {
+            return self.reject(Rejection::NoSuchAction);
+        };
+        card.trigger_cooldown();
+        card.reduce_cooldown_ms(cooldown_reduction_ms);
+        self.player.spend_energy(card.energy_cost);
+        if card.is_spell() {
+            self.player.spend_mana(card.mana_cost);
+        }
+
+        if !self.skip_if_stunned(Side::Player, &card.name) {
+            self.emit(GameEvent::CardPlayed {
+                side: Side::Player,
+                name: card.name.clone(),
+            });
+            self.resolve_player_action(&card.effect);
+        }
+        self.put_away(card);
+        CommandOutcome::CardPlayed
+    }
+
+    /// Sends a card the player played to the discard pile, or out of the battle if it
+    /// exhausts.
+    fn put_away(&mut self, card: Card) {
+        if card.exhaust {
+            self.emit(GameEvent::CardExhausted {
+                name: card.name.clone(),
+            });
+        }
+        self.player.deck_mut().put_away(card);
+    }
+
+    fn use_skill(&mut self, index: usize, target: Option<usize>) -> CommandOutcome {
+        let Some(skill) = self.player.skills.get(index) else {
+            return self.reject(Rejection::NoSuchAction);
+        };
+        if !skill.is_ready() {
+            let reason = Rejection::OnCooldown {
+                name: skill.name.clone(),
+                remaining_secs: skill.remaining_cooldown_secs(),
+            };
+            return self.reject(reason);
+        }
+        let skill = skill.clone();
+        if let Err(reason) = self.take_aim(target) {
+            return self.reject(reason);
+        }
+
+        if self.skip_if_stunned(Side::Player, &skill.name) {
+            self.start_skill_cooldown(index);
+            return CommandOutcome::SkillUsed;
+        }
+        self.emit(GameEvent::SkillUsed {
+            name: skill.name.clone(),
+        });
+        self.resolve_player_action(&skill.effect);
+        self.start_skill_cooldown(index);
+        CommandOutcome::SkillUsed
+    }
+
+    fn start_skill_cooldown(&mut self, index: usize) {
+        let reduction_ms = self.player.relic_modifiers().cooldown_reduction_ms;
+        let skill = &mut self.player.skills[index];
+        skill.trigger_cooldown();
+        skill.tick_cooldown_ms(reduction_ms);
+    }
+
+    /// Resolves a card or skill the player used, then lets relics react to an attack.
+    fn resolve_player_action(&mut self, effect: &Effect) {
+        self.resolve_effect(Side::Player, effect);
+        let damage = effect.damage();
+        if damage > 0 {
+            self.trigger_relics(|hooks| hooks.on_player_attack, damage);
+        }
+    }
+
+    /// Plays the card at `card` in enemy `enemy`'s deck.
+    fn play_enemy_card(&mut self, enemy: usize, card: usize) {
+        let side = Side::Enemy(enemy);
+        let card = &mut self.enemies[enemy].deck_mut()[card];
+        let card_name = card.name.clone();
+        let effect = card.effect.clone();
+        card.trigger_cooldown();
+
+        if self.skip_if_stunned(side, &card_name) {
+            return;
+        }
+        self.emit(GameEvent::CardPlayed {
+            side,
+            name: card_name,
+        });
+        self.resolve_effect(side, &effect);
+    }
+
+    /// Who `side`'s attacks and debuffs land on: the player's target for the player,
+    /// the player for any enemy.
+    fn opponent_of(&self, side: Side) -> Side {
+        match side {
+            Side::Player => Side::Enemy(self.target()),
+            Side::Enemy(_) => Side::Player,
+        }
+    }
+
+    /// Carries out a card or skill effect played by `caster`. A composite stops early
+    /// once anyone is defeated.
+    fn resolve_effect(&mut self, caster: Side, effect: &Effect) {
+        let bonus = match caster {
+            Side::Player => self.player.relic_modifiers(),
+            Side::Enemy(_) => RelicModifiers::NONE,
+        };
+        match effect {
+            Effect::Damage(amount) => {
+                let target = self.opponent_of(caster);
+                self.deal_damage(caster, target, amount + bonus.bonus_damage)
+            }
+            Effect::DamageAll(amount) => {
+                let targets = match caster {
+                    Side::Player => self.living_enemies().into_iter().map(Side::Enemy).collect(),
+                    Side::Enemy(_) => vec![Side::Player],
+                };
+                for target in targets {
+                    if !self.player.is_alive() || !self.combatant(caster).is_alive() {
+                        break;
+                    }
+                    if self.combatant(target).is_alive() {
+                        self.deal_damage(caster, target, amount + bonus.bonus_damage);
+                    }
+                }
+            }
+            Effect::Shield(amount) => self.gain_shield(caster, amount + bonus.bonus_shield),
+            Effect::Heal(amount) => self.heal(caster, amount + bonus.bonus_heal),
+            Effect::DrawCards(count) => {
+                if caster == Side::Player {
+                    let count = self.draw_cards(*count as usize);
+                    self.emit(GameEvent::CardsDrawn { count });
+                }
+            }
+            Effect::GainGold(amount) => {
+                if caster == Side::Player {
+                    self.player.add_gold(*amount);
+                    self.emit(GameEvent::GoldGained {
+                        amount: *amount,
+                        base: *amount,
+                        bonus: 0,
+                    });
+                }
+            }
+            Effect::StealGold(amount) => {
+                let amount = (*amount).min(self.player.gold());
+                if let (Side::Enemy(enemy), true) = (caster, amount > 0) {
+                    self.player.add_gold(-amount);
+                    self.enemies[enemy].steal_gold(amount);
+                    self.emit(GameEvent::GoldStolen { enemy, amount });
+                }
+            }
+            Effect::Split => {
+                if let Side::Enemy(enemy) = caster {
+                    let copy = self.enemies[enemy].split_off();
+                    self.emit(GameEvent::EnemySplit { enemy });
+                    self.add_enemy(copy);
+                }
+            }
+            Effect::ReduceAllCardCooldownMs(amount_ms) => {
+                match caster {
+                    Side::Player => {
+                        for card in self.player.deck_mut().battle_cards_mut() {
+                            card.reduce_cooldown_ms(*amount_ms);
+                        }
+                    }
+                    Side::Enemy(enemy) => {
+                        for card in self.enemies[enemy].deck_mut() {
+                            card.reduce_cooldown_ms(*amount_ms);
+                        }
+                    }
+                }
+                self.emit(GameEvent::CardCooldownsReduced {
+                    side: caster,
+                    amount_ms: *amount_ms,
+                });
+            }
+            Effect::ApplyBuff {
+                target,
+                id,
+                stacks,
+                duration,
+            } => {
+                let side = match target {
+                    EffectTarget::Caster => caster,
+                    EffectTarget::Opponent => self.opponent_of(caster),
+                };
+                if self
+                    .combatant_mut(side)
+                    .buffs_mut()
+                    .apply(id, *stacks, *duration)
+                {
+                    self.emit(GameEvent::BuffApplied {
+                        target: side,
+                        buff: id.clone(),
+                        stacks: *stacks,
+                    });
+                }
+            }
+            Effect::Composite(effects) => {
+                let standing = self.standing();
+                for effect in effects {
+                    if self.someone_fell(&standing) {
+                        break;
+                    }
+                    self.resolve_effect(caster, effect);
+                }
+            }
+        }
+    }
+
+    /// Uses up a buff that skips `side`'s next action, reporting the wasted `action`.
+    fn skip_if_stunned(&mut self, side: Side, action: &str) -> bool {
+        let Some(buff) = self.combatant_mut(side).buffs_mut().take_skipped_action() else {
+            return false;
+        };
+        self.emit(GameEvent::ActionSkipped {
+            side,
+            name: action.to_string(),
+            buff,
+        });
+        true
+    }
+
+    fn combatant(&self, side: Side) -> &dyn Combatant {
+        match side {
+            Side::Player => &self.player,
+            Side::Enemy(index) => &self.enemies[index],
+        }
+    }
+
+    fn combatant_mut(&mut self, side: Side) -> &mut dyn Combatant {
+        match side {
+            Side::Player => &mut self.player,
+            Side::Enemy(index) => &mut self.enemies[index],
+        }
+    }
+
+    /// `attacker` attacks `target`: the attacker's buffs adjust the amount, then dodge is
+    /// rolled and the hit lands. Reflected damage goes back to the attacker.
+    fn deal_damage(&mut self, attacker: Side, target: Side, amount: i32) {
+        let amount = self.combatant(attacker).buffs().damage_dealt(amount);
+
+        let dodge = self.combatant(target).dodge_chance();
+        if dodge > 0.0 && self.rng.gen_bool(dodge) {
+            self.emit(GameEvent::Dodged { target });
+            return;
+        }
+
+        let hp_before = self.combatant(target).hp();
+        let shield_before = self.combatant(target).shield();
+        let reflected = self.land_hit(amount, target);
+        if reflected > 0 && self.combatant(attacker).is_alive() {
+            self.emit(GameEvent::DamageReflected {
+                target: attacker,
+                amount: reflected,
+            });
+            self.land_hit(reflected, attacker);
+        }
+
+        let victim = self.combatant(target);
+        if victim.hp() + victim.shield() < hp_before + shield_before {
+            let lost = hp_before - victim.hp();
+            self.trigger_passives(target, |hooks| hooks.on_hit, lost);
+            if self.combatant(attacker).is_alive() {
+                self.trigger_passives(attacker, |hooks| hooks.on_attack, lost);
+            }
+            if target == Side::Player {
+                self.trigger_relics(|hooks| hooks.on_take_damage, lost);
+            }
+        }
+    }
+
+    /// Applies damage that cannot be dodged, reporting shield / damage.
+    /// Returns how much the target's buffs reflect.
+    fn land_hit(&mut self, amount: i32, target: Side) -> i32 {
+        let combatant = self.combatant_mut(target);
+        let hit = combatant.buffs().incoming(amount);
+        let shield_before = combatant.shield();
+        combatant.take_damage(amount);
+        let absorbed = shield_before - combatant.shield();
+        let actual = hit.amount - absorbed;
+
+        if absorbed > 0 {
+            self.emit(GameEvent::ShieldAbsorbed {
+                target,
+                amount: absorbed,
+            });
+        }
+        if actual > 0 {
+            self.emit(GameEvent::DamageDealt {
+                target,
+                amount: actual,
+            });
+        } else {
+            self.emit(GameEvent::AttackBlocked { target });
+        }
+        hit.reflected
+    }
+
+    fn gain_shield(&mut self, target: Side, amount: i32) {
+        self.combatant_mut(target).add_shield(amount);
+        self.emit(GameEvent::ShieldGained { target, amount });
+    }
+
+    fn heal(&mut self, target: Side, amount: i32) {
+        let healed = self.combatant_mut(target).heal(amount);
+        self.emit(GameEvent::Healed {
+            target,
+            amount: healed,
+        });
+    }
+
+    /// Takes back whatever the enemies stole.
+    fn recover_stolen_gold(&mut self) {
+        let amount: i32 = self.enemies.iter_mut().map(Enemy::take_stolen_gold).sum();
+        if amount > 0 {
+            self.player.add_gold(amount);
+            self.emit(GameEvent::GoldRecovered { amount });
+        }
+    }
+
+    /// Fires kill hooks once per enemy defeated, rolls the victory gold and reports the win.
+    fn claim_victory(&mut self) {
+        for _ in 0..self.enemies.len() {
+            self.trigger_passives(Side::Player, |hooks| hooks.on_kill, 0);
+            self.trigger_relics(|hooks| hooks.on_enemy_kill, 0);
+        }
+        self.emit(GameEvent::BattleWon {
+            is_boss: self.is_boss_stage(),
+        });
+        self.recover_stolen_gold();
+
+        let base = if self.is_boss_stage() {
+            self.rng.gen_range(5..=8)
+        } else if self.is_elite_stage() {
+            self.rng.gen_range(3..=5)
+        } else {
+            self.rng.gen_range(1..=3)
+        };
+        let bonus = self.player.victory_bonus_gold();
+        let amount = base + bonus;
+        self.player.add_gold(amount);
+
+        self.emit(GameEvent::GoldGained {
+            amount,
+            base,
+            bonus,
+        });
+        self.trigger_passives(Side::Player, |hooks| hooks.on_victory, 0);
+        self.emit(GameEvent::BattleSummary);
+    }
+}
+
+/// How many monsters a normal battle on `floor`, counting from 0, brings.
+fn encounter_size(floor: usize, rng: &mut impl Rng) -> usize {
+    let mut count = 1;
+    if floor >= SECOND_ENEMY_FLOOR && rng.gen_bool(EXTRA_ENEMY_CHANCE) {
+        count += 1;
+        if floor >= THIRD_ENEMY_FLOOR && rng.gen_bool(EXTRA_ENEMY_CHANCE) {
+            count += 1;
+        }
+    }
+    count
+}
+
+/// How a call to [`GameEngine::run`] ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RunOutcome {
+    Cleared,
+    Defeated,
+    /// The player chose to stop between stages; save with [`GameEngine::snapshot`].
+    Suspended,
+}
+
+impl Default for GameEngine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// One choice of a post-battle reward.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RewardItem {
+    /// A card from the catalog, by id.
+    Card(String),
+    /// A skill, by the id [`skill::create`] takes.
+    Skill(&'static str),
+    /// A relic, by id.
+    Relic(&'static str),
+}
+
+/// Something the shop can sell.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ShopItem {
+    /// A card from the catalog, by id.
+    Card(String),
+    /// A skill, by the id [`skill::create`] takes.
+    Skill(&'static str),
+    /// A relic, by id.
+    Relic(&'static str),
+    /// Upgrades an owned card of the buyer's choice.
+    Upgrade,
+    /// Removes an owned card of the buyer's choice for good.
+    RemoveCard,
+    /// Restores HP to full.
+    Heal,
+    /// Raises max HP by this much.
+    MaxHp(i32),
+}
+
+impl ShopItem {
+    /// Whether buying this takes a skill slot.
+    pub fn is_skill(&self) -> bool {
+        matches!(self, ShopItem::Skill(_))
+    }
+
+    /// Whether this is a service for an owned card, bought with [`Command::BuyService`].
+    pub fn needs_card(&self) -> bool {
+        matches!(self, ShopItem::Upgrade | ShopItem::RemoveCard)
+    }
+}
+
+/// A priced entry on the shop shelf.
+#[derive(Debug, Clone)]
+pub struct ShopOffer {
+    pub item: ShopItem,
+    pub description: String,
+    pub price: i32,
+    /// Marked down to half price for this visit.
+    pub on_sale: bool,
+}
+
+#[cfg(test)]
+use crate::game::clock::ManualClock;
+#[cfg(test)]
+use crate::game::map::MapNode;
+
+#[cfg(test)]
+impl GameEngine {
+    /// Test-only constructor: use a specific enemy, all cards/skills ready (cooldown 0),
+    /// standing on a map of a single battle. The hand holds attack then defense, unshuffled.
+    fn new_with_enemy(enemy: Enemy) -> Self {
+        let seed = 0;
+        let mut player = Player::new("勇者", 3);
+        player.add_passive(PREPARED);
+        let data = GameData::builtin().clone();
+        for id in ["attack", "defense"] {
+            player.add_card(data.cards.card(id));
+            player.deck_mut().add_to_hand(data.cards.card(id));
+        }
+        player.equip_skill(create_emergency_heal());
+        player.equip_skill(create_fast_cycle());
+
+        let mut map = RunMap::from_floors(vec![vec![MapNode::new(NodeKind::Battle, vec![])]]);
+        map.advance(0);
+
+        Self {
+            player,
+            enemies: vec![enemy],
+            target: 0,
+            round: 1,
+            map,
+            data,
+            seed,
+            stage_seed: seed,
+            rng: StdRng::seed_from_u64(seed),
+            clock: Box::new(ManualClock::new()),
+            resumed: false,
+            shop_offers: Vec::new(),
+            shop_open: false,
+            relic_offers: Vec::new(),
+            reward_offers: Vec::new(),
+            enemy_intents: Vec::new(),
+            round_started_at: Duration::ZERO,
+            events: Vec::new(),
+        }
+    }
+
+    /// Adds a card to the deck and a ready copy to the end of the hand.
+    fn give_card(&mut self, id: &str) {
+        let card = self.data.cards.card(id);
+        self.player.add_card(card.clone());
+        self.player.deck_mut().add_to_hand(card);
+    }
+
+    /// Discards the hand and deals a ready copy of every owned card back into it, in the
+    /// order gained, so a test can play the same card again.
+    fn redeal(&mut self) {
+        let cards = self.player.deck().cards().to_vec();
+        let deck = self.player.deck_mut();
+        deck.discard_hand();
+        for card in cards {
+            deck.add_to_hand(card);
+        }
+    }
+
+    /// Like [`GameEngine::new_with_enemy`], with several enemies in the battle.
+    fn new_with_enemies(enemies: Vec<Enemy>) -> Self {
+        let mut engine = Self::new_with_enemy(enemies[0].clone());
+        engine.enemies = enemies;
+        engine
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::save;
+    use crate::mechanics::buff::{FRAGILE, NOURISH, POISON, STRENGTH, STUN, THORNS};
+    use crate::mechanics::passive::{BLOODTHIRST, COUNTER, DODGE_MASTER, UNDEAD_SHIELD};
+    use crate::skill::vampiric_touch::create_vampiric_touch;
+    use crate::skill::war_cry::create_war_cry;
+    use std::collections::VecDeque;
+
+    /// A built-in enemy with its HP overridden.
+    fn test_enemy(id: &str, max_hp: i32) -> Enemy {
+        let mut def = GameData::builtin().enemies.get(id).unwrap().clone();
+        def.max_hp = max_hp;
+        def.spawn()
+    }
+
+    /// Plays queued commands as soon as it is polled and records every event.
+    struct ScriptedFrontend {
+        commands: VecDeque<Command>,
+        events: Vec<GameEvent>,
+    }
+
+    impl ScriptedFrontend {
+        fn new(commands: Vec<Command>) -> Self {
+            Self {
+                commands: commands.into(),
+                events: Vec::new(),
+            }
+        }
+    }
+
+    impl Frontend for ScriptedFrontend {
+        fn on_event(&mut self, _engine: &GameEngine, event: &GameEvent) {
+            self.events.push(event.clone());
+        }
+
+        fn poll_command(&mut self, _engine: &GameEngine) -> Option<Command> {
+            self.commands.pop_front()
+        }
+
+        fn shop_command(&mut self, _engine: &GameEngine) -> Option<Command> {
+            None
+        }
+    }
+
+    /// Takes the first relic on offer; does nothing else.
+    struct RelicPicker;
+
+    impl Frontend for RelicPicker {
+        fn on_event(&mut self, _engine: &GameEngine, _event: &GameEvent) {}
+
+        fn poll_command(&mut self, _engine: &GameEngine) -> Option<Command> {
+            None
+        }
+
+        fn shop_command(&mut self, _engine: &GameEngine) -> Option<Command> {
+            None
+        }
+
+        fn choose_relic(&mut self, _engine: &GameEngine) -> Option<usize> {
+            Some(0)
+        }
+    }
+
+    /// Attacks whenever the attack card is ready; never shops.
+    struct AttackBot {
+        events: Vec<GameEvent>,
+        suspend: bool,
+    }
+
+    impl AttackBot {
+        fn new() -> Self {
+            Self {
+                events: Vec::new(),
+                suspend: false,
+            }
+        }
+    }
+
+    impl Frontend for AttackBot {
+        fn on_event(&mut self, _engine: &GameEngine, event: &GameEvent) {
+            self.events.push(event.clone());
+        }
+
+        fn poll_command(&mut self, engine: &GameEngine) -> Option<Command> {
+            let player = engine.player();
+            let card = player.hand().iter().position(|card| {
+                card.effect.damage() > 0 && card.is_ready() && player.can_afford(card)
+            })?;
+            Some(Command::PlayCard { card, target: None })
+        }
+
+        fn shop_command(&mut self, _engine: &GameEngine) -> Option<Command> {
+            None
+        }
+
+        fn wants_to_suspend(&mut self, _engine: &GameEngine) -> bool {
+            self.suspend
+        }
+    }
+
+    /// Plays a card as the first action of a fresh round.
+    fn play_fresh(engine: &mut GameEngine, index: usize) -> CommandOutcome {
+        engine.player.refill_energy();
+        engine.execute(Command::PlayCard {
+            card: index,
+            target: None,
+        })
+    }
+
+    /// Deals the whole deck into the hand, in order, with every card and skill ready.
+    fn prepare_ready_actions(engine: &mut GameEngine) {
+        engine.redeal();
+        for skill in &mut engine.player.skills {
+            skill.remaining_cooldown_ms = 0;
+        }
+    }
+
+    // ── Battle simulation tests ─────────────────────────────────────────────
+
+    #[test]
+    fn sim_attack_card_damages_enemy() {
+        let mut engine = GameEngine::new_with_enemy(test_enemy("slime", 3));
+        let hp_before = engine.enemies[0].hp();
+        play_fresh(&mut engine, 0);
+        assert!(engine.enemies[0].hp() < hp_before, "攻击牌应减少敌方 HP");
+    }
+
+    #[test]
+    fn sim_defense_card_shields_player() {
+        let mut engine = GameEngine::new_with_enemy(test_enemy("slime", 3));
+        assert_eq!(engine.player.shield(), 0);
+        play_fresh(&mut engine, 1);
+        assert!(engine.player.shield() > 0, "防御牌应给予玩家护盾");
+    }
+
+    #[test]
+    fn sim_enemy_action_damages_player() {
+        let mut engine = GameEngine::new_with_enemy(test_enemy("slime", 3));
+        let hp_before = engine.player.hp();
+        engine.play_enemy_card(0, 0);
+        assert!(engine.player.hp() < hp_before, "敌方攻击牌应减少玩家 HP");
+    }
+
+    #[test]
+    fn sim_shield_fully_absorbs_enemy_hit() {
+        let mut engine = GameEngine::new_with_enemy(test_enemy("slime", 3));
+        play_fresh(&mut engine, 1);
+        let hp_before = engine.player.hp();
+        engine.play_enemy_card(0, 0);
+        assert_eq!(engine.player.hp(), hp_before, "护盾应完全吸收 1 点伤害");
+        assert_eq!(engine.player.shield(), 0, "护盾耗尽后应归零");
+    }
+
+    #[test]
+    fn sim_player_kills_slime_in_two_hits() {
+        let mut engine = GameEngine::new_with_enemy(test_enemy("slime", 2));
+        play_fresh(&mut engine, 0);
+        assert!(engine.enemies[0].is_alive(), "第一击后史莱姆仍存活");
+        engine.redeal();
+        play_fresh(&mut engine, 0);
+        assert!(!engine.enemies[0].is_alive(), "第二击后史莱姆应被击败");
+    }
+
+    #[test]
+    fn sim_emergency_heal_recovers_hp() {
+        let mut engine = GameEngine::new_with_enemy(test_enemy("slime", 3));
+        engine.player.take_damage(2);
+        assert_eq!(engine.player.hp(), 1);
+        engine.execute(Command::UseSkill {
+            skill: 0,
+            target: None,
+        });
+        assert!(engine.player.hp() > 1, "急救技能应恢复 HP");
+    }
+
+    #[test]
+    fn sim_fast_cycle_reduces_card_cooldowns() {
+        let mut engine = GameEngine::new_with_enemy(test_enemy("slime", 3));
+        for card in engine.player.hand_mut() {
+            card.trigger_cooldown();
+        }
+        assert!(!engine.player.hand()[0].is_ready());
+        for skill in &mut engine.player.skills {
+            skill.remaining_cooldown_ms = 0;
+        }
+        engine.execute(Command::UseSkill {
+            skill: 1,
+            target: None,
+        });
+        assert!(engine.player.hand()[0].remaining_cooldown_ms() < 3000);
+    }
+
+    #[test]
+    fn sim_goblin_rogue_dodge_is_probabilistic() {
+        let mut engine = GameEngine::new_with_enemy(test_enemy("goblin_rogue", 1000));
+        const TRIALS: usize = 200;
+        let mut dodge_count = 0;
+
+        for _ in 0..TRIALS {
+            let hp_before = engine.enemies[0].hp();
+            engine.redeal();
+            play_fresh(&mut engine, 0);
+            if engine.enemies[0].hp() == hp_before {
+                dodge_count += 1;
+            }
+        }
+
+        let dodge_rate = dodge_count as f64 / TRIALS as f64;
+        assert!(
+            dodge_rate < 0.25,
+            "闪避率 {:.1}% 过高（期望 ~10%）",
+            dodge_rate * 100.0
+        );
+        assert!(
+            dodge_rate > 0.02,
+            "闪避率 {:.1}% 过低（期望 ~10%）",
+            dodge_rate * 100.0
+        );
+    }
 
     #[test]
-    fn sim_attack_card_damages_enemy() {
-        let mut engine = GameEngine::new_with_enemy(Box::new(Slime::new("史莱姆", 3)));
-        let hp_before = engine.enemy.hp();
-        engine.try_execute_player_action("1", false);
-        assert!(engine.enemy.hp() < hp_before, "攻击牌应减少敌方 HP");
+    fn sim_slime_never_dodges() {
+        let mut engine = GameEngine::new_with_enemy(test_enemy("slime", 1000));
+        for _ in 0..100 {
+            let hp_before = engine.enemies[0].hp();
+            engine.redeal();
+            play_fresh(&mut engine, 0);
+            assert_eq!(
+                engine.enemies[0].hp(),
+                hp_before - 1,
+                "史莱姆不应闪避任何攻击"
+            );
+        }
+    }
+
+    #[test]
+    fn cards_spend_energy_and_skills_do_not() {
+        let mut engine = GameEngine::new();
+        prepare_ready_actions(&mut engine);
+
+        assert_eq!(
+            engine.execute(Command::PlayCard {
+                card: 0,
+                target: None
+            }),
+            CommandOutcome::CardPlayed
+        );
+        assert_eq!(
+            engine.execute(Command::PlayCard {
+                card: 1,
+                target: None
+            }),
+            CommandOutcome::CardPlayed
+        );
+        assert_eq!(engine.player.energy(), engine.player.max_energy() - 2);
+        assert_eq!(
+            engine.execute(Command::UseSkill {
+                skill: 0,
+                target: None
+            }),
+            CommandOutcome::SkillUsed
+        );
+        assert_eq!(
+            engine.execute(Command::UseSkill {
+                skill: 1,
+                target: None
+            }),
+            CommandOutcome::SkillUsed
+        );
+        assert_eq!(engine.player.energy(), engine.player.max_energy() - 2);
+    }
+
+    #[test]
+    fn cards_are_rejected_once_energy_runs_out() {
+        let mut engine = GameEngine::new();
+        prepare_ready_actions(&mut engine);
+        engine.give_card("critical_strike");
+        let strike = engine.player.hand().len() - 1;
+        engine.player.spend_energy(engine.player.max_energy() - 1);
+        engine.drain_events();
+
+        assert_eq!(
+            engine.execute(Command::PlayCard {
+                card: strike,
+                target: None
+            }),
+            CommandOutcome::Rejected
+        );
+        assert_eq!(
+            engine.drain_events(),
+            vec![GameEvent::ActionRejected(Rejection::NotEnoughEnergy {
+                name: "暴击".to_string(),
+                cost: 2,
+                energy: 1,
+            })]
+        );
+        assert_eq!(
+            engine.execute(Command::PlayCard {
+                card: 0,
+                target: None
+            }),
+            CommandOutcome::CardPlayed
+        );
+        assert_eq!(
+            engine.execute(Command::PlayCard {
+                card: 1,
+                target: None
+            }),
+            CommandOutcome::Rejected
+        );
+    }
+
+    #[test]
+    fn energy_refills_at_round_start() {
+        let mut engine = GameEngine::new_with_enemy(test_enemy("slime", 10));
+        engine.player.spend_energy(engine.player.max_energy());
+        engine.play_round(&mut ScriptedFrontend::new(vec![]));
+        assert_eq!(engine.player.energy(), engine.player.max_energy());
+    }
+
+    // ── New feature tests ───────────────────────────────────────────────────
+
+    #[test]
+    fn sim_heal_card_recovers_hp() {
+        let mut engine = GameEngine::new_with_enemy(test_enemy("slime", 3));
+        engine.give_card("heal");
+        engine.player.take_damage(2);
+        assert_eq!(engine.player.hp(), 1);
+        // Heal card is index 3 (attack=1, defense=2, heal=3)
+        play_fresh(&mut engine, 2);
+        assert_eq!(engine.player.hp(), 2, "治愈卡应恢复 1 HP");
+    }
+
+    #[test]
+    fn sim_critical_strike_deals_two_damage() {
+        let mut engine = GameEngine::new_with_enemy(test_enemy("slime", 5));
+        engine.give_card("critical_strike");
+        let hp_before = engine.enemies[0].hp();
+        // Critical strike is index 3
+        play_fresh(&mut engine, 2);
+        assert_eq!(
+            engine.enemies[0].hp(),
+            hp_before - 2,
+            "暴击卡应造成 2 点伤害"
+        );
+    }
+
+    #[test]
+    fn sim_vampiric_touch_damages_and_heals() {
+        let mut engine = GameEngine::new_with_enemy(test_enemy("slime", 5));
+        // Replace the 2nd default skill with vampiric_touch
+        engine.player.skills[1] = create_vampiric_touch();
+        engine.player.take_damage(1); // HP: 2
+        let enemy_hp_before = engine.enemies[0].hp();
+        engine.execute(Command::UseSkill {
+            skill: 1,
+            target: None,
+        });
+        assert!(
+            engine.enemies[0].hp() < enemy_hp_before,
+            "吸血之触应造成伤害"
+        );
+        assert_eq!(engine.player.hp(), 3, "吸血之触应恢复 HP");
+    }
+
+    #[test]
+    fn sim_war_cry_gives_shield() {
+        let mut engine = GameEngine::new_with_enemy(test_enemy("slime", 3));
+        // Replace the 2nd default skill with war_cry
+        engine.player.skills[1] = create_war_cry();
+        assert_eq!(engine.player.shield(), 0);
+        engine.execute(Command::UseSkill {
+            skill: 1,
+            target: None,
+        });
+        assert_eq!(engine.player.shield(), 2, "战吼应给予 2 点护盾");
+    }
+
+    #[test]
+    fn sim_skeleton_mage_starts_with_shield() {
+        let mut engine = GameEngine::new_with_enemy(test_enemy("skeleton_mage", 5));
+        engine.start_battle();
+        assert_eq!(engine.enemies[0].shield(), 1, "骷髅法师应初始拥有 1 点护盾");
+        assert!(engine
+            .drain_events()
+            .contains(&GameEvent::PassiveTriggered {
+                side: Side::Enemy(0),
+                passive: UNDEAD_SHIELD.to_string(),
+            }));
+    }
+
+    #[test]
+    fn sim_forest_wolf_has_dodge() {
+        let engine = GameEngine::new_with_enemy(test_enemy("forest_wolf", 3));
+        assert!(
+            (engine.enemies[0].dodge_chance() - 0.15).abs() < f64::EPSILON,
+            "森林狼应有 15% 闪避率"
+        );
+    }
+
+    #[test]
+    fn sim_dragon_boss_high_hp() {
+        let engine = GameEngine::new_with_enemy(test_enemy("dragon", 8));
+        assert_eq!(engine.enemies[0].hp(), 8);
+        assert_eq!(engine.enemies[0].max_hp(), 8);
+    }
+
+    #[test]
+    fn sim_boss_card_deals_two_damage() {
+        let mut engine = GameEngine::new_with_enemy(test_enemy("dragon", 8));
+        assert_eq!(engine.enemies[0].deck()[0].name, "龙息");
+        let hp_before = engine.player.hp();
+        engine.play_enemy_card(0, 0);
+        assert_eq!(
+            engine.player.hp(),
+            hp_before - 2,
+            "Boss 龙息应造成 2 点伤害"
+        );
+    }
+
+    #[test]
+    fn same_seed_replays_enemies_and_rewards() {
+        let mut a = GameEngine::with_seed(42);
+        let mut b = GameEngine::with_seed(42);
+        assert_eq!(a.seed(), 42);
+        assert_eq!(a.enemies[0].name(), b.enemies[0].name());
+        for _ in 0..5 {
+            a.claim_victory();
+            b.claim_victory();
+        }
+        assert_eq!(a.player.gold(), b.player.gold());
+        for _ in 0..5 {
+            assert_eq!(
+                a.data.enemies.pick_normal(&mut a.rng).id,
+                b.data.enemies.pick_normal(&mut b.rng).id
+            );
+        }
+    }
+
+    #[test]
+    fn same_seed_replays_dodge_rolls() {
+        let mut a = GameEngine::new_with_enemy(test_enemy("goblin_rogue", 1000));
+        let mut b = GameEngine::new_with_enemy(test_enemy("goblin_rogue", 1000));
+        for _ in 0..50 {
+            a.redeal();
+            b.redeal();
+            play_fresh(&mut a, 0);
+            play_fresh(&mut b, 0);
+            assert_eq!(a.enemies[0].hp(), b.enemies[0].hp());
+        }
+    }
+
+    #[test]
+    fn manual_clock_round_runs_without_waiting() {
+        let mut engine = GameEngine::new_with_enemy(test_enemy("slime", 3));
+        let mut frontend = ScriptedFrontend::new(vec![]);
+        let start = engine.clock.now();
+        let hp_before = engine.player.hp();
+        engine.play_round(&mut frontend);
+        assert_eq!(engine.clock.now() - start, ROUND_DURATION);
+        assert_eq!(engine.player.hp(), hp_before - 1, "敌人应在回合内出手");
+    }
+
+    #[test]
+    fn manual_clock_round_ticks_cooldowns() {
+        let mut engine = GameEngine::new_with_enemy(test_enemy("slime", 3));
+        let mut frontend = ScriptedFrontend::new(vec![Command::PlayCard {
+            card: 0,
+            target: None,
+        }]);
+        engine.play_round(&mut frontend);
+        // Attack was played at the start of the 5s round; its 3s cooldown has expired.
+        assert!(engine.player.hand()[0].is_ready());
+        assert!(engine.enemies[0].hp() < 3);
+    }
+
+    #[test]
+    fn enemy_intent_respects_card_cooldown() {
+        let mut engine = GameEngine::new_with_enemy(test_enemy("forest_wolf", 3));
+
+        engine.enemies[0].deck_mut()[0].set_initial_cooldown_ms(2_000);
+        let intent = engine.plan_enemy_intent(0, ROUND_DURATION).unwrap();
+        assert_eq!(intent.card_name, "攻击");
+        assert_eq!(intent.effect, Effect::Damage(1));
+        assert!(intent.delay >= Duration::from_millis(2_000));
+        assert!(intent.delay <= ROUND_DURATION - Duration::from_millis(300));
+
+        engine.enemies[0].deck_mut()[0].set_initial_cooldown_ms(6_000);
+        assert!(engine.plan_enemy_intent(0, ROUND_DURATION).is_none());
+    }
+
+    #[test]
+    fn declared_intent_is_what_the_enemy_plays() {
+        let mut engine = GameEngine::new_with_enemy(test_enemy("skeleton_mage", 5));
+        let mut frontend = ScriptedFrontend::new(vec![]);
+        engine.play_round(&mut frontend);
+
+        let intent = frontend
+            .events
+            .iter()
+            .find_map(|e| match e {
+                GameEvent::IntentDeclared(intent) => Some(intent.clone()),
+                _ => None,
+            })
+            .expect("回合开始时应公布敌人意图");
+        assert_eq!(frontend.events[0], GameEvent::RoundStarted { round: 1 });
+        assert_eq!(intent.card_name, "防御", "骷髅法师的脚本以防御开场");
+        assert!(frontend.events.contains(&GameEvent::CardPlayed {
+            side: Side::Enemy(0),
+            name: intent.card_name.clone(),
+        }));
+        assert!(engine.enemy_intents().is_empty(), "出手后意图应清除");
+    }
+
+    #[test]
+    fn fragile_enemy_takes_extra_damage() {
+        let mut engine = GameEngine::new_with_enemy(test_enemy("dragon", 8));
+        engine.enemies[0].buffs_mut().apply(FRAGILE, 20, 0);
+        engine.drain_events();
+        engine.deal_damage(Side::Player, Side::Enemy(0), 2);
+        assert_eq!(engine.enemies[0].hp(), 4, "20 层脆弱使伤害翻倍");
+        assert!(engine.drain_events().contains(&GameEvent::DamageDealt {
+            target: Side::Enemy(0),
+            amount: 4
+        }));
+    }
+
+    #[test]
+    fn buffs_tick_at_round_end() {
+        let mut engine = GameEngine::new_with_enemy(test_enemy("slime", 3));
+        engine.player.buffs_mut().apply(NOURISH, 20, 1);
+        engine.enemies[0].buffs_mut().apply(FRAGILE, 1, 2);
+        engine.player.take_damage(2);
+        engine.heal(Side::Player, 1);
+        assert_eq!(engine.player.hp(), 3, "滋润使治疗翻倍");
+
+        engine.drain_events();
+        engine.finish_round();
+        assert_eq!(
+            engine.drain_events(),
+            vec![GameEvent::BuffExpired {
+                target: Side::Player,
+                buff: NOURISH.to_string()
+            }]
+        );
+        assert_eq!(engine.enemies[0].buffs().stacks(FRAGILE), 1);
+        assert!(engine.enemies[0].display_status().contains("脆弱×1(1回合)"));
+    }
+
+    #[test]
+    fn poison_ticks_at_round_start() {
+        let mut engine = GameEngine::new_with_enemy(test_enemy("forest_wolf", 3));
+        engine.enemies[0].buffs_mut().apply(POISON, 2, 0);
+        let mut frontend = ScriptedFrontend::new(vec![]);
+        engine.play_round(&mut frontend);
+        assert_eq!(
+            frontend.events[1..3],
+            [
+                GameEvent::BuffTriggered {
+                    target: Side::Enemy(0),
+                    buff: POISON.to_string()
+                },
+                GameEvent::DamageDealt {
+                    target: Side::Enemy(0),
+                    amount: 2
+                },
+            ]
+        );
+        assert_eq!(engine.enemies[0].hp(), 1);
+        engine.finish_round();
+        assert_eq!(
+            engine.enemies[0].buffs().stacks(POISON),
+            1,
+            "中毒每回合减少一层"
+        );
+    }
+
+    #[test]
+    fn strength_and_thorns_apply_to_attacks() {
+        let mut engine = GameEngine::new_with_enemy(test_enemy("dragon", 8));
+        engine.player.buffs_mut().apply(STRENGTH, 1, 2);
+        engine.enemies[0].buffs_mut().apply(THORNS, 1, 2);
+        play_fresh(&mut engine, 0);
+        assert_eq!(engine.enemies[0].hp(), 6, "力量使攻击多造成 1 点伤害");
+        assert_eq!(engine.player.hp(), 2, "荆棘反弹 1 点伤害");
+        assert!(engine.drain_events().contains(&GameEvent::DamageReflected {
+            target: Side::Player,
+            amount: 1
+        }));
+    }
+
+    #[test]
+    fn stun_wastes_the_next_card() {
+        let mut engine = GameEngine::new_with_enemy(test_enemy("slime", 3));
+        engine.player.buffs_mut().apply(STUN, 1, 1);
+        play_fresh(&mut engine, 0);
+        assert_eq!(engine.enemies[0].hp(), 3);
+        let wasted = &engine.player.deck().discard_pile()[0];
+        assert!(!wasted.is_ready(), "落空的卡牌仍进入冷却并进入弃牌堆");
+        assert_eq!(
+            engine.drain_events(),
+            vec![GameEvent::ActionSkipped {
+                side: Side::Player,
+                name: "攻击".to_string(),
+                buff: STUN.to_string()
+            }]
+        );
+        assert_eq!(engine.player.buffs().stacks(STUN), 0);
+    }
+
+    #[test]
+    fn composite_card_damages_and_applies_debuff() {
+        let mut engine = GameEngine::new_with_enemy(test_enemy("dragon", 8));
+        engine.player.hand_mut()[0] = GameData::builtin().cards.card("armor_break");
+        play_fresh(&mut engine, 0);
+        assert_eq!(
+            engine.drain_events(),
+            vec![
+                GameEvent::CardPlayed {
+                    side: Side::Player,
+                    name: "破甲斩".to_string()
+                },
+                GameEvent::DamageDealt {
+                    target: Side::Enemy(0),
+                    amount: 1
+                },
+                GameEvent::BuffApplied {
+                    target: Side::Enemy(0),
+                    buff: FRAGILE.to_string(),
+                    stacks: 2
+                },
+            ]
+        );
+        assert_eq!(engine.enemies[0].buffs().stacks(FRAGILE), 2);
+    }
+
+    #[test]
+    fn enemy_cards_resolve_through_the_same_effects() {
+        let mut engine = GameEngine::new_with_enemy(test_enemy("goblin_rogue", 4));
+        let dagger = engine.enemies[0]
+            .deck()
+            .iter()
+            .position(|c| c.name == "淬毒匕首")
+            .unwrap();
+        engine.play_enemy_card(0, dagger);
+        assert_eq!(engine.player.hp(), 2);
+        assert_eq!(engine.player.buffs().stacks(POISON), 1);
+    }
+
+    #[test]
+    fn attack_emits_typed_damage_event() {
+        let mut engine = GameEngine::new_with_enemy(test_enemy("slime", 3));
+        play_fresh(&mut engine, 0);
+        assert_eq!(
+            engine.drain_events(),
+            vec![
+                GameEvent::CardPlayed {
+                    side: Side::Player,
+                    name: "攻击".to_string()
+                },
+                GameEvent::DamageDealt {
+                    target: Side::Enemy(0),
+                    amount: 1
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn shield_absorb_emits_events() {
+        let mut engine = GameEngine::new_with_enemy(test_enemy("slime", 3));
+        play_fresh(&mut engine, 1);
+        engine.drain_events();
+        engine.play_enemy_card(0, 0);
+        let events = engine.drain_events();
+        assert!(events.contains(&GameEvent::ShieldAbsorbed {
+            target: Side::Player,
+            amount: 1
+        }));
+        assert!(events.contains(&GameEvent::AttackBlocked {
+            target: Side::Player
+        }));
+    }
+
+    #[test]
+    fn cooldown_rejection_is_an_event() {
+        let mut engine = GameEngine::new_with_enemy(test_enemy("slime", 3));
+        engine.player.hand_mut()[0].trigger_cooldown();
+        assert_eq!(play_fresh(&mut engine, 0), CommandOutcome::Rejected);
+        assert_eq!(
+            engine.drain_events(),
+            vec![GameEvent::ActionRejected(Rejection::OnCooldown {
+                name: "攻击".to_string(),
+                remaining_secs: 3
+            })]
+        );
+        assert_eq!(
+            engine.execute(Command::UseSkill {
+                skill: 9,
+                target: None
+            }),
+            CommandOutcome::Rejected
+        );
+    }
+
+    #[test]
+    fn victory_emits_gold_gained() {
+        let mut engine = GameEngine::new_with_enemy(test_enemy("slime", 3));
+        engine.claim_victory();
+        let events = engine.drain_events();
+        let gained = events.iter().find_map(|e| match *e {
+            GameEvent::GoldGained {
+                amount,
+                base,
+                bonus,
+            } => Some((amount, base, bonus)),
+            _ => None,
+        });
+        let (amount, base, bonus) = gained.expect("应产生金币事件");
+        assert_eq!(bonus, 0);
+        assert_eq!(amount, base);
+
+        let prepared = events
+            .iter()
+            .position(|e| {
+                *e == GameEvent::PassiveTriggered {
+                    side: Side::Player,
+                    passive: PREPARED.to_string(),
+                }
+            })
+            .expect("预备应在胜利后生效");
+        assert_eq!(
+            events[prepared + 1],
+            GameEvent::GoldGained {
+                amount: 1,
+                base: 1,
+                bonus: 0
+            }
+        );
+        assert_eq!(engine.player.gold(), amount + 1);
+    }
+
+    /// Stocks the shop with exactly these items, at their normal prices.
+    fn stock_shop(engine: &mut GameEngine, items: Vec<ShopItem>) {
+        engine.shop_offers = items
+            .into_iter()
+            .map(|item| engine.shop_offer(item, false))
+            .collect();
+    }
+
+    fn card_item(id: &str) -> ShopItem {
+        ShopItem::Card(id.to_string())
+    }
+
+    #[test]
+    fn shop_buy_and_insufficient_gold() {
+        let mut engine = GameEngine::new_with_enemy(test_enemy("slime", 3));
+        stock_shop(
+            &mut engine,
+            vec![card_item("critical_strike"), card_item("heal")],
+        );
+        assert_eq!(
+            engine.execute(Command::BuyItem(0)),
+            CommandOutcome::Rejected
+        );
+
+        engine.player.add_gold(5);
+        engine.drain_events();
+        assert_eq!(
+            engine.execute(Command::BuyItem(0)),
+            CommandOutcome::ItemBought
+        );
+        assert_eq!(
+            engine.drain_events(),
+            vec![GameEvent::ItemBought {
+                name: "暴击".to_string()
+            }]
+        );
+        assert_eq!(engine.player.gold(), 0);
+        assert_eq!(engine.shop_offers().len(), 1);
+        assert!(engine
+            .player
+            .deck()
+            .cards()
+            .iter()
+            .any(|c| c.name == "暴击"));
+    }
+
+    #[test]
+    fn shop_offers_spell_cards() {
+        let mut engine = GameEngine::new_with_enemy(test_enemy("slime", 3));
+        stock_shop(&mut engine, vec![card_item("fireball")]);
+        assert!(engine.shop_offers()[0].description.contains("40法力"));
+        engine.player.add_gold(5);
+        engine.execute(Command::BuyItem(0));
+        assert!(engine.player.has_spell_cards());
+    }
+
+    #[test]
+    fn shop_refunds_when_skill_slots_full() {
+        let mut engine = GameEngine::new_with_enemy(test_enemy("slime", 3));
+        stock_shop(&mut engine, vec![ShopItem::Skill("vampiric_touch")]);
+        engine.player.add_gold(5);
+        assert_eq!(
+            engine.execute(Command::BuyItem(0)),
+            CommandOutcome::Rejected
+        );
+        assert_eq!(engine.player.gold(), 5, "技能栏已满时应退还金币");
+    }
+
+    #[test]
+    fn shop_buys_back_cards_for_part_of_their_price() {
+        let mut engine = GameEngine::new_with_enemy(test_enemy("slime", 3));
+        for id in ["critical_strike", "heal", "heal"] {
+            engine.give_card(id);
+        }
+        let strike = engine
+            .player
+            .deck()
+            .cards()
+            .iter()
+            .position(|c| c.name == "暴击")
+            .unwrap();
+        assert_eq!(
+            engine.execute(Command::SellCard(strike)),
+            CommandOutcome::Rejected,
+            "商店外不能出售"
+        );
+
+        engine.open_shop();
+        engine.drain_events();
+        assert_eq!(engine.card_sell_price(strike), Some(2));
+        assert_eq!(
+            engine.execute(Command::SellCard(strike)),
+            CommandOutcome::ItemSold
+        );
+        assert_eq!(
+            engine.drain_events(),
+            vec![GameEvent::ItemSold {
+                name: "暴击".to_string(),
+                gold: 2
+            }]
+        );
+        assert_eq!(engine.player.gold(), 2);
+        assert_eq!(engine.player.deck().cards().len(), MIN_DECK_SIZE);
+
+        assert_eq!(
+            engine.execute(Command::SellCard(0)),
+            CommandOutcome::Rejected
+        );
+        assert_eq!(
+            engine.drain_events(),
+            vec![GameEvent::ActionRejected(Rejection::DeckTooSmall {
+                min: MIN_DECK_SIZE
+            })]
+        );
+    }
+
+    #[test]
+    fn selling_a_skill_frees_its_slot() {
+        let mut engine = GameEngine::new_with_enemy(test_enemy("slime", 3));
+        stock_shop(&mut engine, vec![ShopItem::Skill("vampiric_touch")]);
+        engine.shop_open = true;
+        engine.player.add_gold(3);
+
+        assert_eq!(
+            engine.execute(Command::SellSkill(0)),
+            CommandOutcome::ItemSold
+        );
+        assert_eq!(
+            engine.player.gold(),
+            3 + SHOP_SKILL_PRICE / SELL_BACK_DIVISOR
+        );
+        assert_eq!(engine.player.skills.len(), MAX_SKILLS - 1);
+        assert_eq!(
+            engine.execute(Command::BuyItem(0)),
+            CommandOutcome::ItemBought
+        );
+        assert!(engine.player.skills.iter().any(|s| s.name == "吸血之触"));
+    }
+
+    #[test]
+    fn replacing_a_skill_sells_the_old_one_towards_the_new_one() {
+        let mut engine = GameEngine::new_with_enemy(test_enemy("slime", 3));
+        stock_shop(&mut engine, vec![ShopItem::Skill("vampiric_touch")]);
+        engine.shop_open = true;
+        let refund = engine.skill_sell_price(0).unwrap();
+        engine.player.add_gold(SHOP_SKILL_PRICE - refund - 1);
+
+        let replace = Command::ReplaceSkill { offer: 0, skill: 0 };
+        assert_eq!(engine.execute(replace), CommandOutcome::Rejected);
+        assert_eq!(
+            engine.player.skills[0].name, "紧急救治",
+            "钱不够时什么都不变"
+        );
+
+        engine.player.add_gold(1);
+        engine.drain_events();
+        assert_eq!(engine.execute(replace), CommandOutcome::ItemBought);
+        let names: Vec<_> = engine.player.skills.iter().map(|s| &s.name).collect();
+        assert_eq!(
+            names,
+            ["吸血之触", "快速循环"],
+            "新技能占用卖掉的技能的位置"
+        );
+        assert_eq!(engine.player.gold(), 0);
+        assert_eq!(
+            engine.drain_events()[..2],
+            [
+                GameEvent::ItemSold {
+                    name: "紧急救治".to_string(),
+                    gold: refund
+                },
+                GameEvent::ItemBought {
+                    name: "吸血之触".to_string()
+                }
+            ]
+        );
+        assert_eq!(
+            engine.execute(Command::ReplaceSkill { offer: 0, skill: 0 }),
+            CommandOutcome::Rejected,
+            "货架上已经没有这个技能"
+        );
+    }
+
+    #[test]
+    fn shop_stock_is_seeded_and_always_has_services() {
+        let stock = |seed| {
+            let mut engine = GameEngine::with_seed(1);
+            engine.rng = StdRng::seed_from_u64(seed);
+            engine.open_shop();
+            engine.shop_offers().to_vec()
+        };
+        assert_eq!(
+            stock(5).iter().map(|o| o.item.clone()).collect::<Vec<_>>(),
+            stock(5).iter().map(|o| o.item.clone()).collect::<Vec<_>>()
+        );
+
+        let mut seen = Vec::new();
+        for seed in 0..20 {
+            let offers = stock(seed);
+            let cards = offers
+                .iter()
+                .filter(|o| matches!(o.item, ShopItem::Card(_)))
+                .count();
+            assert_eq!(cards, SHOP_CARD_SLOTS);
+            assert_eq!(offers.iter().filter(|o| o.on_sale).count(), 1);
+            assert!(offers.iter().any(|o| matches!(o.item, ShopItem::Relic(_))));
+            assert_eq!(offers.iter().filter(|o| o.item.is_skill()).count(), 1);
+            let services: Vec<_> = offers[offers.len() - 3..]
+                .iter()
+                .map(|o| o.item.clone())
+                .collect();
+            assert_eq!(
+                services,
+                vec![
+                    ShopItem::Upgrade,
+                    ShopItem::RemoveCard,
+                    ShopItem::MaxHp(SHOP_MAX_HP_GAIN)
+                ]
+            );
+            seen.extend(offers.into_iter().map(|o| o.item));
+        }
+        assert!(!seen.contains(&ShopItem::Heal), "只有受伤时才提供治疗");
+        assert!(seen.contains(&card_item("whirlwind")));
+        assert!(seen.contains(&card_item("attack")));
+        assert!(!seen.contains(&card_item("dragon_breath")));
+    }
+
+    #[test]
+    fn shop_prices_follow_rarity_stage_sales_and_discounts() {
+        let mut engine = GameEngine::new_with_enemy(test_enemy("slime", 3));
+        let price =
+            |engine: &GameEngine, item: ShopItem, on_sale| engine.shop_offer(item, on_sale).price;
+        assert_eq!(price(&engine, card_item("attack"), false), 3);
+        assert_eq!(price(&engine, card_item("critical_strike"), false), 5);
+        assert_eq!(price(&engine, card_item("critical_strike"), true), 2);
+        assert_eq!(
+            price(&engine, ShopItem::Relic("merchant_badge"), false),
+            RelicRegistry::builtin()
+                .get("merchant_badge")
+                .unwrap()
+                .tier
+                .price()
+        );
+
+        let floors = (0..7)
+            .map(|_| vec![MapNode::new(NodeKind::Battle, vec![0])])
+            .collect();
+        engine.map = RunMap::from_floors(floors);
+        for _ in 0..7 {
+            engine.map.advance(0);
+        }
+        assert_eq!(engine.stage(), 7);
+        assert_eq!(price(&engine, card_item("critical_strike"), false), 7);
+        assert_eq!(
+            price(&engine, ShopItem::Upgrade, false),
+            SHOP_UPGRADE_PRICE,
+            "服务不随层数涨价"
+        );
+
+        engine.player.add_relic("merchant_badge");
+        assert_eq!(price(&engine, card_item("critical_strike"), false), 5);
+        assert_eq!(price(&engine, card_item("attack"), true), 0);
     }
 
     #[test]
-    fn sim_defense_card_shields_player() {
-        let mut engine = GameEngine::new_with_enemy(Box::new(Slime::new("史莱姆", 3)));
-        assert_eq!(engine.player.shield(), 0);
-        engine.try_execute_player_action("2", false);
-        assert!(engine.player.shield() > 0, "防御牌应给予玩家护盾");
+    fn shop_services_upgrade_or_remove_the_chosen_card() {
+        let mut engine = GameEngine::new_with_enemy(test_enemy("slime", 3));
+        for _ in 0..MIN_DECK_SIZE {
+            engine.give_card("heal");
+        }
+        stock_shop(&mut engine, vec![ShopItem::Upgrade, ShopItem::RemoveCard]);
+        engine
+            .player
+            .add_gold(SHOP_UPGRADE_PRICE + SHOP_REMOVAL_PRICE);
+        let owned = engine.player.deck().cards().len();
+        let defense = engine
+            .player
+            .deck()
+            .cards()
+            .iter()
+            .position(|c| c.name == "防御")
+            .unwrap();
+
+        assert_eq!(
+            engine.execute(Command::BuyItem(0)),
+            CommandOutcome::Rejected,
+            "服务要指定卡牌"
+        );
+        assert_eq!(
+            engine.execute(Command::BuyService {
+                offer: 0,
+                card: owned
+            }),
+            CommandOutcome::Rejected
+        );
+        engine.drain_events();
+        assert_eq!(
+            engine.execute(Command::BuyService { offer: 0, card: 0 }),
+            CommandOutcome::ItemBought
+        );
+        assert_eq!(
+            engine.drain_events()[0],
+            GameEvent::CardUpgraded {
+                from: "攻击".to_string(),
+                to: "攻击+".to_string()
+            }
+        );
+        assert!(engine.player.deck().cards()[0].upgraded);
+
+        assert_eq!(
+            engine.execute(Command::BuyService {
+                offer: 0,
+                card: defense
+            }),
+            CommandOutcome::ItemBought
+        );
+        assert_eq!(
+            engine.drain_events()[0],
+            GameEvent::CardRemoved {
+                name: "防御".to_string()
+            }
+        );
+        assert_eq!(engine.player.deck().cards().len(), owned - 1);
+        assert_eq!(engine.player.gold(), 0);
     }
 
     #[test]
-    fn sim_enemy_action_damages_player() {
-        let mut engine = GameEngine::new_with_enemy(Box::new(Slime::new("史莱姆", 3)));
-        let hp_before = engine.player.hp();
-        engine.execute_enemy_action();
-        assert!(engine.player.hp() < hp_before, "敌方攻击牌应减少玩家 HP");
+    fn shop_removal_keeps_enough_cards_for_a_hand() {
+        let mut engine = GameEngine::new_with_enemy(test_enemy("slime", 3));
+        let owned = engine.player.deck().cards().len();
+        assert!(owned <= MIN_DECK_SIZE);
+        engine.open_shop();
+        assert!(!engine
+            .shop_offers()
+            .iter()
+            .any(|o| o.item == ShopItem::RemoveCard));
+
+        stock_shop(&mut engine, vec![ShopItem::RemoveCard]);
+        engine.player.add_gold(SHOP_REMOVAL_PRICE);
+        assert_eq!(
+            engine.execute(Command::BuyService { offer: 0, card: 0 }),
+            CommandOutcome::Rejected
+        );
+        assert_eq!(engine.player.deck().cards().len(), owned);
+        assert_eq!(engine.player.gold(), SHOP_REMOVAL_PRICE);
     }
 
-    #[test]
-    fn sim_shield_fully_absorbs_enemy_hit() {
-        let mut engine = GameEngine::new_with_enemy(Box::new(Slime::new("史莱姆", 3)));
-        engine.try_execute_player_action("2", false);
-        let hp_before = engine.player.hp();
-        engine.execute_enemy_action();
-        assert_eq!(engine.player.hp(), hp_before, "护盾应完全吸收 1 点伤害");
-        assert_eq!(engine.player.shield(), 0, "护盾耗尽后应归零");
+    /// Buys healing whenever the shop offers it at a price it can pay; does nothing else.
+    struct HealBuyer;
+
+    impl Frontend for HealBuyer {
+        fn on_event(&mut self, _engine: &GameEngine, _event: &GameEvent) {}
+
+        fn poll_command(&mut self, _engine: &GameEngine) -> Option<Command> {
+            None
+        }
+
+        fn shop_command(&mut self, engine: &GameEngine) -> Option<Command> {
+            engine
+                .shop_offers()
+                .iter()
+                .position(|o| o.item == ShopItem::Heal && o.price <= engine.player.gold())
+                .map(Command::BuyItem)
+        }
     }
 
     #[test]
-    fn sim_player_kills_slime_in_two_hits() {
-        let mut engine = GameEngine::new_with_enemy(Box::new(Slime::new("史莱姆", 2)));
-        engine.player.hand[0].set_initial_cooldown_ms(0);
-        engine.try_execute_player_action("1", false);
-        assert!(engine.enemy.is_alive(), "第一击后史莱姆仍存活");
-        engine.player.hand[0].set_initial_cooldown_ms(0);
-        engine.try_execute_player_action("1", false);
-        assert!(!engine.enemy.is_alive(), "第二击后史莱姆应被击败");
+    fn hp_healed_in_the_shop_lasts_into_the_next_battle() {
+        for (gold, healed) in [(0, false), (SHOP_HEAL_PRICE, true)] {
+            let mut engine = GameEngine::new();
+            engine.map = RunMap::from_floors(vec![
+                vec![MapNode::new(NodeKind::Shop, vec![0])],
+                vec![MapNode::new(NodeKind::Battle, vec![])],
+            ]);
+            engine.map.advance(0);
+            engine.player.take_damage(2);
+            engine.player.add_gold(gold);
+
+            engine.run_shop(&mut HealBuyer);
+            engine.advance_map(&mut HealBuyer);
+            assert_eq!(engine.node_kind(), Some(NodeKind::Battle));
+            let expected = engine.player.max_hp() - if healed { 0 } else { 2 };
+            assert_eq!(engine.player.hp(), expected, "金币 {gold}");
+        }
     }
 
     #[test]
-    fn sim_emergency_heal_recovers_hp() {
-        let mut engine = GameEngine::new_with_enemy(Box::new(Slime::new("史莱姆", 3)));
+    fn shop_heals_a_hurt_player_to_full() {
+        let mut engine = GameEngine::new_with_enemy(test_enemy("slime", 3));
         engine.player.take_damage(2);
-        assert_eq!(engine.player.hp(), 1);
-        let skill_choice = (engine.player.hand.len() + 1).to_string();
-        engine.try_execute_player_action(&skill_choice, false);
-        assert!(engine.player.hp() > 1, "急救技能应恢复 HP");
+        engine.open_shop();
+        let heal = engine
+            .shop_offers()
+            .iter()
+            .position(|o| o.item == ShopItem::Heal)
+            .expect("受伤时商店提供治疗");
+        engine.player.add_gold(SHOP_HEAL_PRICE);
+        assert_eq!(
+            engine.execute(Command::BuyItem(heal)),
+            CommandOutcome::ItemBought
+        );
+        assert_eq!(engine.player.hp(), engine.player.max_hp());
+        assert_eq!(engine.player.gold(), 0);
+
+        stock_shop(&mut engine, vec![ShopItem::Heal]);
+        engine.player.add_gold(SHOP_HEAL_PRICE);
+        assert_eq!(
+            engine.execute(Command::BuyItem(0)),
+            CommandOutcome::Rejected
+        );
+        assert_eq!(engine.player.gold(), SHOP_HEAL_PRICE);
     }
 
-    #[test]
-    fn sim_fast_cycle_reduces_card_cooldowns() {
-        let mut engine = GameEngine::new_with_enemy(Box::new(Slime::new("史莱姆", 3)));
-        for card in &mut engine.player.hand {
-            card.trigger_cooldown();
+    /// Buys out the shop, then sells its first card once the shelf is empty.
+    struct SellOutShopper {
+        sold: bool,
+        events: Vec<GameEvent>,
+    }
+
+    impl Frontend for SellOutShopper {
+        fn on_event(&mut self, _engine: &GameEngine, event: &GameEvent) {
+            self.events.push(event.clone());
         }
-        assert!(!engine.player.hand[0].is_ready());
-        for skill in &mut engine.player.skills {
-            skill.remaining_cooldown_ms = 0;
+
+        fn poll_command(&mut self, _engine: &GameEngine) -> Option<Command> {
+            None
+        }
+
+        fn shop_command(&mut self, engine: &GameEngine) -> Option<Command> {
+            if let Some(offer) = engine.shop_offers().first() {
+                return Some(if offer.item.needs_card() {
+                    Command::BuyService { offer: 0, card: 0 }
+                } else if offer.item.is_skill() && engine.player.skills.len() >= MAX_SKILLS {
+                    Command::ReplaceSkill { offer: 0, skill: 0 }
+                } else {
+                    Command::BuyItem(0)
+                });
+            }
+            (!std::mem::replace(&mut self.sold, true)).then_some(Command::SellCard(0))
         }
-        let skill_choice = (engine.player.hand.len() + 2).to_string();
-        engine.try_execute_player_action(&skill_choice, false);
-        assert!(engine.player.hand[0].remaining_cooldown_ms() < 3000);
     }
 
     #[test]
-    fn sim_goblin_rogue_dodge_is_probabilistic() {
-        let mut engine = GameEngine::new_with_enemy(Box::new(GoblinRogue::new("哥布林刺客", 1000)));
-        const TRIALS: usize = 200;
-        let mut dodge_count = 0;
+    fn a_sold_out_shop_stays_open_for_selling() {
+        let mut engine = GameEngine::new_with_enemy(test_enemy("slime", 3));
+        engine.player.add_gold(1000);
+        let mut frontend = SellOutShopper {
+            sold: false,
+            events: Vec::new(),
+        };
+        engine.run_shop(&mut frontend);
+
+        let sold_out = frontend
+            .events
+            .iter()
+            .position(|e| *e == GameEvent::ShopSoldOut)
+            .expect("买空了商店");
+        assert!(matches!(
+            frontend.events[sold_out + 1],
+            GameEvent::ItemSold { .. }
+        ));
+        assert_eq!(frontend.events.last(), Some(&GameEvent::ShopClosed));
+    }
 
-        for _ in 0..TRIALS {
-            let hp_before = engine.enemy.hp();
-            engine.player.hand[0].set_initial_cooldown_ms(0);
-            engine.try_execute_player_action("1", false);
-            if engine.enemy.hp() == hp_before {
-                dodge_count += 1;
+    #[test]
+    fn shop_sells_relics_and_max_hp() {
+        let mut engine = GameEngine::new_with_enemy(test_enemy("slime", 3));
+        stock_shop(
+            &mut engine,
+            vec![
+                ShopItem::Relic("merchant_badge"),
+                ShopItem::MaxHp(SHOP_MAX_HP_GAIN),
+            ],
+        );
+        engine.player.add_gold(100);
+        let max_hp = engine.player.max_hp();
+        engine.execute(Command::BuyItem(1));
+        assert_eq!(engine.player.max_hp(), max_hp + SHOP_MAX_HP_GAIN);
+
+        engine.drain_events();
+        engine.execute(Command::BuyItem(0));
+        assert_eq!(engine.player.relics(), ["merchant_badge".to_string()]);
+        assert_eq!(
+            engine.drain_events()[0],
+            GameEvent::RelicGained {
+                relic: "merchant_badge".to_string()
             }
-        }
+        );
+    }
 
-        let dodge_rate = dodge_count as f64 / TRIALS as f64;
-        assert!(
-            dodge_rate < 0.25,
-            "闪避率 {:.1}% 过高（期望 ~10%）",
-            dodge_rate * 100.0
+    #[test]
+    fn scripted_bot_plays_a_full_run_headless() {
+        let mut engine = GameEngine::with_seed(7).with_clock(Box::new(ManualClock::new()));
+        let mut bot = AttackBot::new();
+        engine.run(&mut bot);
+
+        assert!(matches!(
+            bot.events.first(),
+            Some(GameEvent::RunStarted { seed: 7, .. })
+        ));
+        let finished = bot.events.iter().any(|e| {
+            matches!(
+                e,
+                GameEvent::RunCleared { .. } | GameEvent::BattleLost { .. }
+            )
+        });
+        assert!(finished, "整局游戏应以通关或失败结束");
+        assert!(bot.events.iter().any(|e| matches!(
+            e,
+            GameEvent::CardPlayed {
+                side: Side::Player,
+                ..
+            }
+        )));
+    }
+
+    #[test]
+    fn suspended_run_resumes_identically() {
+        const SEED: u64 = 3;
+        let mut full = GameEngine::with_seed(SEED).with_clock(Box::new(ManualClock::new()));
+        let mut full_bot = AttackBot::new();
+        let full_outcome = full.run(&mut full_bot);
+
+        let mut first = GameEngine::with_seed(SEED).with_clock(Box::new(ManualClock::new()));
+        let mut first_bot = AttackBot {
+            suspend: true,
+            ..AttackBot::new()
+        };
+        assert_eq!(first.run(&mut first_bot), RunOutcome::Suspended);
+        assert_eq!(
+            first_bot.events.pop(),
+            Some(GameEvent::RunSuspended { stage: 2 })
         );
-        assert!(
-            dodge_rate > 0.02,
-            "闪避率 {:.1}% 过低（期望 ~10%）",
-            dodge_rate * 100.0
+
+        let json = save::to_json(&first.snapshot()).unwrap();
+        let mut resumed =
+            GameEngine::from_save(save::from_json(&json).unwrap(), GameData::builtin().clone())
+                .with_clock(Box::new(ManualClock::new()));
+        let mut resumed_bot = AttackBot::new();
+        assert_eq!(resumed.run(&mut resumed_bot), full_outcome);
+        assert_eq!(
+            resumed_bot.events.remove(0),
+            GameEvent::RunResumed { stage: 2 }
+        );
+
+        first_bot.events.extend(resumed_bot.events);
+        assert_eq!(
+            first_bot.events, full_bot.events,
+            "读档后应与不中断的对局完全一致"
         );
+        assert_eq!(resumed.player.gold(), full.player.gold());
     }
 
     #[test]
-    fn sim_slime_never_dodges() {
-        let mut engine = GameEngine::new_with_enemy(Box::new(Slime::new("史莱姆", 1000)));
-        for _ in 0..100 {
-            let hp_before = engine.enemy.hp();
-            engine.player.hand[0].set_initial_cooldown_ms(0);
-            engine.try_execute_player_action("1", false);
-            assert_eq!(engine.enemy.hp(), hp_before - 1, "史莱姆不应闪避任何攻击");
-        }
+    fn snapshot_keeps_player_and_enemy_state() {
+        let mut engine = GameEngine::new_with_enemy(test_enemy("skeleton_mage", 5));
+        engine.start_battle();
+        engine.enemies[0].take_damage(2);
+        engine.player.add_gold(7);
+        engine.give_card("heal");
+        engine.player.hand_mut()[0].trigger_cooldown();
+        engine.map = RunMap::generate(&mut StdRng::seed_from_u64(5));
+        engine.map.advance(0);
+        engine.map.advance(engine.map.choices()[0]);
+
+        let restored = GameEngine::from_save(engine.snapshot(), engine.data.clone());
+        assert_eq!(restored.stage(), 2);
+        assert_eq!(restored.map(), engine.map());
+        assert_eq!(restored.player.gold(), 7);
+        assert_eq!(restored.player.deck().cards().len(), 3);
+        assert_eq!(restored.player.deck().draw_pile().len(), 2);
+        assert_eq!(restored.player.hand().len(), 1);
+        assert_eq!(restored.player.hand()[0].remaining_cooldown_ms(), 4_000);
+        assert_eq!(restored.player.skills.len(), 2);
+        assert_eq!(restored.player.passives(), [PREPARED]);
+        assert_eq!(restored.enemies[0].name(), "骷髅法师");
+        assert_eq!(restored.enemies[0].hp(), 4, "护盾先吸收 1 点，再扣 1 点 HP");
+        assert_eq!(restored.enemies[0].shield(), 0);
     }
 
     #[test]
-    fn card_then_skills_are_allowed_but_not_second_card() {
-        let mut engine = GameEngine::new();
-        prepare_ready_actions(&mut engine);
+    fn saved_enemy_keeps_its_definition() {
+        let engine = GameEngine::new_with_enemy(test_enemy("goblin_rogue", 4));
+        let json = save::to_json(&engine.snapshot()).unwrap();
+        let restored = GameEngine::from_save(save::from_json(&json).unwrap(), engine.data.clone());
+        assert_eq!(restored.enemies[0].id(), "goblin_rogue");
+        assert!((restored.enemies[0].dodge_chance() - 0.1).abs() < f64::EPSILON);
+        assert_eq!(restored.enemies[0].passives(), [DODGE_MASTER]);
+    }
 
-        let first_skill_choice = (engine.player.hand.len() + 1).to_string();
-        let second_skill_choice = (engine.player.hand.len() + 2).to_string();
+    #[test]
+    fn spells_spend_mana_and_are_rejected_without_it() {
+        let mut engine = GameEngine::new_with_enemy(test_enemy("slime", 10));
+        engine.give_card("fireball");
+        let fireball = engine.player.hand().len() - 1;
 
         assert_eq!(
-            engine.try_execute_player_action("1", false),
-            PlayerActionResult::CardUsed
+            play_fresh(&mut engine, fireball),
+            CommandOutcome::CardPlayed
         );
+        assert_eq!(engine.player.mana(), 60);
+        assert_eq!(engine.enemies[0].hp(), 8);
+
+        engine.player.spend_mana(40);
+        engine.redeal();
+        engine.drain_events();
+        assert_eq!(play_fresh(&mut engine, fireball), CommandOutcome::Rejected);
         assert_eq!(
-            engine.try_execute_player_action("1", true),
-            PlayerActionResult::None
+            engine.drain_events(),
+            vec![GameEvent::ActionRejected(Rejection::NotEnoughMana {
+                name: "火球术".to_string(),
+                cost: 40,
+                mana: 20,
+            })]
         );
-        assert_eq!(
-            engine.try_execute_player_action(&first_skill_choice, true),
-            PlayerActionResult::SkillUsed
+        assert!(
+            engine.player.hand()[fireball].is_ready(),
+            "被拒绝时不进入冷却"
         );
+        assert_eq!(engine.enemies[0].hp(), 8);
+    }
+
+    #[test]
+    fn rounds_draw_a_hand_and_discard_what_was_not_played() {
+        let mut engine = GameEngine::new_with_enemy(test_enemy("slime", 10));
+        for _ in 0..3 {
+            engine.give_card("attack");
+        }
+        engine.start_battle();
+        assert_eq!(engine.player.deck().draw_pile().len(), 5);
+        assert!(engine.player.hand().is_empty());
+
+        engine.play_round(&mut ScriptedFrontend::new(vec![]));
+        assert_eq!(engine.player.hand().len(), HAND_SIZE);
+        engine.finish_round();
+        assert!(engine.player.hand().is_empty());
+        assert_eq!(engine.player.deck().discard_pile().len(), HAND_SIZE);
+
+        engine.drain_events();
+        let mut frontend = ScriptedFrontend::new(vec![]);
+        engine.play_round(&mut frontend);
+        assert_eq!(engine.player.hand().len(), HAND_SIZE);
+        assert_eq!(frontend.events[1], GameEvent::DeckReshuffled);
+    }
+
+    #[test]
+    fn exhausting_draw_card_pulls_cards_and_leaves_play() {
+        let mut engine = GameEngine::new_with_enemy(test_enemy("slime", 10));
+        engine.give_card("battle_plan");
+        engine.start_battle();
+        engine.redeal();
+        engine.drain_events();
+        assert_eq!(play_fresh(&mut engine, 2), CommandOutcome::CardPlayed);
+        assert_eq!(engine.player.hand().len(), 4);
+        assert_eq!(engine.player.deck().draw_pile().len(), 1);
+        assert_eq!(engine.player.deck().exhaust_pile().len(), 1);
+        assert_eq!(engine.player.energy(), engine.player.max_energy(), "零费");
         assert_eq!(
-            engine.try_execute_player_action(&second_skill_choice, true),
-            PlayerActionResult::SkillUsed
+            engine.drain_events()[1..],
+            [
+                GameEvent::CardsDrawn { count: 2 },
+                GameEvent::CardExhausted {
+                    name: "战术调整".to_string()
+                }
+            ]
         );
     }
 
     #[test]
-    fn skills_then_card_is_allowed_but_not_second_card() {
-        let mut engine = GameEngine::new();
-        prepare_ready_actions(&mut engine);
+    fn mana_regenerates_each_round() {
+        let mut engine = GameEngine::new_with_enemy(test_enemy("slime", 10));
+        engine.player.spend_mana(90);
+        engine.play_round(&mut ScriptedFrontend::new(vec![]));
+        assert_eq!(engine.player.mana(), 10 + MANA_REGEN_PER_ROUND);
 
-        let first_skill_choice = (engine.player.hand.len() + 1).to_string();
-        let second_skill_choice = (engine.player.hand.len() + 2).to_string();
+        engine.player.reset_for_battle();
+        assert_eq!(engine.player.mana(), engine.player.max_mana());
+    }
+
+    #[test]
+    fn relic_modifiers_boost_player_effects() {
+        let mut engine = GameEngine::new_with_enemy(test_enemy("slime", 10));
+        engine.player.add_relic("whetstone");
+        engine.player.add_relic("leather_armor");
+        engine.player.add_relic("quick_draw");
+
+        play_fresh(&mut engine, 0);
+        assert_eq!(engine.enemies[0].hp(), 8, "磨刀石使攻击 +1");
+        let played = &engine.player.deck().discard_pile()[0];
+        assert_eq!(played.remaining_cooldown_ms(), 2_000);
+        play_fresh(&mut engine, 0);
+        assert_eq!(engine.player.shield(), 2, "皮甲使护盾 +1");
+
+        engine.play_enemy_card(0, 0);
+        assert_eq!(engine.player.shield(), 1, "敌方攻击不受遗物加成");
+    }
 
+    #[test]
+    fn relic_hooks_fire_at_round_start_and_on_hits() {
+        let mut engine = GameEngine::new_with_enemy(test_enemy("slime", 10));
+        engine.player.add_relic("iron_shield");
+        engine.player.add_relic("thorn_armor");
+        engine.player.add_relic("vampiric_fang");
+        engine.player.take_damage(1);
+
+        let mut frontend = ScriptedFrontend::new(vec![]);
+        engine.play_round(&mut frontend);
         assert_eq!(
-            engine.try_execute_player_action(&first_skill_choice, false),
-            PlayerActionResult::SkillUsed
+            frontend.events[1],
+            GameEvent::RelicTriggered {
+                relic: "iron_shield".to_string()
+            }
         );
+
+        engine.finish_round();
+        let enemy_hp = engine.enemies[0].hp();
+        engine.play_enemy_card(0, 0);
+        assert_eq!(engine.enemies[0].hp(), enemy_hp - 1, "荆棘甲在受击后反击");
+
+        engine.drain_events();
+        engine.redeal();
+        play_fresh(&mut engine, 0);
+        let events = engine.drain_events();
         assert_eq!(
-            engine.try_execute_player_action(&second_skill_choice, false),
-            PlayerActionResult::SkillUsed
+            events[events.len() - 2..],
+            [
+                GameEvent::RelicTriggered {
+                    relic: "vampiric_fang".to_string()
+                },
+                GameEvent::Healed {
+                    target: Side::Player,
+                    amount: 1
+                }
+            ]
         );
+    }
+
+    #[test]
+    fn enemy_passives_react_to_hits() {
+        let mut def = GameData::builtin().enemies.get("slime").unwrap().clone();
+        def.max_hp = 1000;
+        def.passives = vec![COUNTER.to_string()];
+        let mut engine = GameEngine::new_with_enemy(def.spawn());
+        let countered = (0..50).any(|_| {
+            engine.redeal();
+            play_fresh(&mut engine, 0);
+            let events = engine.drain_events();
+            events.ends_with(&[
+                GameEvent::PassiveTriggered {
+                    side: Side::Enemy(0),
+                    passive: COUNTER.to_string(),
+                },
+                GameEvent::DamageDealt {
+                    target: Side::Player,
+                    amount: 1,
+                },
+            ])
+        });
+        assert!(countered, "反击应在 50 次受击内触发");
+        assert_eq!(engine.player.hp(), 2);
+    }
+
+    #[test]
+    fn player_passives_fire_on_kill_and_victory_in_order() {
+        let mut engine = GameEngine::new_with_enemy(test_enemy("slime", 3));
+        assert!(engine.player.add_passive(BLOODTHIRST));
+        engine.player.take_damage(1);
+        engine.claim_victory();
+        assert_eq!(engine.player.hp(), 3, "嗜血在击败敌人时恢复 1 点生命");
+
+        let triggered: Vec<_> = engine
+            .drain_events()
+            .into_iter()
+            .filter_map(|e| match e {
+                GameEvent::PassiveTriggered { passive, .. } => Some(passive),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(triggered, [BLOODTHIRST, PREPARED]);
+    }
+
+    #[test]
+    fn boss_phases_are_telegraphed_at_round_start() {
+        let dragon = GameData::builtin().enemies.get("dragon").unwrap().spawn();
+        let mut engine = GameEngine::new_with_enemy(dragon);
+        engine.enemies[0].take_damage(6);
+        let mut frontend = ScriptedFrontend::new(vec![]);
+        engine.play_round(&mut frontend);
+
+        let events = &frontend.events;
+        assert_eq!(events[0], GameEvent::RoundStarted { round: 1 });
+        assert!(matches!(
+            &events[1],
+            GameEvent::PhaseChanged {
+                enemy: 0,
+                phase: 2,
+                ..
+            }
+        ));
+        assert!(matches!(
+            &events[2],
+            GameEvent::PhaseChanged { phase: 3, .. }
+        ));
         assert_eq!(
-            engine.try_execute_player_action("1", false),
-            PlayerActionResult::CardUsed
+            events[3],
+            GameEvent::BuffApplied {
+                target: Side::Enemy(0),
+                buff: STRENGTH.to_string(),
+                stacks: 1,
+            }
         );
+        assert_eq!(events[4], GameEvent::EnemyJoined { enemy: 1 });
+        assert!(matches!(&events[5], GameEvent::IntentDeclared(_)));
+        assert_eq!(engine.enemies[0].speed(), 6);
+        assert_eq!(engine.enemies[0].buffs().stacks(STRENGTH), 1);
+        assert_eq!(engine.enemies[1].id(), "dragon_whelp");
+    }
+
+    #[test]
+    fn giant_slime_splits_and_its_copy_joins_the_fight() {
+        let slime = GameData::builtin()
+            .enemies
+            .get("giant_slime")
+            .unwrap()
+            .spawn();
+        let mut engine = GameEngine::new_with_enemy(slime);
+        engine.player.gain_max_hp(30);
+        engine.deal_damage(Side::Player, Side::Enemy(0), 4);
+        assert_eq!(engine.enemies.len(), 2, "跌破一半生命时分裂");
         assert_eq!(
-            engine.try_execute_player_action("2", true),
-            PlayerActionResult::None
+            (engine.enemies[1].name(), engine.enemies[1].hp()),
+            ("巨型史莱姆的分身", 2)
         );
+        let events = engine.drain_events();
+        assert!(events.contains(&GameEvent::EnemySplit { enemy: 0 }));
+        assert!(events.contains(&GameEvent::EnemyJoined { enemy: 1 }));
+        engine.deal_damage(Side::Player, Side::Enemy(0), 1);
+        assert_eq!(engine.enemies.len(), 2, "只在跌破一半的那一击分裂");
+
+        let mut bot = AttackBot::new();
+        assert!(engine.fight(&mut bot));
+        assert!(bot.events.iter().any(|e| matches!(
+            e,
+            GameEvent::DamageDealt {
+                target: Side::Enemy(1),
+                ..
+            }
+        )));
+        assert!(engine.enemies.iter().all(|e| !e.is_alive()));
     }
 
-    // ── New feature tests ───────────────────────────────────────────────────
+    #[test]
+    fn splits_fizzle_on_a_full_battlefield() {
+        let slime = GameData::builtin()
+            .enemies
+            .get("giant_slime")
+            .unwrap()
+            .spawn();
+        let mut engine = GameEngine::new_with_enemies(vec![
+            slime,
+            test_enemy("slime", 3),
+            test_enemy("slime", 3),
+        ]);
+        engine.deal_damage(Side::Player, Side::Enemy(0), 4);
+        assert_eq!(engine.enemies.len(), MAX_ENEMIES);
+        assert!(engine.drain_events().contains(&GameEvent::BattlefieldFull));
+    }
 
     #[test]
-    fn sim_heal_card_recovers_hp() {
-        let mut engine = GameEngine::new_with_enemy(Box::new(Slime::new("史莱姆", 3)));
-        engine.player.add_card(create_heal_card());
-        engine.player.take_damage(2);
-        assert_eq!(engine.player.hp(), 1);
-        // Heal card is index 3 (attack=1, defense=2, heal=3)
-        engine.try_execute_player_action("3", false);
-        assert_eq!(engine.player.hp(), 2, "治愈卡应恢复 1 HP");
+    fn attacks_go_to_the_chosen_target_until_it_falls() {
+        let mut engine =
+            GameEngine::new_with_enemies(vec![test_enemy("slime", 3), test_enemy("slime", 1)]);
+        assert_eq!(engine.target(), 0);
+        engine.execute(Command::PlayCard {
+            card: 0,
+            target: Some(1),
+        });
+        assert_eq!(engine.enemies[0].hp(), 3);
+        assert!(!engine.enemies[1].is_alive());
+        assert_eq!(engine.target(), 0, "目标倒下后改打第一个还站着的敌人");
+
+        engine.redeal();
+        engine.player.refill_energy();
+        engine.execute(Command::PlayCard {
+            card: 0,
+            target: None,
+        });
+        assert_eq!(engine.enemies[0].hp(), 2);
     }
 
     #[test]
-    fn sim_critical_strike_deals_two_damage() {
-        let mut engine = GameEngine::new_with_enemy(Box::new(Slime::new("史莱姆", 5)));
-        engine.player.add_card(create_critical_strike_card());
-        let hp_before = engine.enemy.hp();
-        // Critical strike is index 3
-        engine.try_execute_player_action("3", false);
-        assert_eq!(engine.enemy.hp(), hp_before - 2, "暴击卡应造成 2 点伤害");
+    fn fallen_or_missing_targets_are_rejected_before_paying() {
+        let mut engine =
+            GameEngine::new_with_enemies(vec![test_enemy("slime", 3), test_enemy("slime", 0)]);
+        for target in [1, 5] {
+            assert_eq!(
+                engine.execute(Command::PlayCard {
+                    card: 0,
+                    target: Some(target),
+                }),
+                CommandOutcome::Rejected
+            );
+            assert_eq!(
+                engine.drain_events(),
+                [GameEvent::ActionRejected(Rejection::NoSuchTarget {
+                    target
+                })]
+            );
+        }
+        assert!(engine.player.hand()[0].is_ready());
+        assert_eq!(engine.player.energy(), engine.player.max_energy());
     }
 
     #[test]
-    fn sim_vampiric_touch_damages_and_heals() {
-        let mut engine = GameEngine::new_with_enemy(Box::new(Slime::new("史莱姆", 5)));
-        // Replace the 2nd default skill with vampiric_touch
-        engine.player.skills[1] = create_vampiric_touch();
-        engine.player.take_damage(1); // HP: 2
-        let enemy_hp_before = engine.enemy.hp();
-        // Vampiric touch is skill 2 => index = cards + 2
-        let choice = (engine.player.hand.len() + 2).to_string();
-        engine.try_execute_player_action(&choice, false);
-        assert!(engine.enemy.hp() < enemy_hp_before, "吸血之触应造成伤害");
-        assert_eq!(engine.player.hp(), 3, "吸血之触应恢复 HP");
+    fn whirlwind_hits_every_enemy_standing() {
+        let mut engine = GameEngine::new_with_enemies(vec![
+            test_enemy("slime", 3),
+            test_enemy("slime", 0),
+            test_enemy("slime", 3),
+        ]);
+        engine.give_card("whirlwind");
+        let whirlwind = engine.player.hand().len() - 1;
+        engine.player.hand_mut()[whirlwind].set_initial_cooldown_ms(0);
+        assert_eq!(
+            play_fresh(&mut engine, whirlwind),
+            CommandOutcome::CardPlayed
+        );
+        let hp: Vec<_> = engine.enemies.iter().map(|e| e.hp()).collect();
+        assert_eq!(hp, [2, 0, 2]);
     }
 
     #[test]
-    fn sim_war_cry_gives_shield() {
-        let mut engine = GameEngine::new_with_enemy(Box::new(Slime::new("史莱姆", 3)));
-        // Replace the 2nd default skill with war_cry
-        engine.player.skills[1] = create_war_cry();
-        assert_eq!(engine.player.shield(), 0);
-        // War cry is skill 2 => index = cards + 2
-        let choice = (engine.player.hand.len() + 2).to_string();
-        engine.try_execute_player_action(&choice, false);
-        assert_eq!(engine.player.shield(), 2, "战吼应给予 2 点护盾");
+    fn every_enemy_declares_its_own_intent() {
+        let mut engine = GameEngine::new_with_enemies(vec![
+            test_enemy("slime", 3),
+            test_enemy("forest_wolf", 3),
+        ]);
+        engine.player.gain_max_hp(30);
+        let mut frontend = ScriptedFrontend::new(vec![]);
+        engine.play_round(&mut frontend);
+        let mut declared: Vec<_> = frontend
+            .events
+            .iter()
+            .filter_map(|e| match e {
+                GameEvent::IntentDeclared(intent) => Some(intent.enemy),
+                _ => None,
+            })
+            .collect();
+        declared.sort();
+        assert_eq!(declared, [0, 1]);
+        for side in [Side::Enemy(0), Side::Enemy(1)] {
+            assert!(frontend
+                .events
+                .iter()
+                .any(|e| matches!(e, GameEvent::CardPlayed { side: s, .. } if *s == side)));
+        }
     }
 
     #[test]
-    fn sim_skeleton_mage_starts_with_shield() {
-        let engine = GameEngine::new_with_enemy(Box::new(SkeletonMage::new("骷髅法师", 5)));
-        assert_eq!(engine.enemy.shield(), 1, "骷髅法师应初始拥有 1 点护盾");
+    fn encounters_grow_with_depth() {
+        let mut rng = StdRng::seed_from_u64(5);
+        let sizes = |floor, rng: &mut StdRng| -> Vec<usize> {
+            (0..200).map(|_| encounter_size(floor, rng)).collect()
+        };
+        assert!(sizes(0, &mut rng).iter().all(|&n| n == 1));
+        let middle = sizes(SECOND_ENEMY_FLOOR, &mut rng);
+        assert!(middle.contains(&2) && middle.iter().all(|&n| n <= 2));
+        let deep = sizes(THIRD_ENEMY_FLOOR, &mut rng);
+        assert!(deep.contains(&3) && deep.iter().all(|&n| n <= MAX_ENEMIES));
     }
 
     #[test]
-    fn sim_forest_wolf_has_dodge() {
-        let engine = GameEngine::new_with_enemy(Box::new(ForestWolf::new("森林狼", 3)));
-        assert!(
-            (engine.enemy.dodge_chance() - 0.15).abs() < f64::EPSILON,
-            "森林狼应有 15% 闪避率"
+    fn goblin_bandit_steals_gold_and_returns_it_on_defeat() {
+        let bandit = GameData::builtin()
+            .enemies
+            .get("goblin_bandit")
+            .unwrap()
+            .spawn();
+        let mut engine = GameEngine::new_with_enemy(bandit);
+        engine.player.add_gold(1);
+        engine.resolve_effect(Side::Enemy(0), &Effect::Damage(1));
+        engine.resolve_effect(Side::Enemy(0), &Effect::Damage(1));
+        assert_eq!(engine.player.gold(), 0);
+        assert_eq!(engine.enemies[0].stolen_gold(), 1, "没钱可偷时什么也拿不到");
+        let events = engine.drain_events();
+        assert_eq!(
+            events
+                .iter()
+                .filter(|e| **e
+                    == GameEvent::GoldStolen {
+                        enemy: 0,
+                        amount: 1
+                    })
+                .count(),
+            1
         );
+
+        engine.claim_victory();
+        let events = engine.drain_events();
+        let won = events
+            .iter()
+            .position(|e| matches!(e, GameEvent::BattleWon { .. }))
+            .unwrap();
+        assert_eq!(events[won + 1], GameEvent::GoldRecovered { amount: 1 });
+        assert_eq!(engine.enemies[0].stolen_gold(), 0);
     }
 
     #[test]
-    fn sim_dragon_boss_high_hp() {
-        let engine = GameEngine::new_with_enemy(Box::new(Dragon::new("巨龙", 8)));
-        assert_eq!(engine.enemy.hp(), 8);
-        assert_eq!(engine.enemy.max_hp(), 8);
+    fn elites_always_offer_an_uncommon_or_better_relic() {
+        /// Takes the rarest relic on offer.
+        struct BestRelicPicker;
+
+        impl Frontend for BestRelicPicker {
+            fn on_event(&mut self, _engine: &GameEngine, _event: &GameEvent) {}
+
+            fn poll_command(&mut self, _engine: &GameEngine) -> Option<Command> {
+                None
+            }
+
+            fn shop_command(&mut self, _engine: &GameEngine) -> Option<Command> {
+                None
+            }
+
+            fn choose_relic(&mut self, engine: &GameEngine) -> Option<usize> {
+                let offers = engine.relic_offers();
+                (0..offers.len()).max_by_key(|&i| offers[i].tier)
+            }
+        }
+
+        for seed in 0..20 {
+            let mut engine = GameEngine::new_with_enemy(test_enemy("goblin_bandit", 1));
+            engine.rng = StdRng::seed_from_u64(seed);
+            engine.map = mid_run_map(NodeKind::Elite);
+            engine.enemies[0].take_damage(1);
+            assert!(engine.fight(&mut BestRelicPicker));
+            let relic = RelicRegistry::builtin()
+                .get(&engine.player.relics()[0])
+                .unwrap();
+            assert!(relic.tier >= RelicTier::Uncommon, "{seed}: {}", relic.id);
+        }
     }
 
     #[test]
-    fn sim_boss_card_deals_two_damage() {
-        let mut engine = GameEngine::new_with_enemy(Box::new(Dragon::new("巨龙", 8)));
-        let mut boss_card = GameEngine::create_boss_card();
-        boss_card.set_initial_cooldown_ms(0);
-        engine.enemy_card = boss_card;
-        let hp_before = engine.player.hp();
-        engine.execute_enemy_action();
+    fn relics_change_round_length_and_shop_prices() {
+        let mut engine = GameEngine::new_with_enemy(test_enemy("slime", 3));
+        assert_eq!(engine.round_duration(), ROUND_DURATION);
+        engine.player.add_relic("battle_drum");
+        engine.player.add_relic("merchant_badge");
         assert_eq!(
-            engine.player.hp(),
-            hp_before - 2,
-            "Boss 龙息应造成 2 点伤害"
+            engine.round_duration(),
+            ROUND_DURATION + Duration::from_secs(2)
+        );
+
+        let strike = engine.shop_offer(card_item("critical_strike"), false);
+        assert_eq!(strike.price, 3, "稀有卡 5 金币，商人徽章减 2");
+    }
+
+    #[test]
+    fn rest_sites_upgrade_a_card_or_raise_max_hp() {
+        struct Upgrader(Option<usize>);
+        impl Frontend for Upgrader {
+            fn on_event(&mut self, _engine: &GameEngine, _event: &GameEvent) {}
+            fn poll_command(&mut self, _engine: &GameEngine) -> Option<Command> {
+                None
+            }
+            fn shop_command(&mut self, _engine: &GameEngine) -> Option<Command> {
+                None
+            }
+            fn choose_upgrade(&mut self, _engine: &GameEngine) -> Option<usize> {
+                self.0
+            }
+        }
+
+        let mut engine = GameEngine::new_with_enemy(test_enemy("slime", 3));
+        let max_hp = engine.player.max_hp();
+        engine.rest(&mut Upgrader(Some(0)));
+        assert_eq!(engine.player.deck().cards()[0].name, "攻击+");
+        assert_eq!(engine.player.max_hp(), max_hp);
+
+        engine.rest(&mut Upgrader(Some(0)));
+        assert_eq!(
+            engine.player.max_hp(),
+            max_hp + 1,
+            "已升级的卡不能再升级，改为休整"
+        );
+        engine.rest(&mut Upgrader(None));
+        assert_eq!(engine.player.max_hp(), max_hp + 2);
+    }
+
+    /// A map standing on a `kind` node with a battle still to come, so victories are rewarded.
+    fn mid_run_map(kind: NodeKind) -> RunMap {
+        let mut map = RunMap::from_floors(vec![
+            vec![MapNode::new(kind, vec![0])],
+            vec![MapNode::new(NodeKind::Battle, vec![])],
+        ]);
+        map.advance(0);
+        map
+    }
+
+    /// Remembers every reward offered and answers each with the same choice.
+    struct RewardPicker {
+        choice: Option<usize>,
+        offered: Vec<Vec<RewardItem>>,
+    }
+
+    impl Frontend for RewardPicker {
+        fn on_event(&mut self, engine: &GameEngine, event: &GameEvent) {
+            if *event == GameEvent::RewardOffered {
+                self.offered.push(engine.reward_offers().to_vec());
+            }
+        }
+
+        fn poll_command(&mut self, _engine: &GameEngine) -> Option<Command> {
+            None
+        }
+
+        fn shop_command(&mut self, _engine: &GameEngine) -> Option<Command> {
+            None
+        }
+
+        fn choose_reward(&mut self, _engine: &GameEngine) -> Option<usize> {
+            self.choice
+        }
+    }
+
+    /// Wins a one-node battle of `kind` against a monster already at 0 HP.
+    fn win_node(kind: NodeKind, seed: u64, frontend: &mut RewardPicker) -> GameEngine {
+        let mut engine = GameEngine::new_with_enemy(test_enemy("slime", 1));
+        engine.rng = StdRng::seed_from_u64(seed);
+        engine.map = mid_run_map(kind);
+        engine.enemies[0].take_damage(1);
+        assert!(engine.fight(frontend));
+        engine
+    }
+
+    #[test]
+    fn battle_rewards_add_the_chosen_card_or_nothing() {
+        let owned = GameEngine::new_with_enemy(test_enemy("slime", 1))
+            .player
+            .deck()
+            .cards()
+            .len();
+        let mut skip = RewardPicker {
+            choice: None,
+            offered: Vec::new(),
+        };
+        let engine = win_node(NodeKind::Battle, 3, &mut skip);
+        assert_eq!(skip.offered.len(), 1);
+        assert_eq!(skip.offered[0].len(), CARD_REWARD_CHOICES);
+        assert_eq!(engine.player.deck().cards().len(), owned);
+        assert!(engine.reward_offers().is_empty());
+
+        let mut take = RewardPicker {
+            choice: Some(0),
+            offered: Vec::new(),
+        };
+        let engine = win_node(NodeKind::Battle, 3, &mut take);
+        assert_eq!(take.offered, skip.offered, "同一种子给出同样的奖励");
+        let RewardItem::Card(id) = &take.offered[0][0] else {
+            panic!("第一个奖励总是卡牌");
+        };
+        let cards = engine.player.deck().cards();
+        assert_eq!(cards.len(), owned + 1);
+        assert_eq!(cards[owned].name, engine.data.cards.card(id).name);
+    }
+
+    #[test]
+    fn elite_rewards_hold_a_rarer_card_and_sometimes_an_alternative() {
+        let mut picker = RewardPicker {
+            choice: None,
+            offered: Vec::new(),
+        };
+        for seed in 0..40 {
+            win_node(NodeKind::Elite, seed, &mut picker);
+        }
+        let rarity = |item: &RewardItem| match item {
+            RewardItem::Card(id) => GameData::builtin().cards.card(id).rarity,
+            _ => CardRarity::Common,
+        };
+        for offers in &picker.offered {
+            assert!(offers
+                .iter()
+                .any(|item| rarity(item) >= CardRarity::Uncommon));
+            assert!(offers[..offers.len() - 1]
+                .iter()
+                .all(|item| matches!(item, RewardItem::Card(_))));
+        }
+        assert!(picker
+            .offered
+            .iter()
+            .any(|offers| !matches!(offers.last(), Some(RewardItem::Card(_)))));
+    }
+
+    #[test]
+    fn reward_skills_and_relics_are_granted() {
+        let mut engine = GameEngine::new_with_enemy(test_enemy("slime", 1));
+        engine.player.skills.pop();
+        engine.take_reward(RewardItem::Skill("war_cry"));
+        engine.take_reward(RewardItem::Relic("merchant_badge"));
+        assert!(engine.player.skills.iter().any(|s| s.name == "战吼"));
+        assert_eq!(engine.player.relics(), ["merchant_badge".to_string()]);
+        assert_eq!(
+            engine.drain_events(),
+            vec![
+                GameEvent::RewardTaken {
+                    name: "战吼".to_string()
+                },
+                GameEvent::RelicGained {
+                    relic: "merchant_badge".to_string()
+                }
+            ]
         );
     }
 
+    #[test]
+    fn boss_victory_offers_a_rare_relic() {
+        let mut engine = GameEngine::new_with_enemy(test_enemy("dragon", 1));
+        engine.map = mid_run_map(NodeKind::Boss);
+        engine.enemies[0].take_damage(1);
+
+        assert!(engine.fight(&mut RelicPicker));
+        let relics: Vec<_> = engine.player.relic_defs().collect();
+        assert_eq!(relics.len(), 1);
+        assert!(relics[0].tier >= RelicTier::Rare);
+        assert!(engine.relic_offers().is_empty());
+    }
+
+    #[test]
+    fn the_last_victory_ends_the_run_without_rewards() {
+        let mut engine = GameEngine::new_with_enemy(test_enemy("dragon", 1));
+        engine.map = RunMap::from_floors(vec![vec![MapNode::new(NodeKind::Boss, vec![])]]);
+        engine.map.advance(0);
+        engine.enemies[0].take_damage(1);
+
+        let mut frontend = ScriptedFrontend::new(Vec::new());
+        assert_eq!(engine.run(&mut frontend), RunOutcome::Cleared);
+        assert!(engine.player.relics().is_empty());
+        assert!(!frontend
+            .events
+            .iter()
+            .any(|e| matches!(e, GameEvent::RelicRewardOffered | GameEvent::RewardOffered)));
+    }
+
     #[test]
     fn player_spend_gold() {
         let mut engine = GameEngine::new();
@@ -984,19 +3827,29 @@ mod tests {
         assert_eq!(engine.player.gold(), 5);
     }
 
+    #[test]
+    fn the_starting_deck_only_uses_core_cards() {
+        let ids: Vec<_> = STARTING_DECK.iter().map(|(id, _)| *id).collect();
+        assert_eq!(ids, crate::card::catalog::CORE_CARDS);
+    }
+
     #[test]
     fn player_reset_for_battle() {
         let mut engine = GameEngine::new();
         engine.player.take_damage(2);
         engine.player.add_shield(3);
-        for card in &mut engine.player.hand {
-            card.trigger_cooldown();
+        engine.player.spend_energy(2);
+        for skill in &mut engine.player.skills {
+            skill.trigger_cooldown();
         }
         engine.player.reset_for_battle();
-        assert_eq!(engine.player.hp(), engine.player.max_hp());
+        assert_eq!(
+            engine.player.hp(),
+            engine.player.max_hp() - 2,
+            "生命值不会自动回满"
+        );
         assert_eq!(engine.player.shield(), 0);
-        for card in &engine.player.hand {
-            assert!(card.is_ready());
-        }
+        assert_eq!(engine.player.energy(), engine.player.max_energy());
+        assert!(engine.player.skills.iter().all(|s| s.is_ready()));
     }
 }
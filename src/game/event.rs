@@ -0,0 +1,298 @@
+use std::time::Duration;
+
+use super::map::{EventOutcome, NodeKind};
+use crate::card::CardEffect;
+
+/// Which combatant an event refers to: the player, or an enemy by its position in
+/// [`GameEngine::enemies`](super::engine::GameEngine::enemies).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+    Player,
+    Enemy(usize),
+}
+
+/// A typed player command. Indices are zero-based.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Command {
+    /// Play the card at this position in the hand, aimed at enemy `target`, or at the
+    /// current target if `None`.
+    PlayCard { card: usize, target: Option<usize> },
+    /// Activate the equipped skill at this position, aimed like a card.
+    UseSkill { skill: usize, target: Option<usize> },
+    /// Buy the shop offer at this position.
+    BuyItem(usize),
+    /// Buy the shop service at `offer` (an upgrade or a removal) for owned card `card`,
+    /// an index into the deck's [`cards`](crate::card::Deck::cards).
+    BuyService { offer: usize, card: usize },
+    /// Buy the skill at shop offer `offer` in place of equipped skill `skill`, which is
+    /// sold back towards the price.
+    ReplaceSkill { offer: usize, skill: usize },
+    /// Sell owned card `card` back to the shop, an index into the deck's
+    /// [`cards`](crate::card::Deck::cards).
+    SellCard(usize),
+    /// Unequip the skill at this position and sell it back to the shop.
+    SellSkill(usize),
+}
+
+/// What the engine did with a command.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CommandOutcome {
+    Rejected,
+    CardPlayed,
+    SkillUsed,
+    ItemBought,
+    ItemSold,
+}
+
+/// Why a command was refused.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Rejection {
+    /// The index does not point at a card, skill or shop offer.
+    NoSuchAction,
+    /// The command aimed at an enemy that is not there or already down.
+    NoSuchTarget {
+        target: usize,
+    },
+    /// The card costs more energy than the player has left this round.
+    NotEnoughEnergy {
+        name: String,
+        cost: i32,
+        energy: i32,
+    },
+    OnCooldown {
+        name: String,
+        remaining_secs: u64,
+    },
+    NotEnoughGold {
+        price: i32,
+        gold: i32,
+    },
+    /// A spell card costs more mana than the player has.
+    NotEnoughMana {
+        name: String,
+        cost: i32,
+        mana: i32,
+    },
+    /// The skill bar is full; the gold was refunded.
+    SkillSlotsFull {
+        max: usize,
+    },
+    /// Selling the card would leave fewer cards than a hand needs.
+    DeckTooSmall {
+        min: usize,
+    },
+}
+
+/// What an enemy has committed to doing this round, decided at round start.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EnemyIntent {
+    /// Position of the acting enemy in the battle.
+    pub enemy: usize,
+    /// Position of the card in the enemy's deck.
+    pub card: usize,
+    pub card_name: String,
+    pub effect: CardEffect,
+    /// How long after the round starts the enemy acts.
+    pub delay: Duration,
+}
+
+/// Everything observable that happens in a run, in the order it happened.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GameEvent {
+    RunStarted {
+        /// Floors on the map, the boss's included.
+        floors: u32,
+        seed: u64,
+    },
+    /// A saved run was loaded and continues at `stage`.
+    RunResumed {
+        stage: u32,
+    },
+    /// The player stopped between stages; the next node is on floor `stage`.
+    RunSuspended {
+        stage: u32,
+    },
+    /// The player must pick the next node; see [`GameEngine::map`](super::engine::GameEngine::map).
+    RouteOffered,
+    /// The player arrived at a node on floor `stage`.
+    NodeEntered {
+        stage: u32,
+        kind: NodeKind,
+    },
+    /// A rest site lets the player upgrade a card instead of resting.
+    UpgradeOffered,
+    /// A rest site raised max HP by `max_hp`.
+    Rested {
+        max_hp: i32,
+    },
+    /// An owned card was upgraded, e.g. from "攻击" to "攻击+".
+    CardUpgraded {
+        from: String,
+        to: String,
+    },
+    EventResolved(EventOutcome),
+    BattleStarted {
+        stage: u32,
+        is_boss: bool,
+    },
+    /// The player's new hand is drawn right after it.
+    RoundStarted {
+        round: u32,
+    },
+    /// The player's discard pile was shuffled back into an empty draw pile.
+    DeckReshuffled,
+    /// A card effect drew `count` extra cards; 0 when there was nothing left to draw.
+    CardsDrawn {
+        count: usize,
+    },
+    /// Announced right after `RoundStarted`; absent when the enemy cannot act this round.
+    IntentDeclared(EnemyIntent),
+    CardPlayed {
+        side: Side,
+        name: String,
+    },
+    /// A card the player played left play for the rest of the battle.
+    CardExhausted {
+        name: String,
+    },
+    SkillUsed {
+        name: String,
+    },
+    ActionRejected(Rejection),
+    Dodged {
+        target: Side,
+    },
+    ShieldAbsorbed {
+        target: Side,
+        amount: i32,
+    },
+    DamageDealt {
+        target: Side,
+        amount: i32,
+    },
+    /// A hit was fully absorbed by shield.
+    AttackBlocked {
+        target: Side,
+    },
+    ShieldGained {
+        target: Side,
+        amount: i32,
+    },
+    /// `amount` is the HP actually restored and may be zero at full health.
+    Healed {
+        target: Side,
+        amount: i32,
+    },
+    /// Every card held by `side` cooled down by `amount_ms`.
+    CardCooldownsReduced {
+        side: Side,
+        amount_ms: u64,
+    },
+    BuffApplied {
+        target: Side,
+        buff: String,
+        stacks: u32,
+    },
+    /// Thorns-like buffs sent damage back at `target`; the damage events follow.
+    DamageReflected {
+        target: Side,
+        amount: i32,
+    },
+    /// A buff's hook fired; its effect follows as ordinary damage or heal events.
+    BuffTriggered {
+        target: Side,
+        buff: String,
+    },
+    /// A buff ran out at the end of a round.
+    BuffExpired {
+        target: Side,
+        buff: String,
+    },
+    /// The action was spent without effect because a buff (e.g. stun) skipped it.
+    ActionSkipped {
+        side: Side,
+        name: String,
+        buff: String,
+    },
+    /// The side did nothing during the round that just ended.
+    Idle {
+        side: Side,
+    },
+    /// An enemy's HP crossed a threshold and its fight moved to `phase`, counting from 1.
+    /// Any enrage or summon follows as ordinary events.
+    PhaseChanged {
+        enemy: usize,
+        phase: usize,
+        message: String,
+    },
+    /// An enemy split in two; `EnemyJoined` follows for the copy, or `BattlefieldFull`.
+    EnemySplit {
+        enemy: usize,
+    },
+    /// A split copy or a summoned monster joined the battle.
+    EnemyJoined {
+        enemy: usize,
+    },
+    /// A split or summon fizzled because the battle already has as many enemies as it can hold.
+    BattlefieldFull,
+    /// An enemy took gold from the player.
+    GoldStolen {
+        enemy: usize,
+        amount: i32,
+    },
+    /// Stolen gold came back when the thief was defeated.
+    GoldRecovered {
+        amount: i32,
+    },
+    BattleWon {
+        is_boss: bool,
+    },
+    BattleLost {
+        stage: u32,
+    },
+    GoldGained {
+        amount: i32,
+        base: i32,
+        bonus: i32,
+    },
+    /// Final state of both combatants after a battle.
+    BattleSummary,
+    RunCleared {
+        gold: i32,
+    },
+    ShopOpened,
+    ItemBought {
+        name: String,
+    },
+    /// A card left the deck for good.
+    CardRemoved {
+        name: String,
+    },
+    /// The shop bought back a card or an unequipped skill.
+    ItemSold {
+        name: String,
+        gold: i32,
+    },
+    ShopSoldOut,
+    ShopClosed,
+    /// A post-battle reward is on offer; see [`GameEngine::reward_offers`](super::engine::GameEngine::reward_offers).
+    RewardOffered,
+    /// A card joined the deck, or a skill was learned, from a reward.
+    RewardTaken {
+        name: String,
+    },
+    /// A relic reward is on offer; see [`GameEngine::relic_offers`](super::engine::GameEngine::relic_offers).
+    RelicRewardOffered,
+    RelicGained {
+        relic: String,
+    },
+    /// A relic's hook fired; its effect follows as ordinary events.
+    RelicTriggered {
+        relic: String,
+    },
+    /// A passive skill's hook fired for `side`; its effect follows as ordinary events.
+    PassiveTriggered {
+        side: Side,
+        passive: String,
+    },
+}
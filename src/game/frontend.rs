@@ -0,0 +1,47 @@
+use super::engine::GameEngine;
+use super::event::{Command, GameEvent};
+
+/// A presentation layer driving the engine: it supplies commands and consumes events.
+///
+/// The engine passes itself in read-only so a front end can render any state it needs.
+pub trait Frontend {
+    /// Receives every event the engine emits, in order.
+    fn on_event(&mut self, engine: &GameEngine, event: &GameEvent);
+
+    /// Polled repeatedly during a battle round. Must not block; return `None`
+    /// when no command is pending.
+    fn poll_command(&mut self, engine: &GameEngine) -> Option<Command>;
+
+    /// Asked for the next shop command; `None` leaves the shop. May block.
+    fn shop_command(&mut self, engine: &GameEngine) -> Option<Command>;
+
+    /// Asked for the next node, as an index into [`RunMap::choices`](super::map::RunMap::choices)
+    /// of [`GameEngine::map`]. Out-of-range answers take the first path. May block.
+    fn choose_path(&mut self, _engine: &GameEngine) -> usize {
+        0
+    }
+
+    /// Asked once a relic reward is offered, for an index into
+    /// [`GameEngine::relic_offers`]; `None` skips the reward. May block.
+    fn choose_relic(&mut self, _engine: &GameEngine) -> Option<usize> {
+        None
+    }
+
+    /// Asked once a post-battle reward is offered, for an index into
+    /// [`GameEngine::reward_offers`]; `None` skips it. May block.
+    fn choose_reward(&mut self, _engine: &GameEngine) -> Option<usize> {
+        None
+    }
+
+    /// Asked at a rest site for a card to upgrade instead of resting, as an index into
+    /// the deck's [`cards`](crate::card::Deck::cards); `None` rests. May block.
+    fn choose_upgrade(&mut self, _engine: &GameEngine) -> Option<usize> {
+        None
+    }
+
+    /// Asked after each step on the map, before the new node is played. Returning `true`
+    /// stops the run so it can be saved.
+    fn wants_to_suspend(&mut self, _engine: &GameEngine) -> bool {
+        false
+    }
+}
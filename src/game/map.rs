@@ -0,0 +1,285 @@
+//! The branching route a run follows, generated from the run's seed.
+
+use rand::distributions::{Distribution, WeightedIndex};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+/// Floors before the boss; the boss sits alone on the floor after them.
+pub const FLOORS_BEFORE_BOSS: usize = 6;
+const MIN_WIDTH: usize = 2;
+const MAX_WIDTH: usize = 3;
+/// Chance that a node leads to a second node on the next floor.
+const BRANCH_CHANCE: f64 = 0.5;
+
+/// What waits at a node.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum NodeKind {
+    Battle,
+    Elite,
+    Shop,
+    Rest,
+    Event,
+    Treasure,
+    Boss,
+}
+
+impl NodeKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            NodeKind::Battle => "战斗",
+            NodeKind::Elite => "精英",
+            NodeKind::Shop => "商店",
+            NodeKind::Rest => "休息处",
+            NodeKind::Event => "事件",
+            NodeKind::Treasure => "宝箱",
+            NodeKind::Boss => "首领",
+        }
+    }
+
+    pub fn icon(self) -> &'static str {
+        match self {
+            NodeKind::Battle => "⚔️",
+            NodeKind::Elite => "👹",
+            NodeKind::Shop => "🛒",
+            NodeKind::Rest => "🔥",
+            NodeKind::Event => "❓",
+            NodeKind::Treasure => "💎",
+            NodeKind::Boss => "🐉",
+        }
+    }
+
+    /// Whether the node is a fight.
+    pub fn is_battle(self) -> bool {
+        matches!(self, NodeKind::Battle | NodeKind::Elite | NodeKind::Boss)
+    }
+}
+
+/// Relative chance of each kind on a middle floor, and the first floor it may appear on.
+const MIDDLE_FLOOR_KINDS: [(NodeKind, u32, usize); 6] = [
+    (NodeKind::Battle, 45, 1),
+    (NodeKind::Event, 18, 1),
+    (NodeKind::Shop, 12, 1),
+    (NodeKind::Elite, 12, 2),
+    (NodeKind::Rest, 8, 2),
+    (NodeKind::Treasure, 5, 1),
+];
+
+/// One stop on the map.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MapNode {
+    pub kind: NodeKind,
+    /// Indices of the nodes it leads to on the next floor, in ascending order.
+    pub next: Vec<usize>,
+}
+
+impl MapNode {
+    pub fn new(kind: NodeKind, next: Vec<usize>) -> Self {
+        Self { kind, next }
+    }
+}
+
+/// Floors of nodes plus where the player stands.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RunMap {
+    floors: Vec<Vec<MapNode>>,
+    /// Floor and index of the current node; `None` before the first step.
+    position: Option<(usize, usize)>,
+}
+
+impl RunMap {
+    /// Builds a map from explicit floors. Edges pointing past the next floor are ignored.
+    pub fn from_floors(floors: Vec<Vec<MapNode>>) -> Self {
+        Self {
+            floors,
+            position: None,
+        }
+    }
+
+    /// Rolls a fresh map: [`FLOORS_BEFORE_BOSS`] floors of two or three nodes, then the boss.
+    ///
+    /// The first floor is all battles and the last before the boss all rest sites.
+    pub fn generate(rng: &mut impl Rng) -> Self {
+        let mut floors: Vec<Vec<MapNode>> = (0..FLOORS_BEFORE_BOSS)
+            .map(|floor| {
+                let width = rng.gen_range(MIN_WIDTH..=MAX_WIDTH);
+                (0..width)
+                    .map(|_| MapNode::new(roll_kind(floor, rng), Vec::new()))
+                    .collect()
+            })
+            .collect();
+        floors.push(vec![MapNode::new(NodeKind::Boss, Vec::new())]);
+
+        for floor in 0..floors.len() - 1 {
+            let (from, to) = floors.split_at_mut(floor + 1);
+            connect(&mut from[floor], to[0].len(), rng);
+        }
+        Self::from_floors(floors)
+    }
+
+    pub fn floors(&self) -> &[Vec<MapNode>] {
+        &self.floors
+    }
+
+    pub fn position(&self) -> Option<(usize, usize)> {
+        self.position
+    }
+
+    pub fn current(&self) -> Option<&MapNode> {
+        let (floor, index) = self.position?;
+        self.floors.get(floor)?.get(index)
+    }
+
+    /// The floor the next step lands on.
+    pub fn next_floor(&self) -> usize {
+        self.position.map_or(0, |(floor, _)| floor + 1)
+    }
+
+    /// Indices on the next floor the player can move to: every first-floor node before
+    /// the first step, then the current node's paths. Empty once the map is done.
+    pub fn choices(&self) -> Vec<usize> {
+        let Some(next) = self.floors.get(self.next_floor()) else {
+            return Vec::new();
+        };
+        match self.current() {
+            None => (0..next.len()).collect(),
+            Some(node) => node
+                .next
+                .iter()
+                .copied()
+                .filter(|&i| i < next.len())
+                .collect(),
+        }
+    }
+
+    /// Moves to node `index` on the next floor. Returns false if no path leads there.
+    pub fn advance(&mut self, index: usize) -> bool {
+        if !self.choices().contains(&index) {
+            return false;
+        }
+        self.position = Some((self.next_floor(), index));
+        true
+    }
+}
+
+fn roll_kind(floor: usize, rng: &mut impl Rng) -> NodeKind {
+    if floor == 0 {
+        return NodeKind::Battle;
+    }
+    if floor == FLOORS_BEFORE_BOSS - 1 {
+        return NodeKind::Rest;
+    }
+    let allowed: Vec<_> = MIDDLE_FLOOR_KINDS
+        .iter()
+        .filter(|(_, _, from)| floor >= *from)
+        .collect();
+    let weights = WeightedIndex::new(allowed.iter().map(|(_, weight, _)| weight))
+        .expect("battles are always allowed");
+    allowed[weights.sample(rng)].0
+}
+
+/// Where node `i` of a floor `from` wide lines up on a floor `to` wide.
+fn aligned(i: usize, from: usize, to: usize) -> usize {
+    if from <= 1 {
+        return 0;
+    }
+    (i * (to - 1) + (from - 1) / 2) / (from - 1)
+}
+
+/// Gives every node of `floor` one or two paths to a floor `next_width` wide, so that
+/// every node there can be reached.
+fn connect(floor: &mut [MapNode], next_width: usize, rng: &mut impl Rng) {
+    let width = floor.len();
+    for (i, node) in floor.iter_mut().enumerate() {
+        let home = aligned(i, width, next_width);
+        node.next = vec![home];
+        if next_width > 1 && rng.gen_bool(BRANCH_CHANCE) {
+            let neighbour = if home + 1 < next_width && (home == 0 || rng.gen_bool(0.5)) {
+                home + 1
+            } else {
+                home - 1
+            };
+            node.next.push(neighbour);
+        }
+    }
+    for j in 0..next_width {
+        if !floor.iter().any(|node| node.next.contains(&j)) {
+            floor[aligned(j, next_width, width)].next.push(j);
+        }
+    }
+    for node in floor {
+        node.next.sort_unstable();
+    }
+}
+
+/// What happened at an event node.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventOutcome {
+    FoundGold(i32),
+    /// Max HP raised by this much.
+    Blessing(i32),
+    /// A relic follows as its own event.
+    FoundRelic,
+    /// Gold lost; may be 0 when the player had none.
+    Trap(i32),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn generated_maps_are_connected_and_end_at_the_boss() {
+        for seed in 0..50 {
+            let map = RunMap::generate(&mut StdRng::seed_from_u64(seed));
+            let floors = map.floors();
+            assert_eq!(floors.len(), FLOORS_BEFORE_BOSS + 1);
+            assert!(floors[0].iter().all(|n| n.kind == NodeKind::Battle));
+            assert!(floors[FLOORS_BEFORE_BOSS - 1]
+                .iter()
+                .all(|n| n.kind == NodeKind::Rest));
+            assert_eq!(floors[FLOORS_BEFORE_BOSS][0].kind, NodeKind::Boss);
+            assert!(floors[1].iter().all(|n| n.kind != NodeKind::Elite));
+
+            for pair in floors.windows(2) {
+                for node in &pair[0] {
+                    assert!(!node.next.is_empty());
+                    assert!(node.next.iter().all(|&j| j < pair[1].len()));
+                }
+                for j in 0..pair[1].len() {
+                    assert!(pair[0].iter().any(|n| n.next.contains(&j)), "{seed}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn same_seed_same_map() {
+        let a = RunMap::generate(&mut StdRng::seed_from_u64(9));
+        let b = RunMap::generate(&mut StdRng::seed_from_u64(9));
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn advance_follows_paths_only() {
+        let mut map = RunMap::from_floors(vec![
+            vec![
+                MapNode::new(NodeKind::Battle, vec![0]),
+                MapNode::new(NodeKind::Battle, vec![1]),
+            ],
+            vec![
+                MapNode::new(NodeKind::Shop, vec![]),
+                MapNode::new(NodeKind::Event, vec![]),
+            ],
+        ]);
+        assert_eq!(map.choices(), [0, 1]);
+        assert!(map.advance(1));
+        assert_eq!(map.current().unwrap().kind, NodeKind::Battle);
+        assert_eq!(map.choices(), [1]);
+        assert!(!map.advance(0));
+        assert!(map.advance(1));
+        assert_eq!(map.position(), Some((1, 1)));
+        assert!(map.choices().is_empty());
+    }
+}
@@ -1 +1,8 @@
+pub mod cli;
+pub mod clock;
 pub mod engine;
+pub mod event;
+pub mod frontend;
+pub mod map;
+pub mod save;
+pub mod sim;
@@ -0,0 +1,106 @@
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::character::Player;
+use crate::enemy::Enemy;
+use crate::game::map::RunMap;
+
+/// Bumped whenever the layout of [`SaveData`] changes.
+pub const SAVE_VERSION: u32 = 11;
+
+pub const DEFAULT_SAVE_PATH: &str = "xiaoer_save.json";
+
+/// A run suspended on entering a map node, before it is played.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SaveData {
+    pub version: u32,
+    pub seed: u64,
+    /// Seed of the RNG stream for the node about to be played.
+    pub stage_seed: u64,
+    pub round: u32,
+    pub player: Player,
+    /// The enemies waiting at the node, if it is a fight.
+    pub enemies: Vec<Enemy>,
+    /// The map, including the node the player stands on.
+    pub map: RunMap,
+}
+
+#[derive(Debug)]
+pub enum SaveError {
+    Io(io::Error),
+    Format(serde_json::Error),
+    UnsupportedVersion(u32),
+}
+
+impl fmt::Display for SaveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SaveError::Io(e) => write!(f, "读写存档失败：{e}"),
+            SaveError::Format(e) => write!(f, "存档格式错误：{e}"),
+            SaveError::UnsupportedVersion(v) => {
+                write!(f, "不支持的存档版本 {v}（当前版本 {SAVE_VERSION}）")
+            }
+        }
+    }
+}
+
+impl std::error::Error for SaveError {}
+
+impl From<io::Error> for SaveError {
+    fn from(e: io::Error) -> Self {
+        SaveError::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for SaveError {
+    fn from(e: serde_json::Error) -> Self {
+        SaveError::Format(e)
+    }
+}
+
+/// Only the version field, read first so an old layout fails with a clear message.
+#[derive(Deserialize)]
+struct VersionProbe {
+    version: u32,
+}
+
+pub fn to_json(data: &SaveData) -> Result<String, SaveError> {
+    Ok(serde_json::to_string_pretty(data)?)
+}
+
+pub fn from_json(json: &str) -> Result<SaveData, SaveError> {
+    let probe: VersionProbe = serde_json::from_str(json)?;
+    if probe.version != SAVE_VERSION {
+        return Err(SaveError::UnsupportedVersion(probe.version));
+    }
+    Ok(serde_json::from_str(json)?)
+}
+
+pub fn write(path: &Path, data: &SaveData) -> Result<(), SaveError> {
+    fs::write(path, to_json(data)?)?;
+    Ok(())
+}
+
+pub fn read(path: &Path) -> Result<SaveData, SaveError> {
+    from_json(&fs::read_to_string(path)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_other_versions() {
+        let err = from_json(r#"{ "version": 999 }"#).unwrap_err();
+        assert!(matches!(err, SaveError::UnsupportedVersion(999)));
+    }
+
+    #[test]
+    fn rejects_garbage() {
+        assert!(matches!(from_json("不是存档"), Err(SaveError::Format(_))));
+    }
+}
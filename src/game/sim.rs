@@ -0,0 +1,388 @@
+use std::collections::BTreeMap;
+
+use super::clock::ManualClock;
+use super::engine::{GameEngine, RewardItem, ShopItem};
+use super::event::{Command, GameEvent};
+use super::frontend::Frontend;
+use super::map::NodeKind;
+use crate::card::{CardEffect, CardRarity};
+use crate::character::player::MAX_SKILLS;
+use crate::mechanics::combat::Combatant;
+use crate::skill::SkillEffect;
+
+/// A scripted way of playing, used by the headless simulator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Policy {
+    /// Always plays the hardest-hitting ready card; uses every skill as soon as it helps.
+    Aggressive,
+    /// Heals while hurt, shields when the declared enemy attack could be lethal, otherwise attacks.
+    Defensive,
+}
+
+impl Policy {
+    pub const ALL: [Policy; 2] = [Policy::Aggressive, Policy::Defensive];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Policy::Aggressive => "aggressive",
+            Policy::Defensive => "defensive",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|p| p.name() == name)
+    }
+
+    fn choose_card(&self, engine: &GameEngine) -> Option<usize> {
+        let player = engine.player();
+        let hurt = player.hp() < player.max_hp();
+        let incoming: i32 = engine
+            .enemy_intents()
+            .iter()
+            .map(|i| i.effect.damage())
+            .sum();
+        let threatened = player.hp() <= 1 || incoming >= player.hp();
+        let foes = engine.living_enemies().len() as i32;
+        let ready = player
+            .hand()
+            .iter()
+            .enumerate()
+            .filter(|(_, card)| card.is_ready() && player.can_afford(card));
+
+        let score = |effect: &CardEffect| match (self, effect.primary()) {
+            (Policy::Defensive, CardEffect::Heal(n)) if hurt => *n * 20,
+            (Policy::Defensive, CardEffect::Shield(n)) if threatened => *n * 15,
+            (_, CardEffect::DamageAll(n)) => *n * foes * 10,
+            (_, CardEffect::DrawCards(n)) => *n as i32 * 8,
+            _ => effect.damage() * 10,
+        };
+        ready
+            .map(|(i, card)| (i, score(&card.effect)))
+            .filter(|(_, s)| *s > 0)
+            .max_by_key(|(i, s)| (*s, std::cmp::Reverse(*i)))
+            .map(|(i, _)| i)
+    }
+
+    /// Prefers free rewards, then the shop when there is gold to spend. Only the
+    /// aggressive policy seeks out elites.
+    fn choose_path(&self, engine: &GameEngine) -> usize {
+        let map = engine.map();
+        let next = &map.floors()[map.next_floor()];
+        let can_shop = engine.player().gold() >= 3;
+        let score = |kind: NodeKind| match kind {
+            NodeKind::Treasure => 5,
+            NodeKind::Shop if can_shop => 4,
+            NodeKind::Elite if *self == Policy::Aggressive => 4,
+            NodeKind::Rest => 3,
+            NodeKind::Event => 2,
+            NodeKind::Battle | NodeKind::Boss => 1,
+            NodeKind::Shop | NodeKind::Elite => 0,
+        };
+        map.choices()
+            .iter()
+            .enumerate()
+            .max_by_key(|(i, &index)| (score(next[index].kind), std::cmp::Reverse(*i)))
+            .map_or(0, |(i, _)| i)
+    }
+
+    fn choose_skill(&self, engine: &GameEngine) -> Option<usize> {
+        let player = engine.player();
+        let hurt = player.hp() < player.max_hp();
+        let cards_cooling = player.hand().iter().any(|c| !c.is_ready());
+        player.skills.iter().position(|skill| {
+            skill.is_ready()
+                && match skill.effect {
+                    SkillEffect::Heal(_) => hurt,
+                    SkillEffect::ReduceAllCardCooldownMs(_) => cards_cooling,
+                    SkillEffect::Shield(_) => {
+                        *self == Policy::Aggressive || player.hp() < player.max_hp()
+                    }
+                    SkillEffect::Damage(_)
+                    | SkillEffect::DamageAll(_)
+                    | SkillEffect::DrawCards(_)
+                    | SkillEffect::GainGold(_)
+                    | SkillEffect::StealGold(_)
+                    | SkillEffect::Split
+                    | SkillEffect::ApplyBuff { .. }
+                    | SkillEffect::Composite(_) => true,
+                }
+        })
+    }
+
+    /// The aggressive policy upgrades the card whose upgrade adds the most damage; the
+    /// defensive one always rests for max HP.
+    fn choose_upgrade(&self, engine: &GameEngine) -> Option<usize> {
+        if *self == Policy::Defensive {
+            return None;
+        }
+        let cards = engine.player().deck().cards();
+        (0..cards.len())
+            .filter_map(|i| {
+                let gain = cards[i].upgraded_version()?.effect.damage() - cards[i].effect.damage();
+                Some((i, gain))
+            })
+            .max_by_key(|(i, gain)| (*gain, std::cmp::Reverse(*i)))
+            .map(|(i, _)| i)
+    }
+
+    /// The aggressive policy sheds a basic shield card to draw its attacks more often;
+    /// the defensive one keeps every card.
+    fn choose_removal(&self, engine: &GameEngine) -> Option<usize> {
+        if *self == Policy::Defensive {
+            return None;
+        }
+        engine.player().deck().cards().iter().position(|card| {
+            card.rarity == CardRarity::Common && matches!(card.effect, CardEffect::Shield(_))
+        })
+    }
+
+    /// Focuses the weakest enemy standing, to thin the crowd quickly.
+    fn choose_target(&self, engine: &GameEngine) -> Option<usize> {
+        if engine.enemies().len() <= 1 {
+            return None;
+        }
+        engine
+            .living_enemies()
+            .into_iter()
+            .min_by_key(|&i| engine.enemies()[i].hp())
+    }
+}
+
+/// Per-enemy battle statistics.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EnemyStats {
+    pub battles: u32,
+    pub rounds: u32,
+}
+
+impl EnemyStats {
+    pub fn average_rounds(&self) -> f64 {
+        if self.battles == 0 {
+            return 0.0;
+        }
+        self.rounds as f64 / self.battles as f64
+    }
+}
+
+/// Aggregated results of many simulated runs.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SimReport {
+    pub runs: u32,
+    pub wins: u32,
+    pub total_gold: i64,
+    /// Sum of HP left at the end of every cleared run.
+    pub total_hp_remaining: i64,
+    pub enemies: BTreeMap<String, EnemyStats>,
+}
+
+impl SimReport {
+    pub fn win_rate(&self) -> f64 {
+        if self.runs == 0 {
+            return 0.0;
+        }
+        self.wins as f64 / self.runs as f64
+    }
+
+    pub fn average_gold(&self) -> f64 {
+        if self.runs == 0 {
+            return 0.0;
+        }
+        self.total_gold as f64 / self.runs as f64
+    }
+
+    /// Average HP left over, counted over cleared runs only.
+    pub fn average_hp_remaining(&self) -> f64 {
+        if self.wins == 0 {
+            return 0.0;
+        }
+        self.total_hp_remaining as f64 / self.wins as f64
+    }
+}
+
+/// A front end that plays by policy and tallies statistics from the event stream.
+struct Bot<'a> {
+    policy: Policy,
+    report: &'a mut SimReport,
+    /// Names of the enemies the current battle started with.
+    current_enemies: Vec<String>,
+    battle_rounds: u32,
+}
+
+impl Bot<'_> {
+    fn finish_battle(&mut self) {
+        for name in std::mem::take(&mut self.current_enemies) {
+            let stats = self.report.enemies.entry(name).or_default();
+            stats.battles += 1;
+            stats.rounds += self.battle_rounds;
+        }
+    }
+}
+
+impl Frontend for Bot<'_> {
+    fn on_event(&mut self, engine: &GameEngine, event: &GameEvent) {
+        match event {
+            GameEvent::BattleStarted { .. } => {
+                self.current_enemies = engine
+                    .enemies()
+                    .iter()
+                    .map(|enemy| enemy.name().to_string())
+                    .collect();
+                self.battle_rounds = 0;
+            }
+            GameEvent::RoundStarted { .. } => self.battle_rounds += 1,
+            GameEvent::BattleWon { .. } | GameEvent::BattleLost { .. } => self.finish_battle(),
+            GameEvent::GoldGained { amount, .. } => self.report.total_gold += *amount as i64,
+            GameEvent::RunCleared { .. } => {
+                self.report.wins += 1;
+                self.report.total_hp_remaining += engine.player().hp() as i64;
+            }
+            _ => {}
+        }
+    }
+
+    fn poll_command(&mut self, engine: &GameEngine) -> Option<Command> {
+        let target = self.policy.choose_target(engine);
+        if let Some(skill) = self.policy.choose_skill(engine) {
+            return Some(Command::UseSkill { skill, target });
+        }
+        let card = self.policy.choose_card(engine)?;
+        Some(Command::PlayCard { card, target })
+    }
+
+    fn choose_path(&mut self, engine: &GameEngine) -> usize {
+        self.policy.choose_path(engine)
+    }
+
+    fn choose_relic(&mut self, engine: &GameEngine) -> Option<usize> {
+        // Rarer relics are stronger; take the rarest, the first one on a tie.
+        engine
+            .relic_offers()
+            .iter()
+            .enumerate()
+            .max_by_key(|(i, def)| (def.tier, std::cmp::Reverse(*i)))
+            .map(|(i, _)| i)
+    }
+
+    fn choose_reward(&mut self, engine: &GameEngine) -> Option<usize> {
+        // Relics and skills beat any card; among cards take the rarest, and pass on
+        // commons so the deck stays lean.
+        let rank = |item: &RewardItem| match item {
+            RewardItem::Relic(_) => 4,
+            RewardItem::Skill(_) => 3,
+            RewardItem::Card(id) => match engine.data().cards.get(id).map(|c| c.rarity) {
+                Some(CardRarity::Rare) => 2,
+                Some(CardRarity::Uncommon) => 1,
+                _ => 0,
+            },
+        };
+        engine
+            .reward_offers()
+            .iter()
+            .enumerate()
+            .map(|(i, item)| (i, rank(item)))
+            .filter(|(_, rank)| *rank > 0)
+            .max_by_key(|(i, rank)| (*rank, std::cmp::Reverse(*i)))
+            .map(|(i, _)| i)
+    }
+
+    fn choose_upgrade(&mut self, engine: &GameEngine) -> Option<usize> {
+        self.policy.choose_upgrade(engine)
+    }
+
+    fn shop_command(&mut self, engine: &GameEngine) -> Option<Command> {
+        // Heal first when down to half HP; otherwise buy the most expensive thing we can
+        // afford, skills only if a slot is free, card services only for a card the policy
+        // picks.
+        let gold = engine.player().gold();
+        let player = engine.player();
+        if player.hp() * 2 <= player.max_hp() {
+            let heal = engine
+                .shop_offers()
+                .iter()
+                .position(|offer| offer.item == ShopItem::Heal && offer.price <= gold);
+            if let Some(i) = heal {
+                return Some(Command::BuyItem(i));
+            }
+        }
+        let skill_slot_free = engine.player().skills.len() < MAX_SKILLS;
+        engine
+            .shop_offers()
+            .iter()
+            .enumerate()
+            .filter(|(_, offer)| offer.price <= gold)
+            .filter(|(_, offer)| skill_slot_free || !offer.item.is_skill())
+            .filter_map(|(i, offer)| {
+                let command = match offer.item {
+                    ShopItem::Upgrade => Command::BuyService {
+                        offer: i,
+                        card: self.policy.choose_upgrade(engine)?,
+                    },
+                    ShopItem::RemoveCard => Command::BuyService {
+                        offer: i,
+                        card: self.policy.choose_removal(engine)?,
+                    },
+                    _ => Command::BuyItem(i),
+                };
+                Some((offer.price, command))
+            })
+            .max_by_key(|(price, _)| *price)
+            .map(|(_, command)| command)
+    }
+}
+
+/// Plays `runs` full runs on simulated time with seeds `seed, seed + 1, …`.
+pub fn simulate(runs: u32, seed: u64, policy: Policy) -> SimReport {
+    let mut report = SimReport::default();
+    for i in 0..runs {
+        let mut engine = GameEngine::with_seed(seed.wrapping_add(i as u64))
+            .with_clock(Box::new(ManualClock::new()));
+        let mut bot = Bot {
+            policy,
+            report: &mut report,
+            current_enemies: Vec::new(),
+            battle_rounds: 0,
+        };
+        engine.run(&mut bot);
+        report.runs += 1;
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn policy_names_round_trip() {
+        for policy in Policy::ALL {
+            assert_eq!(Policy::from_name(policy.name()), Some(policy));
+        }
+        assert_eq!(Policy::from_name("random"), None);
+    }
+
+    #[test]
+    fn simulate_counts_every_run() {
+        let report = simulate(5, 11, Policy::Aggressive);
+        assert_eq!(report.runs, 5);
+        assert!(report.wins <= 5);
+        assert!(!report.enemies.is_empty());
+        let battles: u32 = report.enemies.values().map(|s| s.battles).sum();
+        assert!(battles >= 5, "每局至少进行一场战斗");
+    }
+
+    #[test]
+    fn simulate_is_reproducible() {
+        assert_eq!(
+            simulate(3, 99, Policy::Defensive),
+            simulate(3, 99, Policy::Defensive)
+        );
+    }
+
+    #[test]
+    fn empty_report_averages_are_zero() {
+        let report = SimReport::default();
+        assert_eq!(report.win_rate(), 0.0);
+        assert_eq!(report.average_gold(), 0.0);
+        assert_eq!(report.average_hp_remaining(), 0.0);
+    }
+}
@@ -0,0 +1,10 @@
+//! 小二的回合制卡牌游戏 — game rules, shared by the terminal game and the balance simulator.
+
+pub mod card;
+pub mod character;
+pub mod data;
+pub mod enemy;
+pub mod game;
+pub mod mechanics;
+pub mod relic;
+pub mod skill;
@@ -1,13 +1,146 @@
-mod card;
-mod character;
-mod enemy;
-mod game;
-mod mechanics;
-mod skill;
+use std::fs;
+use std::path::{Path, PathBuf};
 
-use game::engine::GameEngine;
+use xiaoer_game::data::GameData;
+use xiaoer_game::game::cli::CliFrontend;
+use xiaoer_game::game::engine::{GameEngine, RunOutcome};
+use xiaoer_game::game::save::{self, DEFAULT_SAVE_PATH};
+
+#[derive(Debug, Default, PartialEq, Eq)]
+struct Options {
+    seed: Option<u64>,
+    resume: bool,
+    data_dir: Option<PathBuf>,
+}
 
 fn main() {
-    let mut engine = GameEngine::new();
-    engine.run();
+    let options = match parse_options(std::env::args().skip(1)) {
+        Ok(options) => options,
+        Err(msg) => {
+            eprintln!("{msg}");
+            eprintln!("用法：xiaoer_game [--seed <数字>] [--resume] [--data <目录>]");
+            std::process::exit(2);
+        }
+    };
+
+    let data = match &options.data_dir {
+        Some(dir) => match GameData::load(dir) {
+            Ok(data) => data,
+            Err(e) => {
+                eprintln!("无法加载游戏数据：{e}");
+                std::process::exit(1);
+            }
+        },
+        None => GameData::builtin().clone(),
+    };
+
+    let save_path = Path::new(DEFAULT_SAVE_PATH);
+    let mut engine = if options.resume {
+        match save::read(save_path).map(|save| GameEngine::from_save(save, data)) {
+            Ok(engine) => engine,
+            Err(e) => {
+                eprintln!("无法读取存档 {}：{e}", save_path.display());
+                std::process::exit(1);
+            }
+        }
+    } else {
+        match options.seed {
+            Some(seed) => GameEngine::with_data(seed, data),
+            None => GameEngine::with_data(rand::random(), data),
+        }
+    };
+
+    match engine.run(&mut CliFrontend::new()) {
+        RunOutcome::Suspended => match save::write(save_path, &engine.snapshot()) {
+            Ok(()) => println!("已保存到 {}，使用 --resume 继续冒险。", save_path.display()),
+            Err(e) => eprintln!("保存失败：{e}"),
+        },
+        RunOutcome::Cleared | RunOutcome::Defeated => {
+            // The run is over; a finished save must not be resumed again.
+            if options.resume {
+                let _ = fs::remove_file(save_path);
+            }
+        }
+    }
+}
+
+/// Parses `--seed <n>` / `--seed=<n>`, `--resume` and `--data <dir>` from the command line.
+fn parse_options(mut args: impl Iterator<Item = String>) -> Result<Options, String> {
+    let mut options = Options::default();
+    while let Some(arg) = args.next() {
+        if arg == "--resume" {
+            options.resume = true;
+            continue;
+        }
+        if arg == "--data" {
+            options.data_dir = Some(args.next().ok_or("--data 需要一个目录参数")?.into());
+            continue;
+        }
+        let value = if arg == "--seed" {
+            args.next().ok_or("--seed 需要一个数字参数")?
+        } else if let Some(value) = arg.strip_prefix("--seed=") {
+            value.to_string()
+        } else {
+            return Err(format!("未知参数：{arg}"));
+        };
+        options.seed = Some(
+            value
+                .parse::<u64>()
+                .map_err(|_| format!("无效的种子：{value}"))?,
+        );
+    }
+    if options.resume && options.seed.is_some() {
+        return Err("--resume 会沿用存档中的种子，不能与 --seed 同时使用".to_string());
+    }
+    Ok(options)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> impl Iterator<Item = String> {
+        list.iter()
+            .map(|s| s.to_string())
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+
+    fn seed_of(list: &[&str]) -> Result<Option<u64>, String> {
+        parse_options(args(list)).map(|o| o.seed)
+    }
+
+    #[test]
+    fn parse_seed_forms() {
+        assert_eq!(seed_of(&[]), Ok(None));
+        assert_eq!(seed_of(&["--seed", "42"]), Ok(Some(42)));
+        assert_eq!(seed_of(&["--seed=7"]), Ok(Some(7)));
+    }
+
+    #[test]
+    fn parse_seed_rejects_bad_input() {
+        assert!(seed_of(&["--seed"]).is_err());
+        assert!(seed_of(&["--seed", "abc"]).is_err());
+        assert!(seed_of(&["--fast"]).is_err());
+    }
+
+    #[test]
+    fn parse_resume() {
+        assert_eq!(
+            parse_options(args(&["--resume"])),
+            Ok(Options {
+                seed: None,
+                resume: true,
+                data_dir: None,
+            })
+        );
+        assert!(parse_options(args(&["--resume", "--seed", "1"])).is_err());
+    }
+
+    #[test]
+    fn parse_data_dir() {
+        let options = parse_options(args(&["--data", "mods/data"])).unwrap();
+        assert_eq!(options.data_dir, Some(PathBuf::from("mods/data")));
+        assert!(parse_options(args(&["--data"])).is_err());
+    }
 }
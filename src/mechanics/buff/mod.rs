@@ -0,0 +1,341 @@
+/// Buff / Debuff system.
+pub mod registry;
+
+pub use registry::{
+    BuffDef, BuffHooks, BuffKind, BuffRegistry, DamageTaken, Expiry, RoundStartEffect, Stacking,
+    FRAGILE, NOURISH, POISON, REGENERATION, STRENGTH, STUN, THORNS, WEAK,
+};
+
+use serde::{Deserialize, Serialize};
+
+/// A buff/debuff instance on a combatant.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Buff {
+    /// Id in [`BuffRegistry::builtin`].
+    pub id: String,
+    pub stacks: u32,
+    pub duration: u32, // 0 = permanent
+}
+
+impl Buff {
+    /// The registered definition; `None` for an id no longer in the registry.
+    pub fn def(&self) -> Option<&'static BuffDef> {
+        BuffRegistry::builtin().get(&self.id)
+    }
+
+    /// Tick one round. Returns true if expired.
+    fn tick(&mut self, def: &BuffDef) -> bool {
+        match def.expiry {
+            Expiry::Rounds => {
+                if self.duration == 0 {
+                    return false;
+                }
+                self.duration -= 1;
+                self.duration == 0
+            }
+            Expiry::DecayStacks => {
+                self.stacks = self.stacks.saturating_sub(1);
+                self.stacks == 0
+            }
+        }
+    }
+}
+
+/// Manages buffs on a combatant.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BuffManager {
+    pub buffs: Vec<Buff>,
+}
+
+impl BuffManager {
+    pub fn new() -> Self {
+        Self { buffs: Vec::new() }
+    }
+
+    /// Applies or re-applies a buff following its stacking rule.
+    /// Returns false, changing nothing, if `id` is not a registered buff or `stacks` is 0.
+    pub fn apply(&mut self, id: &str, stacks: u32, duration: u32) -> bool {
+        let Some(def) = BuffRegistry::builtin().get(id) else {
+            return false;
+        };
+        if stacks == 0 {
+            return false;
+        }
+        if let Some(existing) = self.buffs.iter_mut().find(|b| b.id == id) {
+            match def.stacking {
+                Stacking::Add => existing.stacks = def.cap(existing.stacks + stacks),
+                Stacking::Max => existing.stacks = def.cap(existing.stacks.max(stacks)),
+                Stacking::Replace => {
+                    existing.stacks = def.cap(stacks);
+                    existing.duration = duration;
+                    return true;
+                }
+            }
+            if duration > 0 && duration > existing.duration {
+                existing.duration = duration;
+            }
+        } else {
+            self.buffs.push(Buff {
+                id: id.to_string(),
+                stacks: def.cap(stacks),
+                duration,
+            });
+        }
+        true
+    }
+
+    pub fn remove(&mut self, id: &str) {
+        self.buffs.retain(|b| b.id != id);
+    }
+
+    pub fn stacks(&self, id: &str) -> u32 {
+        self.buffs
+            .iter()
+            .find(|b| b.id == id)
+            .map_or(0, |b| b.stacks)
+    }
+
+    /// Tick all buffs and remove expired ones. Returns expired ids.
+    pub fn tick(&mut self) -> Vec<String> {
+        let mut expired = Vec::new();
+        self.buffs.retain_mut(|b| match b.def() {
+            Some(def) if b.tick(def) => {
+                expired.push(b.id.clone());
+                false
+            }
+            Some(_) => true,
+            None => false,
+        });
+        expired
+    }
+
+    pub fn clear(&mut self) {
+        self.buffs.clear();
+    }
+
+    /// Active buffs with their definitions, skipping unregistered ids.
+    fn active(&self) -> impl Iterator<Item = (&Buff, &'static BuffDef)> {
+        self.buffs.iter().filter_map(|b| Some((b, b.def()?)))
+    }
+
+    /// Runs every `on_damage_taken` hook over an incoming hit.
+    pub fn incoming(&self, amount: i32) -> DamageTaken {
+        let mut hit = DamageTaken {
+            amount,
+            reflected: 0,
+        };
+        for (buff, def) in self.active() {
+            if let Some(hook) = def.hooks.on_damage_taken {
+                hook(buff.stacks, &mut hit);
+            }
+        }
+        hit.amount = hit.amount.max(0);
+        hit
+    }
+
+    /// Incoming damage after buffs.
+    pub fn damage_taken(&self, amount: i32) -> i32 {
+        self.incoming(amount).amount
+    }
+
+    /// Outgoing damage after buffs.
+    pub fn damage_dealt(&self, amount: i32) -> i32 {
+        self.active()
+            .fold(amount, |amount, (buff, def)| {
+                match def.hooks.on_damage_dealt {
+                    Some(hook) => hook(buff.stacks, amount),
+                    None => amount,
+                }
+            })
+            .max(0)
+    }
+
+    /// Healing received after buffs.
+    pub fn heal_received(&self, amount: i32) -> i32 {
+        self.active()
+            .fold(amount, |amount, (buff, def)| {
+                match def.hooks.on_heal_received {
+                    Some(hook) => hook(buff.stacks, amount),
+                    None => amount,
+                }
+            })
+            .max(0)
+    }
+
+    /// What each buff does as a round starts, in the order they were applied.
+    pub fn round_start_effects(&self) -> Vec<(String, RoundStartEffect)> {
+        self.active()
+            .filter_map(|(buff, def)| {
+                let hook = def.hooks.on_round_start?;
+                Some((buff.id.clone(), hook(buff.stacks)))
+            })
+            .collect()
+    }
+
+    /// If a buff makes the next action fizzle, uses up one of its stacks and returns its id.
+    pub fn take_skipped_action(&mut self) -> Option<String> {
+        let index = self
+            .buffs
+            .iter()
+            .position(|b| b.def().is_some_and(|def| def.hooks.skips_action))?;
+        let buff = &mut self.buffs[index];
+        buff.stacks = buff.stacks.saturating_sub(1);
+        let id = buff.id.clone();
+        if buff.stacks == 0 {
+            self.buffs.remove(index);
+        }
+        Some(id)
+    }
+
+    /// Short status text, e.g. "🔻脆弱×3(2回合) 💧滋润×1"; empty without buffs.
+    pub fn summary(&self) -> String {
+        self.active()
+            .map(|(b, def)| {
+                let mut text = format!("{}{}×{}", def.icon, def.name, b.stacks);
+                if def.expiry == Expiry::Rounds && b.duration > 0 {
+                    text.push_str(&format!("({}回合)", b.duration));
+                }
+                text
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nourish_heal_bonus() {
+        let mut mgr = BuffManager::new();
+        assert_eq!(mgr.heal_received(100), 100);
+
+        mgr.apply(NOURISH, 2, 0);
+        assert_eq!(mgr.heal_received(100), 110);
+
+        mgr.apply(NOURISH, 3, 0);
+        assert_eq!(mgr.stacks(NOURISH), 5);
+        assert_eq!(mgr.heal_received(100), 125);
+    }
+
+    #[test]
+    fn max_stacks_capped() {
+        let mut mgr = BuffManager::new();
+        mgr.apply(NOURISH, 100, 0);
+        assert_eq!(mgr.stacks(NOURISH), 20);
+    }
+
+    #[test]
+    fn fragile_damage_bonus() {
+        let mut mgr = BuffManager::new();
+        assert_eq!(mgr.damage_taken(100), 100);
+
+        mgr.apply(FRAGILE, 3, 0);
+        assert_eq!(mgr.damage_taken(100), 115);
+
+        mgr.apply(FRAGILE, 2, 0);
+        assert_eq!(mgr.stacks(FRAGILE), 5);
+        assert_eq!(mgr.damage_taken(100), 125);
+    }
+
+    #[test]
+    fn fragile_max_stacks_capped() {
+        let mut mgr = BuffManager::new();
+        mgr.apply(FRAGILE, 100, 0);
+        assert_eq!(mgr.stacks(FRAGILE), 20);
+    }
+
+    #[test]
+    fn duration_tick_expires() {
+        let mut mgr = BuffManager::new();
+        mgr.apply(NOURISH, 3, 2);
+        assert_eq!(mgr.stacks(NOURISH), 3);
+
+        let expired = mgr.tick();
+        assert!(expired.is_empty());
+        assert_eq!(mgr.stacks(NOURISH), 3);
+
+        let expired = mgr.tick();
+        assert_eq!(expired, vec![NOURISH.to_string()]);
+        assert_eq!(mgr.stacks(NOURISH), 0);
+    }
+
+    #[test]
+    fn multipliers_round_down() {
+        let mut mgr = BuffManager::new();
+        assert_eq!(mgr.damage_taken(3), 3);
+        mgr.apply(FRAGILE, 10, 0);
+        assert_eq!(mgr.damage_taken(1), 1);
+        assert_eq!(mgr.damage_taken(3), 4);
+
+        mgr.apply(NOURISH, 20, 2);
+        assert_eq!(mgr.heal_received(1), 2);
+        assert_eq!(mgr.summary(), "🔻脆弱×10 💧滋润×20(2回合)");
+    }
+
+    #[test]
+    fn unknown_ids_are_ignored() {
+        let mut mgr = BuffManager::new();
+        assert!(!mgr.apply("curse", 1, 0));
+        assert!(mgr.buffs.is_empty());
+    }
+
+    #[test]
+    fn poison_and_regeneration_decay_each_round() {
+        let mut mgr = BuffManager::new();
+        mgr.apply(POISON, 2, 0);
+        mgr.apply(REGENERATION, 1, 0);
+        assert_eq!(
+            mgr.round_start_effects(),
+            vec![
+                (POISON.to_string(), RoundStartEffect::Damage(2)),
+                (REGENERATION.to_string(), RoundStartEffect::Heal(1)),
+            ]
+        );
+        assert_eq!(mgr.tick(), vec![REGENERATION.to_string()]);
+        assert_eq!(mgr.stacks(POISON), 1);
+        assert_eq!(mgr.summary(), "☠️中毒×1");
+    }
+
+    #[test]
+    fn strength_weak_and_thorns_adjust_hits() {
+        let mut mgr = BuffManager::new();
+        mgr.apply(STRENGTH, 2, 3);
+        assert_eq!(mgr.damage_dealt(1), 3);
+        mgr.apply(WEAK, 5, 3);
+        assert_eq!(mgr.damage_dealt(1), 0, "伤害不会低于 0");
+
+        mgr.apply(THORNS, 1, 0);
+        assert_eq!(
+            mgr.incoming(2),
+            DamageTaken {
+                amount: 2,
+                reflected: 1
+            }
+        );
+    }
+
+    #[test]
+    fn stun_skips_one_action() {
+        let mut mgr = BuffManager::new();
+        assert_eq!(mgr.take_skipped_action(), None);
+        mgr.apply(STUN, 1, 2);
+        mgr.apply(STUN, 1, 1);
+        assert_eq!(mgr.stacks(STUN), 1, "眩晕不叠加");
+        assert_eq!(mgr.take_skipped_action(), Some(STUN.to_string()));
+        assert_eq!(mgr.take_skipped_action(), None);
+    }
+
+    #[test]
+    fn zero_stacks_apply_nothing() {
+        let mut mgr = BuffManager::new();
+        assert!(!mgr.apply(STUN, 0, 2));
+        assert_eq!(mgr.stacks(STUN), 0);
+        assert_eq!(mgr.take_skipped_action(), None);
+
+        mgr.apply(STUN, 1, 2);
+        assert!(!mgr.apply(STUN, 0, 5), "0 层也不能替换已有的眩晕");
+        assert_eq!(mgr.stacks(STUN), 1);
+    }
+}
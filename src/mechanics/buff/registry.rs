@@ -0,0 +1,317 @@
+use std::collections::BTreeMap;
+use std::sync::OnceLock;
+
+pub const NOURISH: &str = "nourish";
+pub const FRAGILE: &str = "fragile";
+pub const POISON: &str = "poison";
+pub const STRENGTH: &str = "strength";
+pub const WEAK: &str = "weak";
+pub const REGENERATION: &str = "regeneration";
+pub const STUN: &str = "stun";
+pub const THORNS: &str = "thorns";
+
+/// Whether a buff helps or hurts whoever carries it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BuffKind {
+    Buff,
+    Debuff,
+}
+
+/// What happens to stacks when a buff is applied to someone who already has it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stacking {
+    /// New stacks are added on top, up to `max_stacks`.
+    Add,
+    /// Keeps whichever stack count is higher.
+    Max,
+    /// The new application replaces the old one outright.
+    Replace,
+}
+
+/// How a buff runs out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Expiry {
+    /// Counts down `duration` once per round; a duration of 0 never expires.
+    /// Re-applying keeps the longer duration.
+    Rounds,
+    /// Loses one stack per round and expires at zero; `duration` is unused.
+    DecayStacks,
+}
+
+/// What a buff does to its carrier as a round starts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RoundStartEffect {
+    /// Lost HP, through shield like any other damage.
+    Damage(i32),
+    Heal(i32),
+}
+
+/// Damage about to be taken, as adjusted by the target's buffs.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DamageTaken {
+    pub amount: i32,
+    /// Sent back to the attacker.
+    pub reflected: i32,
+}
+
+/// Optional behaviour a buff adds to its carrier. Every hook receives the current stacks.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BuffHooks {
+    pub on_round_start: Option<fn(u32) -> RoundStartEffect>,
+    /// Adjusts damage the carrier is about to take.
+    pub on_damage_taken: Option<fn(u32, &mut DamageTaken)>,
+    /// Adjusts damage the carrier is about to deal.
+    pub on_damage_dealt: Option<fn(u32, i32) -> i32>,
+    /// Adjusts healing the carrier is about to receive.
+    pub on_heal_received: Option<fn(u32, i32) -> i32>,
+    /// The carrier's next action fizzles, using up one stack.
+    pub skips_action: bool,
+}
+
+/// Everything the game knows about one kind of buff.
+#[derive(Debug, Clone)]
+pub struct BuffDef {
+    pub id: &'static str,
+    pub name: &'static str,
+    pub icon: &'static str,
+    pub kind: BuffKind,
+    /// 0 = unlimited.
+    pub max_stacks: u32,
+    pub stacking: Stacking,
+    pub expiry: Expiry,
+    pub description: fn(u32) -> String,
+    pub hooks: BuffHooks,
+}
+
+impl BuffDef {
+    /// Clamps a stack count to `max_stacks`.
+    pub fn cap(&self, stacks: u32) -> u32 {
+        if self.max_stacks == 0 {
+            stacks
+        } else {
+            stacks.min(self.max_stacks)
+        }
+    }
+}
+
+/// Buff definitions keyed by id.
+#[derive(Debug, Clone, Default)]
+pub struct BuffRegistry {
+    defs: BTreeMap<&'static str, BuffDef>,
+}
+
+impl BuffRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a definition, replacing any earlier one with the same id.
+    pub fn register(&mut self, def: BuffDef) {
+        self.defs.insert(def.id, def);
+    }
+
+    pub fn get(&self, id: &str) -> Option<&BuffDef> {
+        self.defs.get(id)
+    }
+
+    /// Looks a definition up by its display name, e.g. "脆弱".
+    pub fn by_name(&self, name: &str) -> Option<&BuffDef> {
+        self.defs.values().find(|def| def.name == name)
+    }
+
+    pub fn ids(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.defs.keys().copied()
+    }
+
+    /// The buffs the game ships with, built once.
+    pub fn builtin() -> &'static BuffRegistry {
+        static BUILTIN: OnceLock<BuffRegistry> = OnceLock::new();
+        BUILTIN.get_or_init(|| {
+            let mut registry = BuffRegistry::new();
+            for def in builtin_defs() {
+                registry.register(def);
+            }
+            registry
+        })
+    }
+}
+
+/// `amount` scaled by `1 + stacks * 5%`, rounded down. Integer math keeps 100 × 115% at 115.
+fn five_percent_per_stack(stacks: u32, amount: i32) -> i32 {
+    (i64::from(amount) * (20 + i64::from(stacks))).div_euclid(20) as i32
+}
+
+fn builtin_defs() -> Vec<BuffDef> {
+    let hooks = BuffHooks::default();
+    vec![
+        BuffDef {
+            id: NOURISH,
+            name: "滋润",
+            icon: "💧",
+            kind: BuffKind::Buff,
+            max_stacks: 20,
+            stacking: Stacking::Add,
+            expiry: Expiry::Rounds,
+            description: |stacks| format!("回复效果 +{}%", stacks * 5),
+            hooks: BuffHooks {
+                on_heal_received: Some(five_percent_per_stack),
+                ..hooks
+            },
+        },
+        BuffDef {
+            id: FRAGILE,
+            name: "脆弱",
+            icon: "🔻",
+            kind: BuffKind::Debuff,
+            max_stacks: 20,
+            stacking: Stacking::Add,
+            expiry: Expiry::Rounds,
+            description: |stacks| format!("受到伤害 +{}%", stacks * 5),
+            hooks: BuffHooks {
+                on_damage_taken: Some(|stacks, hit| {
+                    hit.amount = five_percent_per_stack(stacks, hit.amount)
+                }),
+                ..hooks
+            },
+        },
+        BuffDef {
+            id: POISON,
+            name: "中毒",
+            icon: "☠️",
+            kind: BuffKind::Debuff,
+            max_stacks: 0,
+            stacking: Stacking::Add,
+            expiry: Expiry::DecayStacks,
+            description: |stacks| format!("回合开始时受到 {stacks} 点伤害，每回合减少 1 层"),
+            hooks: BuffHooks {
+                on_round_start: Some(|stacks| RoundStartEffect::Damage(stacks as i32)),
+                ..hooks
+            },
+        },
+        BuffDef {
+            id: STRENGTH,
+            name: "力量",
+            icon: "💪",
+            kind: BuffKind::Buff,
+            max_stacks: 10,
+            stacking: Stacking::Add,
+            expiry: Expiry::Rounds,
+            description: |stacks| format!("造成伤害 +{stacks}"),
+            hooks: BuffHooks {
+                on_damage_dealt: Some(|stacks, amount| amount + stacks as i32),
+                ..hooks
+            },
+        },
+        BuffDef {
+            id: WEAK,
+            name: "虚弱",
+            icon: "🥀",
+            kind: BuffKind::Debuff,
+            max_stacks: 10,
+            stacking: Stacking::Add,
+            expiry: Expiry::Rounds,
+            description: |stacks| format!("造成伤害 -{stacks}"),
+            hooks: BuffHooks {
+                on_damage_dealt: Some(|stacks, amount| (amount - stacks as i32).max(0)),
+                ..hooks
+            },
+        },
+        BuffDef {
+            id: REGENERATION,
+            name: "再生",
+            icon: "🌿",
+            kind: BuffKind::Buff,
+            max_stacks: 0,
+            stacking: Stacking::Add,
+            expiry: Expiry::DecayStacks,
+            description: |stacks| format!("回合开始时恢复 {stacks} 点生命值，每回合减少 1 层"),
+            hooks: BuffHooks {
+                on_round_start: Some(|stacks| RoundStartEffect::Heal(stacks as i32)),
+                ..hooks
+            },
+        },
+        BuffDef {
+            id: STUN,
+            name: "眩晕",
+            icon: "💫",
+            kind: BuffKind::Debuff,
+            max_stacks: 1,
+            stacking: Stacking::Max,
+            expiry: Expiry::Rounds,
+            description: |_| "下一次行动落空".to_string(),
+            hooks: BuffHooks {
+                skips_action: true,
+                ..hooks
+            },
+        },
+        BuffDef {
+            id: THORNS,
+            name: "荆棘",
+            icon: "🌵",
+            kind: BuffKind::Buff,
+            max_stacks: 10,
+            stacking: Stacking::Add,
+            expiry: Expiry::Rounds,
+            description: |stacks| format!("受到攻击时反弹 {stacks} 点伤害"),
+            hooks: BuffHooks {
+                on_damage_taken: Some(|stacks, hit| hit.reflected += stacks as i32),
+                ..hooks
+            },
+        },
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builtin_registry_has_every_buff() {
+        let registry = BuffRegistry::builtin();
+        let mut ids: Vec<_> = registry.ids().collect();
+        ids.sort_unstable();
+        assert_eq!(
+            ids,
+            [
+                FRAGILE,
+                NOURISH,
+                POISON,
+                REGENERATION,
+                STRENGTH,
+                STUN,
+                THORNS,
+                WEAK
+            ]
+        );
+        assert_eq!(registry.get(POISON).unwrap().kind, BuffKind::Debuff);
+        assert_eq!(
+            (registry.get(THORNS).unwrap().description)(2),
+            "受到攻击时反弹 2 点伤害"
+        );
+    }
+
+    #[test]
+    fn registry_accepts_new_buffs() {
+        let mut registry = BuffRegistry::new();
+        registry.register(BuffDef {
+            id: "burn",
+            name: "灼烧",
+            icon: "🔥",
+            kind: BuffKind::Debuff,
+            max_stacks: 0,
+            stacking: Stacking::Replace,
+            expiry: Expiry::Rounds,
+            description: |stacks| format!("回合开始时受到 {stacks} 点伤害"),
+            hooks: BuffHooks {
+                on_round_start: Some(|stacks| RoundStartEffect::Damage(stacks as i32)),
+                ..BuffHooks::default()
+            },
+        });
+        let burn = registry.get("burn").unwrap();
+        assert_eq!(burn.cap(999), 999);
+        assert_eq!(
+            burn.hooks.on_round_start.map(|hook| hook(3)),
+            Some(RoundStartEffect::Damage(3))
+        );
+    }
+}
@@ -1,3 +1,5 @@
+use super::buff::BuffManager;
+
 /// Trait shared by all combatants (players, enemies, etc.).
 pub trait Combatant {
     fn name(&self) -> &str;
@@ -7,13 +9,20 @@ pub trait Combatant {
     fn shield(&self) -> i32;
     fn add_shield(&mut self, amount: i32);
     fn clear_shield(&mut self);
+    fn buffs(&self) -> &BuffManager;
+    fn buffs_mut(&mut self) -> &mut BuffManager;
 
-    /// Deals damage, absorbing through shield first.
+    /// Deals damage, scaled by buffs, absorbing through shield first.
     fn take_damage(&mut self, amount: i32);
 
-    /// Heal HP, capped at max_hp. Returns actual amount healed.
+    /// Heal HP, scaled by buffs and capped at max_hp. Returns actual amount healed.
     fn heal(&mut self, amount: i32) -> i32;
 
+    /// Ids of the passive skills held, in the order they were gained. Default is none.
+    fn passives(&self) -> &[String] {
+        &[]
+    }
+
     /// Probability [0.0, 1.0] of dodging an incoming hit. Default is 0 (no dodge).
     fn dodge_chance(&self) -> f64 {
         0.0
@@ -24,29 +33,29 @@ pub trait Combatant {
     }
 
     fn display_status(&self) -> String {
+        let mut status = format!("{}: {}/{} HP", self.name(), self.hp(), self.max_hp());
         if self.shield() > 0 {
-            format!(
-                "{}: {}/{} HP (🛡️{})",
-                self.name(),
-                self.hp(),
-                self.max_hp(),
-                self.shield()
-            )
-        } else {
-            format!("{}: {}/{} HP", self.name(), self.hp(), self.max_hp())
+            status.push_str(&format!(" (🛡️{})", self.shield()));
         }
+        let buffs = self.buffs().summary();
+        if !buffs.is_empty() {
+            status.push_str(&format!(" [{buffs}]"));
+        }
+        status
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::mechanics::buff::FRAGILE;
 
     struct DummyCombatant {
         name: String,
         hp: i32,
         max_hp: i32,
         shield: i32,
+        buffs: BuffManager,
     }
 
     impl DummyCombatant {
@@ -56,6 +65,7 @@ mod tests {
                 hp,
                 max_hp,
                 shield: 0,
+                buffs: BuffManager::new(),
             }
         }
     }
@@ -82,12 +92,20 @@ mod tests {
         fn clear_shield(&mut self) {
             self.shield = 0;
         }
+        fn buffs(&self) -> &BuffManager {
+            &self.buffs
+        }
+        fn buffs_mut(&mut self) -> &mut BuffManager {
+            &mut self.buffs
+        }
         fn take_damage(&mut self, amount: i32) {
+            let amount = self.buffs.damage_taken(amount);
             let absorbed = amount.min(self.shield);
             self.shield -= absorbed;
             self.hp = (self.hp - (amount - absorbed)).max(0);
         }
         fn heal(&mut self, amount: i32) -> i32 {
+            let amount = self.buffs.heal_received(amount);
             let before = self.hp;
             self.hp = (self.hp + amount).min(self.max_hp);
             self.hp - before
@@ -148,4 +166,11 @@ mod tests {
         e.add_shield(1);
         assert!(e.display_status().contains("🛡️1"));
     }
+
+    #[test]
+    fn display_status_lists_buffs() {
+        let mut e = DummyCombatant::new("勇者", 3, 3);
+        e.buffs_mut().apply(FRAGILE, 2, 3);
+        assert_eq!(e.display_status(), "勇者: 3/3 HP [🔻脆弱×2(3回合)]");
+    }
 }
@@ -0,0 +1,153 @@
+use serde::{Deserialize, Serialize};
+
+use super::buff::{BuffKind, BuffRegistry};
+
+/// Who an effect lands on, relative to whoever plays it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EffectTarget {
+    Caster,
+    Opponent,
+}
+
+impl EffectTarget {
+    /// Buffs go on the caster and debuffs on the opponent unless stated otherwise.
+    pub fn default_for(kind: BuffKind) -> Self {
+        match kind {
+            BuffKind::Buff => EffectTarget::Caster,
+            BuffKind::Debuff => EffectTarget::Opponent,
+        }
+    }
+}
+
+/// What a card or skill does. The engine resolves every effect the same way,
+/// whoever plays it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Effect {
+    /// Attacks the opponent.
+    Damage(i32),
+    /// Attacks every opponent still standing.
+    DamageAll(i32),
+    /// Shields the caster for the rest of the round.
+    Shield(i32),
+    /// Heals the caster.
+    Heal(i32),
+    /// Shortens the cooldown of every card the caster holds.
+    ReduceAllCardCooldownMs(u64),
+    /// Draws cards into the caster's hand. Enemies have no draw pile, so only the
+    /// player draws.
+    DrawCards(u32),
+    /// Gold for the caster; enemies carry none, so only the player gains anything.
+    GainGold(i32),
+    /// Takes up to this much gold from the player, kept until the enemy is defeated.
+    /// Only enemies steal.
+    StealGold(i32),
+    /// The caster splits off a copy with its current HP that joins the battle.
+    /// Only enemies can split.
+    Split,
+    ApplyBuff {
+        target: EffectTarget,
+        /// Id in the buff registry.
+        id: String,
+        stacks: u32,
+        /// Rounds; 0 = permanent.
+        duration: u32,
+    },
+    /// Runs each sub-effect in order.
+    Composite(Vec<Effect>),
+}
+
+impl Effect {
+    /// Total damage dealt to each opponent hit, before buffs.
+    pub fn damage(&self) -> i32 {
+        match self {
+            Effect::Damage(n) | Effect::DamageAll(n) => *n,
+            Effect::Composite(effects) => effects.iter().map(Effect::damage).sum(),
+            _ => 0,
+        }
+    }
+
+    /// The first effect that is not a composite, used to summarise what is coming.
+    pub fn primary(&self) -> &Effect {
+        match self {
+            Effect::Composite(effects) => effects.first().map_or(self, Effect::primary),
+            _ => self,
+        }
+    }
+
+    /// Player-facing text, e.g. "造成 1 点伤害并对敌方施加 2 层脆弱（3 回合）".
+    pub fn describe(&self) -> String {
+        match self {
+            Effect::Damage(n) => format!("造成 {n} 点伤害"),
+            Effect::DamageAll(n) => format!("对所有敌人造成 {n} 点伤害"),
+            Effect::Shield(n) => format!("获得 {n} 点护盾，持续 1 回合"),
+            Effect::Heal(n) => format!("恢复 {n} 点生命值"),
+            Effect::ReduceAllCardCooldownMs(ms) => {
+                format!("所有卡牌冷却减少 {} 秒", ms.div_ceil(1_000))
+            }
+            Effect::DrawCards(n) => format!("抽 {n} 张牌"),
+            Effect::GainGold(n) => format!("获得 {n} 金币"),
+            Effect::StealGold(n) => format!("偷走 {n} 金币"),
+            Effect::Split => "分裂出一只分身".to_string(),
+            Effect::ApplyBuff {
+                target,
+                id,
+                stacks,
+                duration,
+            } => {
+                let name = BuffRegistry::builtin()
+                    .get(id)
+                    .map_or(id.as_str(), |def| def.name);
+                let verb = match target {
+                    EffectTarget::Caster => "获得",
+                    EffectTarget::Opponent => "对敌方施加",
+                };
+                let mut text = format!("{verb} {stacks} 层{name}");
+                if *duration > 0 {
+                    text.push_str(&format!("（{duration} 回合）"));
+                }
+                text
+            }
+            Effect::Composite(effects) => effects
+                .iter()
+                .map(Effect::describe)
+                .collect::<Vec<_>>()
+                .join("并"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mechanics::buff::FRAGILE;
+
+    fn armor_break() -> Effect {
+        Effect::Composite(vec![
+            Effect::Damage(1),
+            Effect::ApplyBuff {
+                target: EffectTarget::Opponent,
+                id: FRAGILE.to_string(),
+                stacks: 2,
+                duration: 3,
+            },
+        ])
+    }
+
+    #[test]
+    fn composite_describes_every_part() {
+        assert_eq!(
+            armor_break().describe(),
+            "造成 1 点伤害并对敌方施加 2 层脆弱（3 回合）"
+        );
+        assert_eq!(Effect::Shield(1).describe(), "获得 1 点护盾，持续 1 回合");
+    }
+
+    #[test]
+    fn damage_and_primary_look_inside_composites() {
+        let effect = Effect::Composite(vec![armor_break(), Effect::Damage(2)]);
+        assert_eq!(effect.damage(), 3);
+        assert_eq!(effect.primary(), &Effect::Damage(1));
+        assert_eq!(Effect::Heal(1).damage(), 0);
+        assert_eq!(Effect::DamageAll(2).damage(), 2);
+    }
+}
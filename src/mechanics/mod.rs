@@ -1,5 +1,7 @@
-#[allow(dead_code)]
 pub mod buff;
 pub mod combat;
+pub mod effect;
+pub mod passive;
+pub mod pick;
 #[allow(dead_code)]
 pub mod turn;
@@ -0,0 +1,268 @@
+use std::collections::BTreeMap;
+use std::sync::OnceLock;
+
+use super::effect::Effect;
+
+pub const PREPARED: &str = "prepared";
+pub const UNDEAD_SHIELD: &str = "undead_shield";
+pub const DODGE_MASTER: &str = "dodge_master";
+pub const SWIFT: &str = "swift";
+pub const DRAGON_SCALE: &str = "dragon_scale";
+pub const COUNTER: &str = "counter";
+pub const BLOODTHIRST: &str = "bloodthirst";
+pub const SPLIT: &str = "split";
+pub const PICKPOCKET: &str = "pickpocket";
+
+/// What a passive hook sees when it fires.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct PassiveContext {
+    /// The current round of the battle, starting at 1.
+    pub round: u32,
+    /// HP the holder just lost for `on_hit`, or the opponent lost for `on_attack`; 0 otherwise.
+    pub damage: i32,
+    /// The holder's HP after whatever triggered the hook.
+    pub hp: i32,
+    pub max_hp: i32,
+    /// A uniform draw in `[0, 1)` from the run's RNG, for passives that trigger by chance.
+    pub roll: f64,
+}
+
+/// Returns the effect to resolve, as if the holder had cast it, or `None` to do nothing.
+pub type PassiveHook = fn(&PassiveContext) -> Option<Effect>;
+
+/// Moments a passive can react to, for whichever side holds it.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PassiveHooks {
+    pub on_battle_start: Option<PassiveHook>,
+    pub on_round_start: Option<PassiveHook>,
+    /// After an attack lands on the holder.
+    pub on_hit: Option<PassiveHook>,
+    /// After an attack by the holder lands on its opponent.
+    pub on_attack: Option<PassiveHook>,
+    /// After the holder defeats its opponent.
+    pub on_kill: Option<PassiveHook>,
+    /// After the battle is won. Only the player wins battles; a defeat ends the run.
+    pub on_victory: Option<PassiveHook>,
+}
+
+/// Everything the game knows about one passive skill.
+#[derive(Debug, Clone)]
+pub struct PassiveDef {
+    pub id: &'static str,
+    /// Matches the skill name in codex entries, e.g. "亡灵护盾".
+    pub name: &'static str,
+    pub icon: &'static str,
+    pub description: &'static str,
+    /// Added to the holder's chance of dodging each hit.
+    pub dodge_chance: f64,
+    pub hooks: PassiveHooks,
+}
+
+/// Passive definitions keyed by id.
+#[derive(Debug, Clone, Default)]
+pub struct PassiveRegistry {
+    defs: BTreeMap<&'static str, PassiveDef>,
+}
+
+impl PassiveRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a definition, replacing any earlier one with the same id.
+    pub fn register(&mut self, def: PassiveDef) {
+        self.defs.insert(def.id, def);
+    }
+
+    pub fn get(&self, id: &str) -> Option<&PassiveDef> {
+        self.defs.get(id)
+    }
+
+    /// Looks a definition up by its display name, e.g. "龙鳞".
+    pub fn by_name(&self, name: &str) -> Option<&PassiveDef> {
+        self.defs.values().find(|def| def.name == name)
+    }
+
+    pub fn ids(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.defs.keys().copied()
+    }
+
+    /// Definitions of `ids` in order, skipping ones not registered.
+    pub fn resolve<'a>(&'a self, ids: &'a [String]) -> impl Iterator<Item = &'a PassiveDef> + 'a {
+        ids.iter().filter_map(|id| self.get(id))
+    }
+
+    /// The passives the game ships with, built once.
+    pub fn builtin() -> &'static PassiveRegistry {
+        static BUILTIN: OnceLock<PassiveRegistry> = OnceLock::new();
+        BUILTIN.get_or_init(|| {
+            let mut registry = PassiveRegistry::new();
+            for def in builtin_defs() {
+                registry.register(def);
+            }
+            registry
+        })
+    }
+}
+
+/// Total dodge chance the passives `ids` grant.
+pub fn dodge_chance(ids: &[String]) -> f64 {
+    PassiveRegistry::builtin()
+        .resolve(ids)
+        .map(|def| def.dodge_chance)
+        .sum()
+}
+
+/// A passive with no dodge or hooks, to fill in with struct update syntax.
+fn passive(
+    id: &'static str,
+    name: &'static str,
+    icon: &'static str,
+    description: &'static str,
+) -> PassiveDef {
+    PassiveDef {
+        id,
+        name,
+        icon,
+        description,
+        dodge_chance: 0.0,
+        hooks: PassiveHooks::default(),
+    }
+}
+
+fn builtin_defs() -> Vec<PassiveDef> {
+    let hooks = PassiveHooks::default();
+    vec![
+        PassiveDef {
+            hooks: PassiveHooks {
+                on_victory: Some(|_| Some(Effect::GainGold(1))),
+                ..hooks
+            },
+            ..passive(PREPARED, "预备", "🎒", "胜利后额外获得 1 金币")
+        },
+        PassiveDef {
+            hooks: PassiveHooks {
+                on_battle_start: Some(|_| Some(Effect::Shield(1))),
+                ..hooks
+            },
+            ..passive(
+                UNDEAD_SHIELD,
+                "亡灵护盾",
+                "💀",
+                "每场战斗开始时获得 1 点护盾",
+            )
+        },
+        PassiveDef {
+            dodge_chance: 0.1,
+            ..passive(
+                DODGE_MASTER,
+                "躲闪大师",
+                "🌀",
+                "每次受击有 10% 概率完全闪避",
+            )
+        },
+        PassiveDef {
+            dodge_chance: 0.15,
+            ..passive(SWIFT, "迅捷", "💨", "每次受击有 15% 概率完全闪避")
+        },
+        PassiveDef {
+            dodge_chance: 0.05,
+            ..passive(DRAGON_SCALE, "龙鳞", "🐉", "每次受击有 5% 概率完全闪避")
+        },
+        PassiveDef {
+            hooks: PassiveHooks {
+                on_hit: Some(|ctx| (ctx.roll < 0.3).then_some(Effect::Damage(1))),
+                ..hooks
+            },
+            ..passive(
+                COUNTER,
+                "反击",
+                "🤺",
+                "受到伤害时 30% 概率反击，造成 1 点伤害",
+            )
+        },
+        PassiveDef {
+            hooks: PassiveHooks {
+                on_kill: Some(|_| Some(Effect::Heal(1))),
+                ..hooks
+            },
+            ..passive(BLOODTHIRST, "嗜血", "🩸", "击败敌人时恢复 1 点生命值")
+        },
+        PassiveDef {
+            hooks: PassiveHooks {
+                on_hit: Some(|ctx| {
+                    let crossed_half =
+                        ctx.hp * 2 < ctx.max_hp && (ctx.hp + ctx.damage) * 2 >= ctx.max_hp;
+                    (ctx.hp > 0 && crossed_half).then_some(Effect::Split)
+                }),
+                ..hooks
+            },
+            ..passive(
+                SPLIT,
+                "分裂",
+                "🫧",
+                "生命值跌破一半时分裂出一只同等生命值的分身",
+            )
+        },
+        PassiveDef {
+            hooks: PassiveHooks {
+                on_attack: Some(|_| Some(Effect::StealGold(1))),
+                ..hooks
+            },
+            ..passive(
+                PICKPOCKET,
+                "顺手牵羊",
+                "🫳",
+                "攻击命中时偷走 1 金币，被击败后归还",
+            )
+        },
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builtin_passives_are_found_by_codex_name() {
+        let registry = PassiveRegistry::builtin();
+        assert_eq!(registry.by_name("亡灵护盾").unwrap().id, UNDEAD_SHIELD);
+        assert_eq!(registry.by_name("龙鳞").unwrap().id, DRAGON_SCALE);
+        assert!(registry.by_name("石肤").is_none());
+
+        let on_battle_start = registry.get(UNDEAD_SHIELD).unwrap().hooks.on_battle_start;
+        assert_eq!(
+            on_battle_start.and_then(|hook| hook(&PassiveContext::default())),
+            Some(Effect::Shield(1))
+        );
+    }
+
+    #[test]
+    fn split_fires_only_when_crossing_half_hp() {
+        let on_hit = PassiveRegistry::builtin()
+            .get(SPLIT)
+            .unwrap()
+            .hooks
+            .on_hit
+            .unwrap();
+        let hit = |hp, damage| {
+            on_hit(&PassiveContext {
+                damage,
+                hp,
+                max_hp: 6,
+                ..PassiveContext::default()
+            })
+        };
+        assert_eq!(hit(2, 2), Some(Effect::Split));
+        assert_eq!(hit(4, 2), None, "还在一半以上");
+        assert_eq!(hit(1, 1), None, "早已低于一半");
+        assert_eq!(hit(0, 4), None, "被一击打倒");
+    }
+
+    #[test]
+    fn dodge_adds_up_and_skips_unknown_ids() {
+        let ids = [DODGE_MASTER, SWIFT, "no_such_passive"].map(str::to_string);
+        assert!((dodge_chance(&ids) - 0.25).abs() < 1e-9);
+        assert_eq!(PassiveRegistry::builtin().resolve(&ids).count(), 2);
+    }
+}
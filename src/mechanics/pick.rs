@@ -0,0 +1,70 @@
+//! Weighted draws shared by the card and relic reward pools.
+
+use rand::distributions::{Distribution, WeightedIndex};
+use rand::Rng;
+
+/// Draws up to `count` distinct entries from `pool`, each draw weighted by `weight`.
+///
+/// Entries weighing 0 are never drawn, not even to meet `min_rank`: when nothing drawn
+/// reaches it, the first pick is swapped for a random remaining entry that does, if any.
+pub fn pick_distinct<T, K: Ord>(
+    mut pool: Vec<T>,
+    count: usize,
+    weight: impl Fn(&T) -> u32,
+    rank: impl Fn(&T) -> K,
+    min_rank: Option<K>,
+    rng: &mut impl Rng,
+) -> Vec<T> {
+    pool.retain(|entry| weight(entry) > 0);
+    let mut picked = Vec::new();
+    while picked.len() < count {
+        let Ok(weights) = WeightedIndex::new(pool.iter().map(&weight)) else {
+            break;
+        };
+        picked.push(pool.remove(weights.sample(rng)));
+    }
+
+    if let Some(min_rank) = min_rank {
+        if !picked.is_empty() && picked.iter().all(|entry| rank(entry) < min_rank) {
+            let better: Vec<usize> = (0..pool.len())
+                .filter(|&i| rank(&pool[i]) >= min_rank)
+                .collect();
+            if !better.is_empty() {
+                picked[0] = pool.swap_remove(better[rng.gen_range(0..better.len())]);
+            }
+        }
+    }
+    picked
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn draws_distinct_entries_and_skips_weightless_ones() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let picked = pick_distinct(vec![0, 1, 2, 3], 4, |&n| n, |&n| n, None, &mut rng);
+        let mut sorted = picked.clone();
+        sorted.sort();
+        assert_eq!(sorted, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn swaps_in_a_ranked_entry_when_none_was_drawn() {
+        for seed in 0..20 {
+            let mut rng = StdRng::seed_from_u64(seed);
+            // 10 outranks everything but can never be drawn.
+            let weight = |&n: &u32| match n {
+                10 => 0,
+                9 => 1,
+                _ => 1000,
+            };
+            let picked = pick_distinct(vec![1, 2, 3, 9, 10], 2, weight, |&n| n, Some(5), &mut rng);
+            assert_eq!(picked.len(), 2);
+            assert!(picked.contains(&9), "{seed}: {picked:?}");
+        }
+    }
+}
@@ -0,0 +1,476 @@
+use super::{RelicDef, RelicHooks, RelicModifiers, RelicTier};
+use crate::mechanics::effect::Effect;
+
+const NONE: RelicModifiers = RelicModifiers::NONE;
+
+/// A relic with no modifiers or hooks, to fill in with struct update syntax.
+fn relic(
+    id: &'static str,
+    name: &'static str,
+    icon: &'static str,
+    tier: RelicTier,
+    description: &'static str,
+) -> RelicDef {
+    RelicDef {
+        id,
+        name,
+        icon,
+        tier,
+        description,
+        modifiers: NONE,
+        hooks: RelicHooks::default(),
+    }
+}
+
+pub(super) fn builtin_defs() -> Vec<RelicDef> {
+    use RelicTier::{Common, Legendary, Rare, Uncommon};
+    let hooks = RelicHooks::default();
+    vec![
+        // ── Common ──
+        RelicDef {
+            hooks: RelicHooks {
+                on_round_start: Some(|_| Some(Effect::Shield(1))),
+                ..hooks
+            },
+            ..relic(
+                "iron_shield",
+                "铁皮盾",
+                "🛡️",
+                Common,
+                "每回合开始获得 1 点护盾",
+            )
+        },
+        RelicDef {
+            modifiers: RelicModifiers {
+                bonus_max_hp: 1,
+                ..NONE
+            },
+            ..relic("health_crystal", "生命水晶", "💎", Common, "最大生命值 +1")
+        },
+        RelicDef {
+            modifiers: RelicModifiers {
+                victory_bonus_gold: 1,
+                ..NONE
+            },
+            ..relic(
+                "copper_purse",
+                "铜币袋",
+                "👛",
+                Common,
+                "每场胜利额外 +1 金币",
+            )
+        },
+        RelicDef {
+            modifiers: RelicModifiers {
+                bonus_damage: 1,
+                ..NONE
+            },
+            ..relic("whetstone", "磨刀石", "🪨", Common, "攻击伤害 +1")
+        },
+        RelicDef {
+            modifiers: RelicModifiers {
+                bonus_shield: 1,
+                ..NONE
+            },
+            ..relic("leather_armor", "皮甲", "🧥", Common, "护盾效果 +1")
+        },
+        RelicDef {
+            modifiers: RelicModifiers {
+                bonus_heal: 1,
+                ..NONE
+            },
+            ..relic("herb_pouch", "草药包", "🌿", Common, "治疗效果 +1")
+        },
+        RelicDef {
+            modifiers: RelicModifiers {
+                round_time_bonus_ms: 1_000,
+                ..NONE
+            },
+            ..relic("swift_boots", "疾风靴", "👟", Common, "回合时间 +1 秒")
+        },
+        RelicDef {
+            modifiers: RelicModifiers {
+                shop_discount: 1,
+                ..NONE
+            },
+            ..relic(
+                "old_compass",
+                "旧指南针",
+                "🧭",
+                Common,
+                "商店物品价格 -1 金币",
+            )
+        },
+        RelicDef {
+            modifiers: RelicModifiers {
+                dodge_chance: 0.05,
+                ..NONE
+            },
+            ..relic("lucky_coin", "幸运币", "🪙", Common, "5% 概率闪避攻击")
+        },
+        RelicDef {
+            modifiers: RelicModifiers {
+                cooldown_reduction_ms: 1_000,
+                ..NONE
+            },
+            ..relic("cracked_orb", "碎裂宝珠", "🔮", Common, "技能冷却减少 1 秒")
+        },
+        RelicDef {
+            hooks: RelicHooks {
+                on_battle_start: Some(|_| Some(Effect::Shield(1))),
+                ..hooks
+            },
+            ..relic(
+                "tiny_bell",
+                "小铃铛",
+                "🔔",
+                Common,
+                "战斗开始时获得 1 点护盾",
+            )
+        },
+        RelicDef {
+            hooks: RelicHooks {
+                on_battle_start: Some(|_| Some(Effect::Damage(1))),
+                ..hooks
+            },
+            ..relic(
+                "rusty_dagger",
+                "生锈匕首",
+                "🗡️",
+                Common,
+                "战斗开始时对敌人造成 1 点伤害",
+            )
+        },
+        RelicDef {
+            hooks: RelicHooks {
+                on_round_end: Some(|ctx| (ctx.round % 3 == 0).then_some(Effect::Heal(1))),
+                ..hooks
+            },
+            ..relic(
+                "wooden_idol",
+                "木制神像",
+                "🪆",
+                Common,
+                "每 3 回合自动恢复 1 HP",
+            )
+        },
+        RelicDef {
+            modifiers: RelicModifiers {
+                energy_per_round: 1,
+                ..NONE
+            },
+            ..relic(
+                "energy_pebble",
+                "能量小石",
+                "🪨",
+                Common,
+                "每回合额外恢复 1 点能量",
+            )
+        },
+        RelicDef {
+            hooks: RelicHooks {
+                on_take_damage: Some(|ctx| (ctx.roll < 0.2).then_some(Effect::Damage(1))),
+                ..hooks
+            },
+            ..relic(
+                "rusty_nail",
+                "生锈钉子",
+                "📌",
+                Common,
+                "受击时 20% 概率对敌人造成 1 伤害",
+            )
+        },
+        RelicDef {
+            hooks: RelicHooks {
+                on_battle_start: Some(|_| Some(Effect::GainGold(1))),
+                ..hooks
+            },
+            ..relic(
+                "penny_pouch",
+                "零钱袋",
+                "💰",
+                Common,
+                "战斗开始时获得 1 金币",
+            )
+        },
+        RelicDef {
+            hooks: RelicHooks {
+                on_enemy_kill: Some(|ctx| (ctx.roll < 0.3).then_some(Effect::GainGold(1))),
+                ..hooks
+            },
+            ..relic(
+                "tip_jar",
+                "小费罐",
+                "🫙",
+                Common,
+                "击杀敌人时 30% 概率 +1 金币",
+            )
+        },
+        // ── Uncommon ──
+        RelicDef {
+            hooks: RelicHooks {
+                on_player_attack: Some(|_| Some(Effect::Heal(1))),
+                ..hooks
+            },
+            ..relic(
+                "vampiric_fang",
+                "吸血尖牙",
+                "🦷",
+                Uncommon,
+                "每次攻击恢复 1 HP",
+            )
+        },
+        RelicDef {
+            modifiers: RelicModifiers {
+                bonus_damage: 2,
+                ..NONE
+            },
+            ..relic("thunder_stone", "雷电石", "⚡", Uncommon, "攻击伤害 +2")
+        },
+        RelicDef {
+            modifiers: RelicModifiers {
+                bonus_max_energy: 1,
+                ..NONE
+            },
+            ..relic("mana_gem", "法力宝石", "💠", Uncommon, "最大能量 +1")
+        },
+        RelicDef {
+            hooks: RelicHooks {
+                on_round_end: Some(|_| Some(Effect::Heal(1))),
+                ..hooks
+            },
+            ..relic(
+                "healing_totem",
+                "治愈图腾",
+                "🗿",
+                Uncommon,
+                "每回合结束时恢复 1 HP",
+            )
+        },
+        RelicDef {
+            modifiers: RelicModifiers {
+                dodge_chance: 0.15,
+                ..NONE
+            },
+            ..relic(
+                "shadow_cloak",
+                "暗影斗篷",
+                "🌑",
+                Uncommon,
+                "15% 概率闪避攻击",
+            )
+        },
+        RelicDef {
+            modifiers: RelicModifiers {
+                victory_bonus_gold: 2,
+                ..NONE
+            },
+            ..relic(
+                "gold_magnet",
+                "金币磁铁",
+                "🧲",
+                Uncommon,
+                "每场胜利额外 +2 金币",
+            )
+        },
+        RelicDef {
+            modifiers: RelicModifiers {
+                bonus_shield: 2,
+                ..NONE
+            },
+            ..relic("frozen_heart", "冰冻之心", "❄️", Uncommon, "护盾效果 +2")
+        },
+        RelicDef {
+            hooks: RelicHooks {
+                on_battle_start: Some(|_| Some(Effect::Shield(3))),
+                ..hooks
+            },
+            ..relic(
+                "phoenix_feather",
+                "凤凰羽毛",
+                "🪶",
+                Uncommon,
+                "战斗开始时获得 3 点护盾",
+            )
+        },
+        RelicDef {
+            modifiers: RelicModifiers {
+                cooldown_reduction_ms: 1_000,
+                ..NONE
+            },
+            ..relic(
+                "quick_draw",
+                "快速拔刀",
+                "🤠",
+                Uncommon,
+                "卡牌冷却减少 1 秒",
+            )
+        },
+        RelicDef {
+            modifiers: RelicModifiers {
+                bonus_max_hp: 3,
+                ..NONE
+            },
+            ..relic("life_spring", "生命之泉", "⛲", Uncommon, "最大生命值 +3")
+        },
+        RelicDef {
+            hooks: RelicHooks {
+                on_take_damage: Some(|ctx| (ctx.damage > 0).then_some(Effect::Damage(1))),
+                ..hooks
+            },
+            ..relic(
+                "thorn_armor",
+                "荆棘甲",
+                "🌵",
+                Uncommon,
+                "受到攻击时，对敌人造成 1 点伤害",
+            )
+        },
+        RelicDef {
+            modifiers: RelicModifiers {
+                round_time_bonus_ms: 2_000,
+                ..NONE
+            },
+            ..relic("battle_drum", "战鼓", "🥁", Uncommon, "回合时间 +2 秒")
+        },
+        RelicDef {
+            modifiers: RelicModifiers {
+                shop_discount: 2,
+                ..NONE
+            },
+            ..relic(
+                "merchant_badge",
+                "商人徽章",
+                "📛",
+                Uncommon,
+                "商店物品价格 -2 金币",
+            )
+        },
+        // ── Rare ──
+        RelicDef {
+            modifiers: RelicModifiers {
+                bonus_damage: 3,
+                ..NONE
+            },
+            ..relic("storm_blade", "风暴之刃", "🌪️", Rare, "攻击伤害 +3")
+        },
+        RelicDef {
+            modifiers: RelicModifiers {
+                bonus_max_energy: 2,
+                ..NONE
+            },
+            ..relic("time_crystal", "时间水晶", "⏳", Rare, "最大能量 +2")
+        },
+        RelicDef {
+            hooks: RelicHooks {
+                on_round_end: Some(|_| Some(Effect::Heal(2))),
+                ..hooks
+            },
+            ..relic("angel_wings", "天使之翼", "👼", Rare, "每回合恢复 2 HP")
+        },
+        RelicDef {
+            modifiers: RelicModifiers {
+                victory_bonus_gold: 5,
+                ..NONE
+            },
+            ..relic(
+                "golden_crown",
+                "黄金王冠",
+                "👑",
+                Rare,
+                "每场胜利额外 +5 金币",
+            )
+        },
+        RelicDef {
+            modifiers: RelicModifiers {
+                dodge_chance: 0.25,
+                ..NONE
+            },
+            ..relic("void_cloak", "虚空斗篷", "🌌", Rare, "25% 概率闪避攻击")
+        },
+        RelicDef {
+            modifiers: RelicModifiers {
+                cooldown_reduction_ms: 2_000,
+                ..NONE
+            },
+            ..relic(
+                "arcane_focus",
+                "奥术聚焦",
+                "🔯",
+                Rare,
+                "技能和卡牌冷却减少 2 秒",
+            )
+        },
+        RelicDef {
+            hooks: RelicHooks {
+                on_round_end: Some(|ctx| (ctx.round % 3 == 0).then_some(Effect::Damage(5))),
+                ..hooks
+            },
+            ..relic("earthquake", "地震", "🌋", Rare, "每 3 回合对敌造成 5 伤害")
+        },
+        // ── Legendary ──
+        RelicDef {
+            modifiers: RelicModifiers {
+                bonus_max_hp: 5,
+                bonus_damage: 2,
+                ..NONE
+            },
+            hooks: RelicHooks {
+                on_round_end: Some(|_| Some(Effect::Heal(1))),
+                ..hooks
+            },
+            ..relic(
+                "dragon_heart",
+                "龙之心",
+                "❤️‍🔥",
+                Legendary,
+                "最大 HP +5，攻击 +2，每回合恢复 1 HP",
+            )
+        },
+        RelicDef {
+            modifiers: RelicModifiers {
+                bonus_max_energy: 3,
+                energy_per_round: 2,
+                ..NONE
+            },
+            ..relic(
+                "infinity_gem",
+                "无限宝石",
+                "💎",
+                Legendary,
+                "最大能量 +3，每回合额外 +2 能量",
+            )
+        },
+        RelicDef {
+            modifiers: RelicModifiers {
+                round_time_bonus_ms: 5_000,
+                cooldown_reduction_ms: 3_000,
+                ..NONE
+            },
+            ..relic(
+                "time_stop",
+                "时间停止",
+                "⏰",
+                Legendary,
+                "回合时间 +5 秒，冷却 -3 秒",
+            )
+        },
+        RelicDef {
+            modifiers: RelicModifiers {
+                victory_bonus_gold: 10,
+                ..NONE
+            },
+            hooks: RelicHooks {
+                on_player_attack: Some(|_| Some(Effect::GainGold(2))),
+                ..hooks
+            },
+            ..relic(
+                "philosopher_stone",
+                "贤者之石",
+                "⚗️",
+                Legendary,
+                "每次攻击获得 2 金币，胜利 +10 金币",
+            )
+        },
+    ]
+}
@@ -0,0 +1,272 @@
+//! Relics: permanent items the player collects during a run, ported from the web
+//! version's `docs/js/game/relics.js`.
+mod builtin;
+
+use std::collections::BTreeMap;
+use std::sync::OnceLock;
+
+use rand::Rng;
+
+use crate::mechanics::effect::Effect;
+use crate::mechanics::pick::pick_distinct;
+
+/// How rare a relic is; rarer tiers show up less often in rewards.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum RelicTier {
+    Common,
+    Uncommon,
+    Rare,
+    Legendary,
+}
+
+impl RelicTier {
+    pub fn label(self) -> &'static str {
+        match self {
+            RelicTier::Common => "普通",
+            RelicTier::Uncommon => "稀有",
+            RelicTier::Rare => "史诗",
+            RelicTier::Legendary => "传说",
+        }
+    }
+
+    /// Relative chance of being drawn for a reward.
+    pub fn weight(self) -> u32 {
+        match self {
+            RelicTier::Common => 50,
+            RelicTier::Uncommon => 30,
+            RelicTier::Rare => 15,
+            RelicTier::Legendary => 5,
+        }
+    }
+
+    /// Shop price before stage markup and discounts.
+    pub fn price(self) -> i32 {
+        match self {
+            RelicTier::Common => 6,
+            RelicTier::Uncommon => 8,
+            RelicTier::Rare => 10,
+            RelicTier::Legendary => 14,
+        }
+    }
+}
+
+/// Passive numbers a relic adds while held. The engine sums them over every relic.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct RelicModifiers {
+    /// Added to every damage effect the player causes.
+    pub bonus_damage: i32,
+    pub bonus_shield: i32,
+    pub bonus_heal: i32,
+    /// Applied once, when the relic is picked up.
+    pub bonus_max_hp: i32,
+    /// Applied once, when the relic is picked up.
+    pub bonus_max_energy: i32,
+    /// Energy on top of the round-start refill.
+    pub energy_per_round: i32,
+    /// Taken off every card and skill cooldown as it starts.
+    pub cooldown_reduction_ms: u64,
+    pub victory_bonus_gold: i32,
+    /// Taken off every shop price.
+    pub shop_discount: i32,
+    /// Added to the length of a round; may be negative.
+    pub round_time_bonus_ms: i64,
+    pub dodge_chance: f64,
+}
+
+impl RelicModifiers {
+    pub const NONE: RelicModifiers = RelicModifiers {
+        bonus_damage: 0,
+        bonus_shield: 0,
+        bonus_heal: 0,
+        bonus_max_hp: 0,
+        bonus_max_energy: 0,
+        energy_per_round: 0,
+        cooldown_reduction_ms: 0,
+        victory_bonus_gold: 0,
+        shop_discount: 0,
+        round_time_bonus_ms: 0,
+        dodge_chance: 0.0,
+    };
+
+    /// Adds `other` on top of these modifiers.
+    pub fn stack(&mut self, other: &RelicModifiers) {
+        self.bonus_damage += other.bonus_damage;
+        self.bonus_shield += other.bonus_shield;
+        self.bonus_heal += other.bonus_heal;
+        self.bonus_max_hp += other.bonus_max_hp;
+        self.bonus_max_energy += other.bonus_max_energy;
+        self.energy_per_round += other.energy_per_round;
+        self.cooldown_reduction_ms += other.cooldown_reduction_ms;
+        self.victory_bonus_gold += other.victory_bonus_gold;
+        self.shop_discount += other.shop_discount;
+        self.round_time_bonus_ms += other.round_time_bonus_ms;
+        self.dodge_chance += other.dodge_chance;
+    }
+}
+
+/// What a relic hook sees when it fires.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct RelicContext {
+    /// The current round of the battle, starting at 1.
+    pub round: u32,
+    /// Damage dealt for `on_player_attack`, damage taken for `on_take_damage`; 0 otherwise.
+    pub damage: i32,
+    /// A uniform draw in `[0, 1)` from the run's RNG, for relics that trigger by chance.
+    pub roll: f64,
+}
+
+/// Returns the effect to resolve, as if the player had cast it, or `None` to do nothing.
+pub type RelicHook = fn(&RelicContext) -> Option<Effect>;
+
+/// Moments a relic can react to. All of them fire for the player's relics only.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RelicHooks {
+    pub on_battle_start: Option<RelicHook>,
+    pub on_round_start: Option<RelicHook>,
+    /// Before shields are cleared.
+    pub on_round_end: Option<RelicHook>,
+    /// After a card or skill that deals damage.
+    pub on_player_attack: Option<RelicHook>,
+    /// After an enemy attack lands on the player.
+    pub on_take_damage: Option<RelicHook>,
+    pub on_enemy_kill: Option<RelicHook>,
+}
+
+/// Everything the game knows about one relic.
+#[derive(Debug, Clone)]
+pub struct RelicDef {
+    pub id: &'static str,
+    pub name: &'static str,
+    pub icon: &'static str,
+    pub tier: RelicTier,
+    pub description: &'static str,
+    pub modifiers: RelicModifiers,
+    pub hooks: RelicHooks,
+}
+
+/// Relic definitions keyed by id.
+#[derive(Debug, Clone, Default)]
+pub struct RelicRegistry {
+    defs: BTreeMap<&'static str, RelicDef>,
+}
+
+impl RelicRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a definition, replacing any earlier one with the same id.
+    pub fn register(&mut self, def: RelicDef) {
+        self.defs.insert(def.id, def);
+    }
+
+    pub fn get(&self, id: &str) -> Option<&RelicDef> {
+        self.defs.get(id)
+    }
+
+    pub fn ids(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.defs.keys().copied()
+    }
+
+    /// Draws up to `count` different relics the player does not own, weighted by tier.
+    /// With `min_tier`, at least one of them is of that tier or better when possible.
+    pub fn pick_reward(
+        &self,
+        count: usize,
+        owned: &[String],
+        min_tier: Option<RelicTier>,
+        rng: &mut impl Rng,
+    ) -> Vec<&RelicDef> {
+        let pool: Vec<&RelicDef> = self
+            .defs
+            .values()
+            .filter(|def| !owned.iter().any(|id| id == def.id))
+            .collect();
+        pick_distinct(
+            pool,
+            count,
+            |def| def.tier.weight(),
+            |def| def.tier,
+            min_tier,
+            rng,
+        )
+    }
+
+    /// The relics the game ships with, built once.
+    pub fn builtin() -> &'static RelicRegistry {
+        static BUILTIN: OnceLock<RelicRegistry> = OnceLock::new();
+        BUILTIN.get_or_init(|| {
+            let mut registry = RelicRegistry::new();
+            for def in builtin::builtin_defs() {
+                registry.register(def);
+            }
+            registry
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn builtin_relics_cover_every_tier_and_hook() {
+        let registry = RelicRegistry::builtin();
+        let defs: Vec<_> = registry.ids().filter_map(|id| registry.get(id)).collect();
+        for tier in [
+            RelicTier::Common,
+            RelicTier::Uncommon,
+            RelicTier::Rare,
+            RelicTier::Legendary,
+        ] {
+            assert!(defs.iter().any(|def| def.tier == tier), "{}", tier.label());
+        }
+        assert!(defs.iter().any(|d| d.hooks.on_battle_start.is_some()));
+        assert!(defs.iter().any(|d| d.hooks.on_round_start.is_some()));
+        assert!(defs.iter().any(|d| d.hooks.on_round_end.is_some()));
+        assert!(defs.iter().any(|d| d.hooks.on_player_attack.is_some()));
+        assert!(defs.iter().any(|d| d.hooks.on_take_damage.is_some()));
+        assert!(defs.iter().any(|d| d.hooks.on_enemy_kill.is_some()));
+
+        let idol = registry.get("wooden_idol").unwrap();
+        let on_round_end = idol.hooks.on_round_end.unwrap();
+        let ctx = |round| RelicContext {
+            round,
+            ..RelicContext::default()
+        };
+        assert_eq!(on_round_end(&ctx(2)), None);
+        assert_eq!(on_round_end(&ctx(3)), Some(Effect::Heal(1)));
+    }
+
+    #[test]
+    fn modifiers_stack() {
+        let registry = RelicRegistry::builtin();
+        let mut total = RelicModifiers::NONE;
+        for id in ["whetstone", "thunder_stone", "lucky_coin"] {
+            total.stack(&registry.get(id).unwrap().modifiers);
+        }
+        assert_eq!(total.bonus_damage, 3);
+        assert!((total.dodge_chance - 0.05).abs() < 1e-9);
+    }
+
+    #[test]
+    fn rewards_skip_owned_relics_and_honour_min_tier() {
+        let registry = RelicRegistry::builtin();
+        let owned = vec!["whetstone".to_string()];
+        let mut rng = StdRng::seed_from_u64(3);
+        for _ in 0..50 {
+            let picked = registry.pick_reward(3, &owned, Some(RelicTier::Rare), &mut rng);
+            assert_eq!(picked.len(), 3);
+            assert!(picked.iter().all(|def| def.id != "whetstone"));
+            assert!(picked.iter().any(|def| def.tier >= RelicTier::Rare));
+            assert!(picked[0].id != picked[1].id && picked[1].id != picked[2].id);
+        }
+
+        let everything: Vec<String> = registry.ids().map(str::to_string).collect();
+        assert!(registry
+            .pick_reward(3, &everything, None, &mut rng)
+            .is_empty());
+    }
+}
@@ -3,17 +3,29 @@ pub mod fast_cycle;
 pub mod vampiric_touch;
 pub mod war_cry;
 
-/// The effect a skill produces when activated.
-#[derive(Debug, Clone)]
-pub enum SkillEffect {
-    Heal(i32),
-    ReduceAllCardCooldownMs(u64),
-    DamageAndHeal { damage: i32, heal: i32 },
-    GainShield(i32),
+use serde::{Deserialize, Serialize};
+
+use crate::mechanics::effect::Effect;
+
+/// Skills a run can pick up along the way, by id; the starting ones are not among them.
+pub const LEARNABLE_SKILLS: [&str; 2] = ["vampiric_touch", "war_cry"];
+
+/// Builds the skill with this id (its module name), or `None` for an unknown id.
+pub fn create(id: &str) -> Option<Skill> {
+    match id {
+        "emergency_heal" => Some(emergency_heal::create_emergency_heal()),
+        "fast_cycle" => Some(fast_cycle::create_fast_cycle()),
+        "vampiric_touch" => Some(vampiric_touch::create_vampiric_touch()),
+        "war_cry" => Some(war_cry::create_war_cry()),
+        _ => None,
+    }
 }
 
+/// The effect a skill produces when activated.
+pub type SkillEffect = Effect;
+
 /// An equippable skill with a time-based cooldown.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Skill {
     pub name: String,
     pub description: String,
@@ -122,4 +134,12 @@ mod tests {
         let text = format!("{s}");
         assert!(text.contains("冷却 4 秒"));
     }
+
+    #[test]
+    fn skills_are_created_by_id() {
+        assert_eq!(create("war_cry").unwrap().name, "战吼");
+        assert_eq!(create("vampiric_touch").unwrap().name, "吸血之触");
+        assert!(LEARNABLE_SKILLS.iter().all(|id| create(id).is_some()));
+        assert!(create("fireball").is_none());
+    }
 }
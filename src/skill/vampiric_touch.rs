@@ -5,7 +5,7 @@ pub fn create_vampiric_touch() -> Skill {
     Skill::new(
         "吸血之触",
         "对敌方造成 1 点伤害，同时恢复 1 点生命值",
-        SkillEffect::DamageAndHeal { damage: 1, heal: 1 },
+        SkillEffect::Composite(vec![SkillEffect::Damage(1), SkillEffect::Heal(1)]),
         18_000,
     )
 }
@@ -20,9 +20,9 @@ mod tests {
         assert_eq!(s.name, "吸血之触");
         assert_eq!(s.cooldown_ms, 18_000);
         assert!(s.is_ready());
-        assert!(matches!(
+        assert_eq!(
             s.effect,
-            SkillEffect::DamageAndHeal { damage: 1, heal: 1 }
-        ));
+            SkillEffect::Composite(vec![SkillEffect::Damage(1), SkillEffect::Heal(1)])
+        );
     }
 }
@@ -2,7 +2,7 @@ use super::{Skill, SkillEffect};
 
 /// Creates the "战吼" skill: gain 2 shield. Cooldown 12s.
 pub fn create_war_cry() -> Skill {
-    Skill::new("战吼", "获得 2 点护盾", SkillEffect::GainShield(2), 12_000)
+    Skill::new("战吼", "获得 2 点护盾", SkillEffect::Shield(2), 12_000)
 }
 
 #[cfg(test)]
@@ -15,6 +15,6 @@ mod tests {
         assert_eq!(s.name, "战吼");
         assert_eq!(s.cooldown_ms, 12_000);
         assert!(s.is_ready());
-        assert!(matches!(s.effect, SkillEffect::GainShield(2)));
+        assert!(matches!(s.effect, SkillEffect::Shield(2)));
     }
 }